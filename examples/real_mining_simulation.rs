@@ -2,7 +2,8 @@
 //!
 //! 模拟真实的比特币挖矿环境和工作流程
 
-use cgminer_cpu_btc_core::{SoftwareDevice, DeviceConfig};
+use cgminer_cpu_btc_core::{SoftwareDevice, DeviceConfig, ProfitabilityConfig};
+use cgminer_cpu_btc_core::profitability;
 use cgminer_core::{MiningDevice, Work, DeviceInfo};
 use std::sync::Arc;
 use std::time::Duration;
@@ -172,14 +173,12 @@ fn simulate_hardware_changes(info: &DeviceInfo) {
     }
 }
 
-/// 计算预估收益
-fn calculate_estimated_earnings(hashrate: f64, _difficulty: u64) -> f64 {
-    // 简化的收益计算（实际计算会更复杂）
-    let btc_price = 45000.0; // 假设BTC价格
-    let network_hashrate = 400_000_000_000_000_000.0; // 400 EH/s
-    let block_reward = 6.25; // BTC
-    let blocks_per_day = 144.0; // 平均每天144个区块
-
-    let daily_btc = (hashrate / network_hashrate) * block_reward * blocks_per_day;
-    daily_btc * btc_price
+/// 计算预估收益（使用库的收益估算）
+fn calculate_estimated_earnings(hashrate: f64, difficulty: u64) -> f64 {
+    let config = ProfitabilityConfig {
+        btc_price: 45000.0, // 假设BTC价格
+        ..Default::default()
+    };
+    profitability::estimate(&config, hashrate, None, difficulty as f64)
+        .map_or(0.0, |estimate| estimate.revenue_per_day)
 }
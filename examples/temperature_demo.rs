@@ -19,7 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     custom_params.insert("device_count".to_string(), serde_json::Value::Number(4.into()));
     custom_params.insert("min_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(1_000_000_000.0).unwrap()));
     custom_params.insert("max_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(6_000_000_000.0).unwrap()));
-    custom_params.insert("temperature_limit".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(75.0).unwrap()));
+    custom_params.insert("temperature".to_string(), serde_json::json!({ "critical_threshold": 75.0 }));
 
     let config = CoreConfig {
         name: "TemperatureDemo".to_string(),
@@ -1,10 +1,9 @@
 use cgminer_cpu_btc_core::{
-    SoftwareDevice,
+    SoftwareDevice, ResultBuffer, ResultBufferConfig,
     concurrent_optimization::LockFreeWorkQueue
 };
 use cgminer_core::{Work, DeviceInfo, DeviceConfig, MiningDevice};
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -68,7 +67,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ).await?;
 
     // 设置结果通道
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let result_buffer = ResultBuffer::new(ResultBufferConfig::default());
+    let (tx, mut rx) = result_buffer.channel();
     device_with_channel.set_result_sender(tx);
 
     device_with_channel.initialize(DeviceConfig::default()).await?;
@@ -0,0 +1,162 @@
+//! # cgroup v2 CPU限额模块
+//!
+//! 在容器中运行时，`num_cpus` 和 `core_affinity` 看到的是宿主机的CPU，
+//! 而容器实际能用的CPU由cgroup v2的 `cpu.max`（带宽配额）和
+//! `cpuset.cpus.effective`（可用CPU集合）决定。本模块读取这两个限制，
+//! 供核心推导设备数量和每个设备的占空比。
+//!
+//! ## 🚀 数据来源
+//!
+//! | 路径 | 内容 |
+//! |------|------|
+//! | `/proc/self/cgroup` | 进程所在的cgroup (`0::/path`) |
+//! | `<root>/<path>/cpu.max` | `配额 周期` 或 `max 周期` (微秒) |
+//! | `<root>/<path>/cpuset.cpus.effective` | 可用CPU列表 |
+//!
+//! `cpu.max` 沿cgroup路径逐级向上检查，取最严格的配额。
+//!
+//! ## 🎯 CPU预算
+//!
+//! - 设备数量不超过 `ceil(配额)` 和有效CPU数量
+//! - 配额不是整数时（例如 `150000 100000` = 1.5个CPU），
+//!   每个设备按 `配额 / 设备数` 的占空比运行，避免周期内被CFS限流
+
+use crate::topology::parse_cpu_list;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 默认的cgroup v2挂载点
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// 默认的进程cgroup文件
+pub const DEFAULT_PROC_CGROUP: &str = "/proc/self/cgroup";
+
+/// `cpu.max` 带宽配额
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CpuQuota {
+    /// 每个周期可用的CPU时间 (微秒)
+    pub quota_us: u64,
+    /// 周期 (微秒)
+    pub period_us: u64,
+}
+
+impl CpuQuota {
+    /// 配额折合的CPU数量
+    pub fn cpus(&self) -> f64 {
+        self.quota_us as f64 / self.period_us as f64
+    }
+}
+
+/// 进程所在cgroup的CPU限制
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CgroupLimits {
+    /// cgroup路径（相对于挂载点）
+    pub path: String,
+    /// 最严格的带宽配额，`None` 表示不限制
+    pub quota: Option<CpuQuota>,
+    /// 有效CPU集合，`None` 表示未启用cpuset控制器
+    pub effective_cpus: Option<Vec<usize>>,
+}
+
+/// 解析 `cpu.max`，`max` 表示不限制
+pub fn parse_cpu_max(content: &str) -> Option<CpuQuota> {
+    let mut fields = content.split_whitespace();
+    let quota = fields.next()?;
+    let period_us: u64 = fields.next().and_then(|period| period.parse().ok()).unwrap_or(100_000);
+    if quota == "max" || period_us == 0 {
+        return None;
+    }
+    Some(CpuQuota { quota_us: quota.parse().ok()?, period_us })
+}
+
+/// 解析 `/proc/self/cgroup`，返回cgroup v2路径
+pub fn parse_proc_cgroup(content: &str) -> Option<String> {
+    content.lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| path.trim().to_string())
+}
+
+impl CgroupLimits {
+    /// 检测当前进程的cgroup v2限制（仅Linux）
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            Self::from_fs(DEFAULT_CGROUP_ROOT, DEFAULT_PROC_CGROUP).ok()
+        } else {
+            None
+        }
+    }
+
+    /// 从指定的cgroup挂载点和进程cgroup文件读取限制（测试时可指向fixture目录）
+    pub fn from_fs(root: impl AsRef<Path>, proc_cgroup: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref();
+        let path = parse_proc_cgroup(&fs::read_to_string(proc_cgroup)?).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "进程不在cgroup v2层级中")
+        })?;
+        if !root.join("cgroup.controllers").exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} 不是cgroup v2挂载点", root.display()),
+            ));
+        }
+
+        // 从叶子到根逐级检查
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        let mut dir = root.join(path.trim_start_matches('/'));
+        loop {
+            dirs.push(dir.clone());
+            if dir == root || !dir.pop() || !dir.starts_with(root) {
+                break;
+            }
+        }
+
+        let quota = dirs.iter()
+            .filter_map(|dir| fs::read_to_string(dir.join("cpu.max")).ok())
+            .filter_map(|content| parse_cpu_max(&content))
+            .min_by(|a, b| a.cpus().total_cmp(&b.cpus()));
+
+        let effective_cpus = dirs.iter()
+            .find_map(|dir| fs::read_to_string(dir.join("cpuset.cpus.effective")).ok())
+            .map(|list| parse_cpu_list(list.trim()))
+            .filter(|cpus| !cpus.is_empty());
+
+        Ok(Self { path, quota, effective_cpus })
+    }
+
+    /// 配额折合的CPU数量，`None` 表示不限制
+    pub fn cpu_budget(&self) -> Option<f64> {
+        self.quota.map(|quota| quota.cpus())
+    }
+
+    /// cgroup允许同时运行的CPU数量上限，`None` 表示不限制
+    pub fn cpu_limit(&self) -> Option<u32> {
+        let from_quota = self.cpu_budget().map(|cpus| cpus.ceil().max(1.0) as u32);
+        let from_cpuset = self.effective_cpus.as_ref().map(|cpus| cpus.len() as u32);
+        match (from_quota, from_cpuset) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// 是否存在任何CPU限制
+    pub fn is_limited(&self) -> bool {
+        self.cpu_limit().is_some()
+    }
+
+    /// 只保留cpuset允许的CPU
+    pub fn restrict(&self, cpus: &[usize]) -> Vec<usize> {
+        match self.effective_cpus {
+            Some(ref allowed) => cpus.iter().copied().filter(|cpu| allowed.contains(cpu)).collect(),
+            None => cpus.to_vec(),
+        }
+    }
+
+    /// `device_count` 个设备共享配额时每个设备的占空比 (0.0-1.0]
+    pub fn duty_cycle(&self, device_count: u32) -> f64 {
+        match self.cpu_budget() {
+            Some(budget) if device_count > 0 => (budget / device_count as f64).clamp(0.01, 1.0),
+            _ => 1.0,
+        }
+    }
+}
@@ -246,6 +246,29 @@ impl LockFreeWorkQueue {
         }
     }
 
+    /// 在当前线程上分配一个新的队列，移入待处理的工作和完成的结果并保留统计计数
+    ///
+    /// 设备的挖矿工作线程绑定CPU之后调用，新队列按first-touch策略位于绑定节点的本地内存。
+    pub fn relocate(&self) -> Self {
+        let queue = Self::new(self.max_queue_size);
+        while let Some(work) = self.pending_work.pop() {
+            let _ = queue.pending_work.push(work);
+        }
+        while let Some(result) = self.completed_work.pop() {
+            queue.completed_work.push(result);
+        }
+        for (to, from) in [
+            (&queue.active_work_count, &self.active_work_count),
+            (&queue.total_enqueued, &self.total_enqueued),
+            (&queue.total_dequeued, &self.total_dequeued),
+            (&queue.queue_full_count, &self.queue_full_count),
+            (&queue.current_work_version, &self.current_work_version),
+        ] {
+            to.store(from.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        queue
+    }
+
     /// 检查队列是否接近满载
     pub fn is_nearly_full(&self, threshold: f32) -> bool {
         let current_size = self.pending_work.len();
@@ -462,6 +485,22 @@ mod tests {
         assert_eq!(stats.queue_full_count, 1);
     }
 
+    #[test]
+    fn test_relocate_keeps_pending_work_and_stats() {
+        let queue = LockFreeWorkQueue::new(3);
+        let work = Arc::new(Work::new("test_job_1".to_string(), [0u8; 32], [0u8; 80], 1.0));
+        assert!(queue.enqueue_work(work.clone()).is_ok());
+        queue.update_work_version();
+
+        let relocated = thread::spawn(move || queue.relocate()).join().unwrap();
+        let stats = relocated.get_stats();
+        assert_eq!(stats.pending_count, 1);
+        assert_eq!(stats.total_enqueued, 1);
+        assert_eq!(stats.current_version, 1);
+        assert_eq!(stats.max_queue_size, 3);
+        assert_eq!(relocated.dequeue_work().map(|work| work.id), Some(work.id));
+    }
+
     #[tokio::test]
     async fn test_atomic_stats_manager() {
         let mut manager = AtomicStatsManager::new(100);
@@ -0,0 +1,852 @@
+//! # CPU核心配置模块
+//!
+//! 本模块把原先分散在 `custom_params` 中的无类型参数统一为强类型的
+//! [`CpuCoreConfig`]，提供默认值、单位说明和唯一的验证入口。
+//! 工厂和核心的 `validate_config` 都通过 [`validate_core_config`] 验证配置。
+//!
+//! ## 🚀 配置来源
+//!
+//! - `CoreConfig::custom_params` → [`CpuCoreConfig::from_core_config`]
+//! - TOML 文件/字符串 → [`CpuCoreConfig::from_toml_str`]
+//! - JSON 文件/字符串 → [`CpuCoreConfig::from_json_str`]
+//! - 按扩展名自动识别 → [`CpuCoreConfig::from_file`]
+//!
+//! 以上所有来源都在解析后统一应用环境变量覆盖再验证：
+//! 环境变量 `CGMINER_SOFTWARE_DEVICE_COUNT` 会覆盖 `device_count`。
+//! 未知的参数名（包括各个子表中的参数名）会被拒绝，避免拼写错误的参数被静默忽略。
+//!
+//! ## ⚙️ 配置参数说明
+//!
+//! | 参数名 | 类型 | 默认值 | 说明 |
+//! |--------|------|--------|------|
+//! | `device_count` | u32 | 4 | 虚拟设备数量 (1-1000，运行时限制为CPU核心数) |
+//! | `min_hashrate` | f64 | 3e7 | 最小设备算力 (H/s) |
+//! | `max_hashrate` | f64 | 4e7 | 最大设备算力 (H/s) |
+//! | `error_rate` | f64 | 0.01 | 模拟错误率 (0.0-1.0) |
+//! | `batch_size` | u32 | 1000000 | 每批次计算的nonce数量 |
+//! | `work_timeout_ms` | u64 | 5000 | 工作超时 (毫秒) |
+//! | `result_buffer_capacity` | usize | 1024 | 结果缓冲容量 (结果个数) |
+//! | `result_overflow_policy` | string | `drop_oldest` | 结果缓冲溢出策略 |
+//! | `affinity` | table | 启用, `intelligent` | CPU绑定配置，`excluded_cores`/`headroom` 预留核心 |
+//! | `temperature` | table | 75°C / 85°C | 温度阈值 (摄氏度) |
+//! | `throttling` | table | 不限制 | 单设备算力上限 (H/s) |
+//! | `power` | table | 启用 | RAPL功耗测量 (powercap根目录)，`cap` 功率上限 |
+//! | `profitability` | table | 禁用 | 收益估算 (电价/币价/难度)，`auto_pause` 亏损时暂停 |
+//! | `idle` | table | 禁用 | 空闲检测：其他程序CPU占用超过阈值时暂停 |
+//! | `schedule` | table | 禁用 | 挖矿时间表：按星期和时刻停止或降速 |
+//! | `priority` | table | 不修改 | 挖矿线程调度策略 (`batch`/`idle`)、nice值和I/O优先级 (Linux) |
+//! | `tuning` | table | 禁用 | 批次大小自动调优：响应延迟上限内算力最高，结果按CPU和线程数缓存到 `cache_path` |
+//! | `backend` | string | `auto` | 哈希后端 |
+//! | `benchmark` | table | 禁用 | 创建核心时测量各哈希实现并选择最快的，结果按CPU缓存到 `cache_path` |
+//! | `stats_journal` | table | 禁用 | 累计统计日志：周期性和停止时写入 `path`，初始化时读取，重启后保留累计总数 |
+//!
+//! ## 🔄 TOML 示例
+//!
+//! ```toml
+//! device_count = 8
+//! min_hashrate = 30000000.0
+//! max_hashrate = 40000000.0
+//! batch_size = 100000
+//!
+//! [affinity]
+//! strategy = "manual"
+//! manual_mapping = [  # core 为逻辑CPU编号
+//!     { device_id = 1000, core = 2 },
+//!     { device_id = 1001, core = 3 },
+//! ]
+//!
+//! [affinity.load_balancer]  # 仅 load_balanced 策略使用
+//! sample_interval_ms = 2000
+//! high_watermark = 0.5
+//! low_watermark = 0.2
+//! min_dwell_ms = 30000
+//!
+//! [temperature]
+//! warning_threshold = 70.0
+//! critical_threshold = 80.0
+//! # command = ["/usr/local/bin/osx-cpu-temp"]  # 可选：用外部命令代替sysfs
+//!
+//! [temperature.governor]  # 闭环温度调节
+//! kp = 0.05
+//! ki = 0.01
+//! resume_hysteresis = 5.0
+//!
+//! [throttling]
+//! max_device_hashrate = 20000000.0
+//!
+//! [power]  # RAPL功耗测量
+//! powercap_root = "/sys/class/powercap"
+//! interval_ms = 1000             # 采样周期
+//!
+//! [power.cap]  # 功率上限：调整活跃线程数和占空比
+//! budget_watts = 65.0
+//! model = { idle_watts = 15.0, watts_per_thread = 9.0 }  # 可选：没有RAPL时使用
+//!
+//! [profitability]  # 收益估算
+//! enabled = true
+//! electricity_price = 0.12       # 每kWh
+//! btc_price = 60000.0
+//! network_difficulty = 0.0       # 0表示从工作区块头推算
+//! watts = 65.0                   # 可选：没有RAPL时使用
+//! auto_pause = true
+//!
+//! [idle]  # 只在机器空闲时挖矿
+//! enabled = true
+//! max_foreign_load = 0.2         # 其他程序的CPU占用 (0.0-1.0)
+//! quiet_period_ms = 30000        # 安静多久后恢复
+//!
+//! [schedule]  # 挖矿时间表：只在夜间低谷电价时挖矿
+//! enabled = true
+//! timezone = "+08:00"
+//! default_duty_cycle = 0.0
+//! windows = [{ name = "夜间", start = "23:00", end = "07:00" }]
+//!
+//! [priority]  # 挖矿线程让位于交互式程序 (Linux)
+//! policy = "idle"
+//! nice = 19
+//! ioprio = { class = "idle" }
+//!
+//! [tuning]  # 批次大小自动调优
+//! enabled = true
+//! max_latency_ms = 50            # 响应停止和新工作的最大延迟
+//! cache_path = "/var/lib/cgminer/batch-tuning.json"
+//!
+//! [benchmark]  # 启动时选择最快的哈希实现
+//! enabled = true
+//! cache_path = "/var/lib/cgminer/hash-benchmark.json"
+//!
+//! [stats_journal]  # 跨重启的累计统计
+//! enabled = true
+//! path = "/var/lib/cgminer/stats.json"
+//! ```
+
+use crate::cpu_affinity::{self, CoreReservation, CpuAffinityConfig, CpuAffinityStrategy};
+use crate::hashing::BenchmarkConfig;
+use crate::idle::IdleConfig;
+use crate::load_balancer::LoadBalancerConfig;
+use crate::power::PowerConfig;
+use crate::priority::PriorityConfig;
+use crate::profitability::ProfitabilityConfig;
+use crate::result_buffer::{ResultBufferConfig, OverflowPolicy, DEFAULT_RESULT_BUFFER_CAPACITY};
+use crate::schedule::{Schedule, ScheduleConfig};
+use crate::stats_journal::StatsJournalConfig;
+use crate::temperature::TemperatureConfig;
+use crate::tuning::BatchTuningConfig;
+use cgminer_core::{CoreConfig, CoreError, DeviceConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{info, warn};
+
+/// 设备数量上限
+pub const MAX_DEVICE_COUNT: u32 = 1000;
+
+/// 覆盖设备数量的环境变量
+pub const DEVICE_COUNT_ENV: &str = "CGMINER_SOFTWARE_DEVICE_COUNT";
+
+/// CPU挖矿核心配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CpuCoreConfig {
+    /// 虚拟设备数量
+    pub device_count: u32,
+    /// 最小设备算力 (H/s)
+    pub min_hashrate: f64,
+    /// 最大设备算力 (H/s)
+    pub max_hashrate: f64,
+    /// 模拟错误率 (0.0-1.0)
+    pub error_rate: f64,
+    /// 每批次计算的nonce数量
+    pub batch_size: u32,
+    /// 工作超时 (毫秒)
+    pub work_timeout_ms: u64,
+    /// 结果缓冲容量 (结果个数)
+    pub result_buffer_capacity: usize,
+    /// 结果缓冲溢出策略
+    pub result_overflow_policy: OverflowPolicy,
+    /// CPU绑定配置
+    pub affinity: AffinitySettings,
+    /// 温度阈值配置
+    pub temperature: TemperatureConfig,
+    /// 算力限制配置
+    pub throttling: ThrottleSettings,
+    /// 功耗测量配置
+    pub power: PowerConfig,
+    /// 收益估算配置
+    pub profitability: ProfitabilityConfig,
+    /// 空闲检测配置
+    pub idle: IdleConfig,
+    /// 挖矿时间表配置
+    pub schedule: ScheduleConfig,
+    /// 挖矿线程优先级配置
+    pub priority: PriorityConfig,
+    /// 批次大小调优配置
+    pub tuning: BatchTuningConfig,
+    /// 哈希后端
+    pub backend: HashBackend,
+    /// 哈希实现基准测试配置
+    pub benchmark: BenchmarkConfig,
+    /// 累计统计日志配置
+    pub stats_journal: StatsJournalConfig,
+}
+
+impl Default for CpuCoreConfig {
+    fn default() -> Self {
+        Self {
+            device_count: 4,
+            min_hashrate: 30_000_000.0, // 30 MH/s
+            max_hashrate: 40_000_000.0, // 40 MH/s
+            error_rate: 0.01,           // 1% 错误率
+            batch_size: 1_000_000,      // 100万，提高实际算力
+            work_timeout_ms: 5000,
+            result_buffer_capacity: DEFAULT_RESULT_BUFFER_CAPACITY,
+            result_overflow_policy: OverflowPolicy::default(),
+            affinity: AffinitySettings::default(),
+            temperature: TemperatureConfig::default(),
+            throttling: ThrottleSettings::default(),
+            power: PowerConfig::default(),
+            profitability: ProfitabilityConfig::default(),
+            idle: IdleConfig::default(),
+            schedule: ScheduleConfig::default(),
+            priority: PriorityConfig::default(),
+            tuning: BatchTuningConfig::default(),
+            backend: HashBackend::default(),
+            benchmark: BenchmarkConfig::default(),
+            stats_journal: StatsJournalConfig::default(),
+        }
+    }
+}
+
+/// CPU绑定策略名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AffinityStrategyKind {
+    /// 轮询分配
+    RoundRobin,
+    /// 手动指定（使用 `manual_mapping`）
+    Manual,
+    /// 性能核心优先
+    PerformanceFirst,
+    /// 只使用物理核心
+    PhysicalCoresOnly,
+    /// 智能分配
+    #[default]
+    Intelligent,
+    /// 负载均衡
+    LoadBalanced,
+}
+
+/// 手动绑定项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManualCoreBinding {
+    /// 设备ID
+    pub device_id: u32,
+    /// 逻辑CPU编号（与 `excluded_cores` 相同的编号，不是可用核心的索引）
+    pub core: usize,
+}
+
+/// CPU绑定配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AffinitySettings {
+    /// 是否启用CPU绑定
+    pub enabled: bool,
+    /// 绑定策略
+    pub strategy: AffinityStrategyKind,
+    /// 手动绑定表（仅 `manual` 策略使用）
+    pub manual_mapping: Vec<ManualCoreBinding>,
+    /// 后台负载均衡参数（仅 `load_balanced` 策略使用）
+    pub load_balancer: LoadBalancerConfig,
+    /// 永不使用的逻辑CPU编号
+    pub excluded_cores: Vec<usize>,
+    /// 额外留空的核心数量
+    pub headroom: usize,
+}
+
+impl Default for AffinitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strategy: AffinityStrategyKind::default(),
+            manual_mapping: Vec::new(),
+            load_balancer: LoadBalancerConfig::default(),
+            excluded_cores: Vec::new(),
+            headroom: 0,
+        }
+    }
+}
+
+impl AffinitySettings {
+    /// 转换为运行时绑定策略
+    pub fn to_strategy(&self) -> CpuAffinityStrategy {
+        match self.strategy {
+            AffinityStrategyKind::RoundRobin => CpuAffinityStrategy::RoundRobin,
+            AffinityStrategyKind::Manual => CpuAffinityStrategy::Manual(self.manual_map()),
+            AffinityStrategyKind::PerformanceFirst => CpuAffinityStrategy::PerformanceFirst,
+            AffinityStrategyKind::PhysicalCoresOnly => CpuAffinityStrategy::PhysicalCoresOnly,
+            AffinityStrategyKind::Intelligent => CpuAffinityStrategy::Intelligent,
+            AffinityStrategyKind::LoadBalanced => CpuAffinityStrategy::LoadBalanced,
+        }
+    }
+
+    /// 转换为CPU绑定配置
+    pub fn to_cpu_affinity_config(&self) -> CpuAffinityConfig {
+        CpuAffinityConfig {
+            enabled: self.enabled,
+            strategy: self.to_strategy(),
+            manual_mapping: match self.strategy {
+                AffinityStrategyKind::Manual => Some(self.manual_map()),
+                _ => None,
+            },
+            reservation: self.reservation(),
+        }
+    }
+
+    /// 预留核心配置
+    pub fn reservation(&self) -> CoreReservation {
+        CoreReservation {
+            excluded_cores: self.excluded_cores.clone(),
+            headroom: self.headroom,
+        }
+    }
+
+    /// 手动绑定表（设备ID → 逻辑CPU编号）
+    pub fn manual_map(&self) -> HashMap<u32, usize> {
+        self.manual_mapping.iter().map(|binding| (binding.device_id, binding.core)).collect()
+    }
+}
+
+/// 算力限制配置
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThrottleSettings {
+    /// 单设备算力上限 (H/s)，0表示不限制
+    pub max_device_hashrate: f64,
+}
+
+impl ThrottleSettings {
+    /// 对设备目标算力应用上限
+    pub fn apply(&self, hashrate: f64) -> f64 {
+        if self.max_device_hashrate > 0.0 {
+            hashrate.min(self.max_device_hashrate)
+        } else {
+            hashrate
+        }
+    }
+}
+
+/// 哈希后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashBackend {
+    /// 运行时自动选择（sha2 会自动使用可用的硬件加速）
+    #[default]
+    Auto,
+    /// 要求CPU支持SHA硬件扩展，不支持时配置验证失败
+    HardwareAccelerated,
+}
+
+impl HashBackend {
+    /// 当前CPU是否支持该后端
+    pub fn is_supported(&self) -> bool {
+        match self {
+            HashBackend::Auto => true,
+            HashBackend::HardwareAccelerated => cpu_has_sha_extensions(),
+        }
+    }
+}
+
+/// 检测CPU是否支持SHA硬件扩展
+fn cpu_has_sha_extensions() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        std::arch::is_x86_feature_detected!("sha")
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        std::arch::is_aarch64_feature_detected!("sha2")
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        false
+    }
+}
+
+impl CpuCoreConfig {
+    /// 从 `CoreConfig::custom_params` 解析并验证配置
+    ///
+    /// 未出现的参数使用默认值，环境变量 `CGMINER_SOFTWARE_DEVICE_COUNT` 覆盖设备数量。
+    pub fn from_core_config(config: &CoreConfig) -> Result<Self, CoreError> {
+        let params: serde_json::Map<String, serde_json::Value> = config.custom_params
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let cpu_config: Self = serde_json::from_value(serde_json::Value::Object(params))
+            .map_err(|e| CoreError::config(format!("custom_params 解析失败: {}", e)))?;
+        cpu_config.finish()
+    }
+
+    /// 从TOML字符串解析并验证配置
+    pub fn from_toml_str(content: &str) -> Result<Self, CoreError> {
+        let cpu_config: Self = toml::from_str(content)
+            .map_err(|e| CoreError::config(format!("TOML配置解析失败: {}", e)))?;
+        cpu_config.finish()
+    }
+
+    /// 从JSON字符串解析并验证配置
+    pub fn from_json_str(content: &str) -> Result<Self, CoreError> {
+        let cpu_config: Self = serde_json::from_str(content)
+            .map_err(|e| CoreError::config(format!("JSON配置解析失败: {}", e)))?;
+        cpu_config.finish()
+    }
+
+    /// 所有配置来源解析后的共同步骤：应用环境变量覆盖，然后验证
+    fn finish(mut self) -> Result<Self, CoreError> {
+        self.apply_env_overrides();
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// 从文件加载配置，按扩展名 (`.toml` / `.json`) 选择格式
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CoreError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CoreError::config(format!("读取配置文件 {} 失败: {}", path.display(), e)))?;
+
+        let result = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("json") => Self::from_json_str(&content),
+            _ => Err(CoreError::config(format!(
+                "无法识别配置文件格式: {}（支持 .toml 和 .json）", path.display()
+            ))),
+        };
+
+        result.map_err(|e| CoreError::config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// 生成以本配置为参数的 `CoreConfig`
+    pub fn to_core_config(&self, name: impl Into<String>) -> CoreConfig {
+        CoreConfig {
+            name: name.into(),
+            enabled: true,
+            devices: default_device_configs(self.device_count),
+            custom_params: self.to_custom_params(),
+        }
+    }
+
+    /// 转换为 `custom_params`
+    pub fn to_custom_params(&self) -> HashMap<String, serde_json::Value> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        }
+    }
+
+    /// 应用环境变量覆盖
+    pub(crate) fn apply_env_overrides(&mut self) {
+        if let Ok(count_str) = std::env::var(DEVICE_COUNT_ENV) {
+            match count_str.parse::<u32>() {
+                Ok(count) if count > 0 && count <= MAX_DEVICE_COUNT => {
+                    info!("从环境变量读取优化CPU设备数量: {}", count);
+                    self.device_count = count;
+                }
+                _ => warn!("环境变量 {} 的值 '{}' 无效，使用配置文件值", DEVICE_COUNT_ENV, count_str),
+            }
+        }
+    }
+
+    /// 结果缓冲配置
+    pub fn result_buffer(&self) -> ResultBufferConfig {
+        ResultBufferConfig {
+            capacity: self.result_buffer_capacity,
+            overflow_policy: self.result_overflow_policy,
+        }
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> Result<(), CoreError> {
+        if self.device_count == 0 {
+            return Err(CoreError::config("软算法设备数量不能为0"));
+        }
+        if self.device_count > MAX_DEVICE_COUNT {
+            return Err(CoreError::config(format!("软算法设备数量不能超过{}", MAX_DEVICE_COUNT)));
+        }
+        if self.device_count > 32 {
+            warn!("配置了 {} 个软算法设备，这可能会消耗大量系统资源", self.device_count);
+        }
+
+        if !self.min_hashrate.is_finite() || self.min_hashrate <= 0.0 {
+            return Err(CoreError::config("最小算力必须大于0"));
+        }
+        if !self.max_hashrate.is_finite() || self.max_hashrate <= 0.0 {
+            return Err(CoreError::config("最大算力必须大于0"));
+        }
+        if self.min_hashrate >= self.max_hashrate {
+            return Err(CoreError::config("最小算力必须小于最大算力"));
+        }
+
+        if !(0.0..=1.0).contains(&self.error_rate) {
+            return Err(CoreError::config("错误率必须在0.0到1.0之间"));
+        }
+        if self.batch_size == 0 {
+            return Err(CoreError::config("batch_size 必须大于0"));
+        }
+        if self.work_timeout_ms == 0 {
+            return Err(CoreError::config("work_timeout_ms 必须大于0"));
+        }
+        if self.result_buffer_capacity == 0 {
+            return Err(CoreError::config("result_buffer_capacity 必须是正整数"));
+        }
+
+        if self.affinity.strategy == AffinityStrategyKind::Manual {
+            if self.affinity.manual_mapping.is_empty() {
+                return Err(CoreError::config("manual 绑定策略需要提供 affinity.manual_mapping"));
+            }
+            let usable = cpu_affinity::usable_cpus(&self.affinity.reservation());
+            for binding in &self.affinity.manual_mapping {
+                if self.affinity.excluded_cores.contains(&binding.core) {
+                    return Err(CoreError::config(format!(
+                        "affinity.manual_mapping: 设备 {} 指定的CPU {} 在 excluded_cores 中", binding.device_id, binding.core
+                    )));
+                }
+                if !usable.contains(&binding.core) {
+                    return Err(CoreError::config(format!(
+                        "affinity.manual_mapping: 设备 {} 指定的CPU {} 不可用（超出范围或被预留），可用CPU: {:?}",
+                        binding.device_id, binding.core, usable
+                    )));
+                }
+            }
+        }
+
+        let balancer = &self.affinity.load_balancer;
+        if balancer.sample_interval_ms == 0 {
+            return Err(CoreError::config("affinity.load_balancer.sample_interval_ms 必须大于0"));
+        }
+        if !(0.0..=1.0).contains(&balancer.low_watermark) || !(0.0..=1.0).contains(&balancer.high_watermark) {
+            return Err(CoreError::config("affinity.load_balancer 负载阈值必须在0.0到1.0之间"));
+        }
+        if balancer.low_watermark >= balancer.high_watermark {
+            return Err(CoreError::config("affinity.load_balancer.low_watermark 必须小于 high_watermark"));
+        }
+
+        let temperature = &self.temperature;
+        if temperature.warning_threshold <= 0.0 || temperature.critical_threshold <= 0.0 {
+            return Err(CoreError::config("温度阈值必须大于0"));
+        }
+        if temperature.warning_threshold >= temperature.critical_threshold {
+            return Err(CoreError::config("温度警告阈值必须小于危险阈值"));
+        }
+        if let Some(ref command) = temperature.command {
+            if command.first().map_or(true, |program| program.trim().is_empty()) {
+                return Err(CoreError::config("temperature.command 不能为空"));
+            }
+            if temperature.command_timeout_ms == 0 {
+                return Err(CoreError::config("temperature.command_timeout_ms 必须大于0"));
+            }
+        }
+        let governor = &temperature.governor;
+        if governor.interval_ms == 0 {
+            return Err(CoreError::config("temperature.governor.interval_ms 必须大于0"));
+        }
+        if governor.kp < 0.0 || governor.ki < 0.0 || governor.resume_hysteresis < 0.0 {
+            return Err(CoreError::config("temperature.governor 的系数和滞回不能为负数"));
+        }
+        if !(0.0..=1.0).contains(&governor.min_duty_cycle) {
+            return Err(CoreError::config("temperature.governor.min_duty_cycle 必须在0.0到1.0之间"));
+        }
+
+        if self.power.interval_ms == 0 {
+            return Err(CoreError::config("power.interval_ms 必须大于0"));
+        }
+        if let Some(ref cap) = self.power.cap {
+            if !(cap.budget_watts.is_finite() && cap.budget_watts > 0.0) {
+                return Err(CoreError::config("power.cap.budget_watts 必须大于0"));
+            }
+            if cap.interval_ms == 0 {
+                return Err(CoreError::config("power.cap.interval_ms 必须大于0"));
+            }
+            if !(cap.ramp_gain > 0.0 && cap.ramp_gain <= 1.0) {
+                return Err(CoreError::config("power.cap.ramp_gain 必须在0.0到1.0之间"));
+            }
+            if !(0.0..1.0).contains(&cap.headroom) || cap.min_units < 0.0 {
+                return Err(CoreError::config("power.cap.headroom 必须在0.0到1.0之间，min_units 不能为负数"));
+            }
+            if let Some(model) = cap.model {
+                if model.idle_watts < 0.0 || model.watts_per_thread <= 0.0 {
+                    return Err(CoreError::config("power.cap.model 的空闲功率不能为负数，每线程功率必须大于0"));
+                }
+            }
+        }
+
+        let profitability = &self.profitability;
+        if [profitability.electricity_price, profitability.btc_price, profitability.network_difficulty,
+            profitability.block_reward, profitability.fees_per_block, profitability.resume_margin]
+            .iter()
+            .any(|value| !value.is_finite() || *value < 0.0)
+        {
+            return Err(CoreError::config("profitability 的电价、币价、难度、奖励和余量不能为负数"));
+        }
+        if matches!(profitability.watts, Some(watts) if !watts.is_finite() || watts <= 0.0) {
+            return Err(CoreError::config("profitability.watts 必须大于0"));
+        }
+        if profitability.interval_ms == 0 {
+            return Err(CoreError::config("profitability.interval_ms 必须大于0"));
+        }
+
+        if !(self.idle.max_foreign_load > 0.0 && self.idle.max_foreign_load <= 1.0) {
+            return Err(CoreError::config("idle.max_foreign_load 必须在0.0到1.0之间"));
+        }
+        if self.idle.sample_interval_ms == 0 {
+            return Err(CoreError::config("idle.sample_interval_ms 必须大于0"));
+        }
+
+        Schedule::from_config(&self.schedule).map_err(|e| CoreError::config(format!("schedule: {}", e)))?;
+        if self.schedule.interval_ms == 0 {
+            return Err(CoreError::config("schedule.interval_ms 必须大于0"));
+        }
+
+        if matches!(self.priority.nice, Some(nice) if !(-20..=19).contains(&nice)) {
+            return Err(CoreError::config("priority.nice 必须在-20到19之间"));
+        }
+        if matches!(self.priority.ioprio, Some(ioprio) if ioprio.level > 7) {
+            return Err(CoreError::config("priority.ioprio.level 必须在0到7之间"));
+        }
+
+        let tuning = &self.tuning;
+        if tuning.max_latency_ms == 0 {
+            return Err(CoreError::config("tuning.max_latency_ms 必须大于0"));
+        }
+        if tuning.min_batch_size == 0 || tuning.min_batch_size > tuning.max_batch_size {
+            return Err(CoreError::config("tuning.min_batch_size 必须大于0且不超过 max_batch_size"));
+        }
+        if tuning.samples_per_step == 0 {
+            return Err(CoreError::config("tuning.samples_per_step 必须大于0"));
+        }
+        if !tuning.min_gain.is_finite() || tuning.min_gain < 0.0 {
+            return Err(CoreError::config("tuning.min_gain 不能为负数"));
+        }
+
+        if !self.throttling.max_device_hashrate.is_finite() || self.throttling.max_device_hashrate < 0.0 {
+            return Err(CoreError::config("throttling.max_device_hashrate 不能为负数"));
+        }
+
+        if !self.backend.is_supported() {
+            return Err(CoreError::config(format!("当前CPU不支持哈希后端 {:?}", self.backend)));
+        }
+        if self.benchmark.duration_ms == 0 {
+            return Err(CoreError::config("benchmark.duration_ms 必须大于0"));
+        }
+        if self.stats_journal.enabled && self.stats_journal.path.is_none() {
+            return Err(CoreError::config("stats_journal.path 启用统计日志时必须设置"));
+        }
+        if self.stats_journal.interval_ms == 0 {
+            return Err(CoreError::config("stats_journal.interval_ms 必须大于0"));
+        }
+
+        Ok(())
+    }
+}
+
+/// 原子地写入文件：先写同目录的临时文件再重命名，避免中途失败留下不完整的文件
+///
+/// 重命名前把临时文件刷到磁盘，重命名后同步目录，断电后不会出现重命名已生效但内容为空的文件。
+pub(crate) fn write_file_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+    if let Some(parent) = parent {
+        std::fs::create_dir_all(parent)?;
+    }
+    // 临时文件名包含完整文件名、进程ID和序号：同时写同一个文件，或同一目录下同名不同扩展名的文件，
+    // 都不会共用一个临时文件
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_name = format!(".{}.{}.{}.tmp", file_name, std::process::id(), TEMP_COUNTER.fetch_add(1, Ordering::Relaxed));
+    let temp_path = path.with_file_name(temp_name);
+    let written = std::fs::File::create(&temp_path).and_then(|mut file| {
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&temp_path, path)) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    // 目录同步只在Unix上可用（Windows无法以只读方式打开目录）
+    #[cfg(unix)]
+    std::fs::File::open(parent.unwrap_or_else(|| Path::new(".")))?.sync_all()?;
+    Ok(())
+}
+
+/// 第 `index` 个设备的默认配置（频率和电压随索引递增）
+pub fn default_device_config(index: u32) -> DeviceConfig {
+    DeviceConfig {
+        chain_id: index as u8,
+        enabled: true,
+        frequency: 600 + index * 50,
+        voltage: 900 + index * 20,
+        auto_tune: false,
+        chip_count: 64,
+        temperature_limit: 80.0,
+        fan_speed: Some(50 + index * 5),
+    }
+}
+
+/// 生成 `count` 个设备的默认配置
+pub fn default_device_configs(count: u32) -> Vec<DeviceConfig> {
+    (0..count).map(default_device_config).collect()
+}
+
+/// 验证完整的核心配置（核心名称、设备配置和自定义参数），返回解析后的CPU核心配置
+pub fn validate_core_config(config: &CoreConfig) -> Result<CpuCoreConfig, CoreError> {
+    if config.name.is_empty() {
+        return Err(CoreError::config("核心名称不能为空"));
+    }
+
+    for (i, device_config) in config.devices.iter().enumerate() {
+        if device_config.frequency == 0 {
+            return Err(CoreError::config(format!("设备 {} 的频率不能为0", i)));
+        }
+        if device_config.voltage == 0 {
+            return Err(CoreError::config(format!("设备 {} 的电压不能为0", i)));
+        }
+        if device_config.temperature_limit <= 0.0 {
+            return Err(CoreError::config(format!("设备 {} 的温度限制必须大于0", i)));
+        }
+        if device_config.chip_count == 0 {
+            return Err(CoreError::config(format!("设备 {} 的芯片数量不能为0", i)));
+        }
+    }
+
+    CpuCoreConfig::from_core_config(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_custom_params() {
+        let mut cpu_config = CpuCoreConfig::default();
+        cpu_config.device_count = 2;
+        cpu_config.affinity.strategy = AffinityStrategyKind::RoundRobin;
+
+        let core_config = CoreConfig {
+            name: "config-test".to_string(),
+            enabled: true,
+            devices: Vec::new(),
+            custom_params: cpu_config.to_custom_params(),
+        };
+
+        let parsed = CpuCoreConfig::from_core_config(&core_config).unwrap();
+        assert_eq!(parsed.affinity, cpu_config.affinity);
+        assert_eq!(parsed.batch_size, cpu_config.batch_size);
+    }
+
+    #[test]
+    fn test_wrong_type_is_reported() {
+        let err = CpuCoreConfig::from_json_str(r#"{ "batch_size": "many" }"#).unwrap_err();
+        assert!(err.to_string().contains("batch_size"), "错误信息应该指出字段: {}", err);
+    }
+
+    #[test]
+    fn test_manual_mapping_rejects_unusable_cpus() {
+        let err = CpuCoreConfig::from_toml_str(r#"
+            [affinity]
+            strategy = "manual"
+            excluded_cores = [0]
+            manual_mapping = [{ device_id = 1000, core = 0 }]
+        "#).unwrap_err();
+        assert!(err.to_string().contains("excluded_cores"), "{}", err);
+
+        let err = CpuCoreConfig::from_toml_str(r#"
+            [affinity]
+            strategy = "manual"
+            manual_mapping = [{ device_id = 1000, core = 100000 }]
+        "#).unwrap_err();
+        assert!(err.to_string().contains("100000"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_field_is_rejected() {
+        let err = CpuCoreConfig::from_toml_str("batch_sise = 1000").unwrap_err();
+        assert!(err.to_string().contains("batch_sise"), "错误信息应该指出未知字段: {}", err);
+
+        let err = CpuCoreConfig::from_toml_str("[affinity]
+strategi = \"manual\"").unwrap_err();
+        assert!(err.to_string().contains("strategi"), "{}", err);
+    }
+
+    #[test]
+    fn test_concurrent_atomic_writes_use_separate_temp_files() {
+        let dir = std::env::temp_dir().join(format!("atomic-write-{}", std::process::id()));
+        let path = dir.join("stats.json");
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || write_file_atomic(&path, &i.to_string().repeat(4096)))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().expect("并发写入都应该成功");
+        }
+        write_file_atomic(&dir.join("stats.toml"), "toml").unwrap();
+
+        // 最终内容是某一次完整的写入，不会混入其他写入，也没有残留的临时文件
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.len(), 4096);
+        assert!(content.chars().all(|c| c == content.chars().next().unwrap()));
+        assert_eq!(std::fs::read_to_string(dir.join("stats.toml")).unwrap(), "toml");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_nested_field_is_rejected() {
+        let cases = [
+            "[stats_journal]\nintervall_ms = 5",
+            "[temperature]\ncritical_treshold = 90.0",
+            "[temperature.governor]\nkpp = 0.1",
+            "[power]\nenabeld = true",
+            "[power.cap]\nwatt = 50.0",
+            "[idle]\nquiet_ms = 100",
+            "[schedule]\ntimezone = \"UTC\"\nwindow = []",
+            "[[schedule.windows]]\nstart = \"08:00\"\nend = \"18:00\"\nbegin = \"08:00\"",
+            "[priority]\nnicee = 5",
+            "[priority.ioprio]\nclass = \"idle\"\nlvl = 3",
+            "[tuning]\nmax_latency = 10",
+            "[benchmark]\nduration = 10",
+            "[profitability]\nbtc_prise = 1.0",
+            "[affinity.load_balancer]\nintervall_ms = 5",
+            "[[affinity.manual_mapping]]\ndevice_id = 1000\ncpu_core = 0",
+        ];
+        for toml in cases {
+            let field = toml.lines().last().unwrap().split(' ').next().unwrap();
+            let err = CpuCoreConfig::from_toml_str(toml).expect_err(toml);
+            assert!(err.to_string().contains(field), "{} 的错误信息应该指出未知字段 {}: {}", toml, field, err);
+        }
+    }
+
+    #[test]
+    fn test_default_hashrate_and_batch_size() {
+        let cpu_config = CpuCoreConfig::default();
+        assert_eq!(cpu_config.min_hashrate, 30_000_000.0);
+        assert_eq!(cpu_config.max_hashrate, 40_000_000.0);
+        assert_eq!(cpu_config.batch_size, 1_000_000);
+    }
+
+    #[test]
+    fn test_manual_mapping_from_toml() {
+        let cpu_config = CpuCoreConfig::from_toml_str(r#"
+            [affinity]
+            strategy = "manual"
+            manual_mapping = [{ device_id = 1000, core = 0 }]
+        "#).unwrap();
+
+        assert_eq!(cpu_config.affinity.manual_map().get(&1000), Some(&0));
+        assert!(matches!(cpu_config.affinity.to_strategy(), CpuAffinityStrategy::Manual(_)));
+    }
+}
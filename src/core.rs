@@ -26,8 +26,17 @@
 //! ## 📦 主要组件
 //!
 //! - [`SoftwareMiningCore`]: 主要的挖矿核心实现
-//! - 设备管理: 支持最多64个虚拟设备
-//! - 结果收集: 支持即时上报和批量收集
+//! - 设备管理: 支持最多64个虚拟设备，通过 [`DeviceHandle`] 进行单设备控制
+//! - 结果收集: 支持即时上报和批量收集，有界缓冲和可配置的溢出策略
+//! - 事件流: 通过 [`SoftwareMiningCore::subscribe_events`] 订阅份额、工作和设备事件
+//! - 暂停控制: [`SoftwareMiningCore::pause`] / [`SoftwareMiningCore::resume`] 按原因暂停所有设备
+//! - 收益估算: [`SoftwareMiningCore::profitability`] 给出每日收益、电费和盈亏平衡点
+//! - 空闲检测: 其他程序占用CPU时暂停，[`SoftwareMiningCore::idle_stats`] 给出挖矿/暂停时间
+//! - 挖矿时间表: 按时间段停止或降速，[`SoftwareMiningCore::schedule_state`] 给出当前时间段
+//! - 批次调优: 按响应延迟上限调整批次大小，[`SoftwareMiningCore::batch_tuning`] 给出各设备的结果
+//! - 哈希实现: 工厂按基准测试选择最快的实现，[`SoftwareMiningCore::hash_benchmark`] 给出测试结果
+//! - CPU信息: 核心能力中的SIMD扩展和缓存大小来自真实检测，[`SoftwareMiningCore::cpu_info`] 给出厂商和型号
+//! - 统计日志: 累计统计周期性写入磁盘、初始化时读取，[`SoftwareMiningCore::lifetime_stats`] 给出跨重启的总计
 //! - 配置管理: 支持环境变量和配置文件
 //!
 //! ## 🎯 设计特点
@@ -41,20 +50,81 @@ use cgminer_core::{
     MiningCore, CoreInfo, CoreCapabilities, CoreConfig, CoreStats, CoreError,
     DeviceInfo, MiningDevice, Work, MiningResult,
     TemperatureCapabilities, VoltageCapabilities, FrequencyCapabilities,
-    FanCapabilities, CpuSpecificCapabilities, CpuCacheInfo
+    FanCapabilities, CpuSpecificCapabilities
 };
-use crate::device::SoftwareDevice;
+use crate::device::{self, NonceRange, PauseFlags, PauseReason, SoftwareDevice};
+use crate::device_handle::{self, DeviceHandle, DeviceMap, SharedDevice};
+use crate::events::{EventBus, MiningEvent, MiningEventKind, CORE_EVENT_DEVICE_ID};
+use crate::hashing::{BenchmarkReport, CpuIdentity, HashImplementation};
 use crate::performance::PerformanceOptimizer;
-use crate::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy};
+use crate::cgroup::CgroupLimits;
+use crate::cpu_info::CpuInfo;
+use crate::config::{self, CpuCoreConfig};
+use crate::cpu_affinity::{self, CpuAffinityManager, CpuAffinityStats, CpuAffinityStrategy};
+use crate::idle::{IdleDetector, IdleStats, LoadSource, SysinfoLoadSource};
+use crate::load_balancer;
+use crate::numa::{NumaNodeStats, NumaTopology};
+use crate::power::{PowerAllocation, PowerMeter, PowerReport};
+use crate::profitability::{self, ProfitabilityEstimate, ProfitabilityEstimator};
+use crate::result_buffer::{ResultBuffer, ResultBufferConfig, ResultBufferStats, ResultReceiver, ResultSender};
+use crate::schedule::{Clock, Schedule, ScheduleState, SystemClock};
+use crate::stats_journal::{LifetimeStats, SessionCounters, StatsJournal, StatsJournalConfig};
+use crate::temperature::TemperatureManager;
+use crate::tuning::{BatchTuner, TuningCache, TuningResult};
 // 平台优化模块
 use crate::platform_optimization;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, SystemTime};
-use tokio::sync::{Mutex, mpsc};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{Mutex, broadcast};
 use tracing::{info, warn, error, debug};
 
+/// 用于份额验证的最近工作数量
+const RECENT_WORK_LIMIT: usize = 16;
+
+/// 已移除设备的累计统计，保证设备缩容后核心总计单调递增
+#[derive(Debug, Clone, Default)]
+pub struct RetiredTotals {
+    /// 已移除的设备数量
+    pub devices: u32,
+    /// 累计哈希数
+    pub total_hashes: u64,
+    /// 累计接受的工作
+    pub accepted_work: u64,
+    /// 累计拒绝的工作
+    pub rejected_work: u64,
+    /// 累计硬件错误
+    pub hardware_errors: u64,
+}
+
+impl RetiredTotals {
+    /// 累加一个被移除设备的统计
+    fn absorb(&mut self, stats: &cgminer_core::DeviceStats) {
+        self.devices += 1;
+        self.total_hashes += stats.total_hashes;
+        self.accepted_work += stats.accepted_work;
+        self.rejected_work += stats.rejected_work;
+        self.hardware_errors += stats.hardware_errors;
+    }
+}
+
+/// 核心复算份额时拒绝的计数（设备统计中这些份额仍计为接受）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShareRejections {
+    /// 哈希不满足目标难度
+    pub invalid: u64,
+    /// 对应的工作已过期或未知（不在最近 `RECENT_WORK_LIMIT` 个工作中）
+    pub stale: u64,
+}
+
+impl ShareRejections {
+    /// 拒绝的份额总数
+    pub fn total(&self) -> u64 {
+        self.invalid + self.stale
+    }
+}
+
 /// 软算法挖矿核心
 pub struct SoftwareMiningCore {
     /// 核心信息
@@ -63,28 +133,85 @@ pub struct SoftwareMiningCore {
     capabilities: CoreCapabilities,
     /// 核心配置
     config: Option<CoreConfig>,
+    /// 解析后的CPU核心配置
+    cpu_config: CpuCoreConfig,
     /// 设备列表
-    devices: Arc<Mutex<HashMap<u32, Box<dyn MiningDevice>>>>,
+    devices: DeviceMap,
     /// 核心统计信息
     stats: Arc<RwLock<CoreStats>>,
     /// 是否正在运行
     running: Arc<RwLock<bool>>,
     /// 启动时间
     start_time: Option<SystemTime>,
-    /// 性能优化器
-    performance_optimizer: Option<PerformanceOptimizer>,
+    /// 性能优化器（功率上限任务共享）
+    performance_optimizer: Option<Arc<RwLock<PerformanceOptimizer>>>,
     /// CPU绑定管理器
     cpu_affinity_manager: Option<Arc<RwLock<CpuAffinityManager>>>,
-    /// cgminer风格结果通道 - 立即上报
-    result_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<MiningResult>>>>,
-    result_sender: Option<mpsc::UnboundedSender<MiningResult>>,
-    /// 收集到的结果缓存
-    collected_results: Arc<Mutex<Vec<MiningResult>>>,
+    /// cgminer风格结果通道 - 立即上报（有界）
+    result_receiver: Arc<Mutex<Option<ResultReceiver>>>,
+    result_sender: Option<ResultSender>,
+    /// 收集到的结果缓存（有界）
+    collected_results: Arc<ResultBuffer>,
+    /// 最近分发的工作，用于验证设备上报的份额
+    recent_work: Arc<Mutex<VecDeque<Arc<Work>>>>,
+    /// 事件总线
+    event_bus: EventBus,
+    /// 已移除设备的累计统计
+    retired_totals: Arc<RwLock<RetiredTotals>>,
+    /// 核心复算拒绝的份额
+    share_rejections: Arc<RwLock<ShareRejections>>,
+    /// 负载均衡后台任务（仅 `LoadBalanced` 策略）
+    load_balancer_task: Option<tokio::task::JoinHandle<()>>,
+    /// cgroup v2 CPU限制（容器中运行时）
+    cgroup_limits: Option<CgroupLimits>,
+    /// 指定的可用CPU数量（替换按CPU掩码和cgroup计算的结果）
+    usable_cpu_override: Option<u32>,
+    /// 检测到的CPU信息
+    cpu_info: CpuInfo,
+    /// 所有设备共用的温度管理器（初始化时创建）
+    temperature_manager: Option<TemperatureManager>,
+    /// 闭环温度调节后台任务
+    thermal_task: Option<tokio::task::JoinHandle<()>>,
+    /// RAPL功耗测量（不支持时为None）
+    power_meter: Arc<RwLock<Option<PowerMeter>>>,
+    /// 功耗采样后台任务（仅RAPL可用时），唯一调用 `PowerMeter::update` 的地方
+    power_sampler_task: Option<tokio::task::JoinHandle<()>>,
+    /// 功率上限后台任务（仅配置了 `[power.cap]` 时）
+    power_cap_task: Option<tokio::task::JoinHandle<()>>,
+    /// 所有设备共享的暂停标志
+    pause_flags: PauseFlags,
+    /// 收益估算器
+    profitability: Arc<RwLock<ProfitabilityEstimator>>,
+    /// 收益估算后台任务（仅启用 `[profitability]` 时）
+    profitability_task: Option<tokio::task::JoinHandle<()>>,
+    /// 空闲检测器
+    idle_detector: Arc<RwLock<IdleDetector>>,
+    /// 系统负载来源（第一次启动时默认创建sysinfo来源，重新启动时继续使用）
+    load_source: Option<Arc<std::sync::Mutex<Box<dyn LoadSource>>>>,
+    /// 空闲检测后台任务（仅启用 `[idle]` 时）
+    idle_task: Option<tokio::task::JoinHandle<()>>,
+    /// 挖矿时间表使用的时钟
+    clock: Arc<dyn Clock>,
+    /// 挖矿时间表的当前状态
+    schedule_state: Arc<RwLock<Option<ScheduleState>>>,
+    /// 挖矿时间表后台任务（仅启用 `[schedule]` 时）
+    schedule_task: Option<tokio::task::JoinHandle<()>>,
+    /// 上一次保存的批次调优结果（新设备从这里开始调优）
+    tuning_cache: Arc<RwLock<Option<TuningCache>>>,
+    /// 哈希实现基准测试结果（未运行时为None）
+    hash_benchmark: Option<BenchmarkReport>,
+    /// 累计统计日志
+    stats_journal: Arc<RwLock<StatsJournal>>,
+    /// 统计日志后台任务（仅启用 `[stats_journal]` 时）
+    stats_journal_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl SoftwareMiningCore {
     /// 创建新的软算法挖矿核心
     pub fn new(name: String) -> Self {
+        let mut cpu_config = CpuCoreConfig::default();
+        cpu_config.apply_env_overrides();
+
         let core_info = CoreInfo::new(
             name.clone(),
             cgminer_core::CoreType::Custom("optimized_cpu".to_string()),
@@ -94,6 +221,21 @@ impl SoftwareMiningCore {
             vec!["optimized_cpu".to_string(), "simd".to_string(), "cpu".to_string()],
         );
 
+        // 容器中的CPU数量以cgroup限制为准
+        let cgroup_limits = CgroupLimits::detect().filter(CgroupLimits::is_limited);
+        let cgroup_cpu_limit = cgroup_limits.as_ref().and_then(CgroupLimits::cpu_limit);
+        if let Some(ref limits) = cgroup_limits {
+            info!("检测到cgroup CPU限制: 配额 {:?} 个CPU, cpuset {:?}",
+                  limits.cpu_budget(), limits.effective_cpus);
+        }
+        let logical_cores = cgroup_cpu_limit.map_or(num_cpus::get() as u32, |limit| limit.min(num_cpus::get() as u32));
+        let physical_cores = (num_cpus::get_physical() as u32).min(logical_cores);
+
+        let cpu_info = CpuInfo::detect();
+        info!("检测到CPU: {} {}, SIMD {:?}, 缓存 {:?}",
+              cpu_info.vendor.as_deref().unwrap_or("未知厂商"), cpu_info.model.as_deref().unwrap_or("未知型号"),
+              cpu_info.simd_support(), cpu_info.cache);
+
         let capabilities = CoreCapabilities {
             supports_auto_tuning: false,
             temperature_capabilities: TemperatureCapabilities {
@@ -121,30 +263,27 @@ impl SoftwareMiningCore {
             max_devices: Some(64), // 软算法核心支持最多64个设备
             supported_algorithms: vec!["SHA256".to_string(), "SHA256d".to_string()],
             cpu_capabilities: Some(CpuSpecificCapabilities {
-                simd_support: vec!["SSE".to_string(), "AVX".to_string(), "AVX2".to_string(), "SHA".to_string()], // 优化SIMD支持
+                simd_support: cpu_info.simd_support(), // 检测到的SIMD扩展
                 supports_cpu_affinity: true,  // 支持CPU绑定
-                supports_numa_awareness: true, // 优化版本支持NUMA
-                physical_cores: num_cpus::get_physical() as u32,
-                logical_cores: num_cpus::get() as u32,
-                cache_info: Some(CpuCacheInfo {
-                    l1_data_kb: 32,
-                    l1_instruction_kb: 32,
-                    l2_kb: 256,
-                    l3_kb: 8192,
-                }),
+                supports_numa_awareness: NumaTopology::detect().is_some(), // 能读取NUMA节点信息时支持
+                physical_cores,  // cgroup限制时不超过可用CPU数量
+                logical_cores,
+                cache_info: cpu_info.cache.to_cache_info(), // 无法检测时为None
             }),
             core_type: cgminer_core::CoreType::Custom("optimized_cpu".to_string()),
         };
 
         let stats = CoreStats::new(name);
 
-        // 创建cgminer风格的结果通道
-        let (sender, receiver) = mpsc::unbounded_channel();
+        // 创建cgminer风格的有界结果通道
+        let collected_results = Arc::new(ResultBuffer::new(ResultBufferConfig::default()));
+        let (sender, receiver) = collected_results.channel();
 
         Self {
             core_info,
             capabilities,
             config: None,
+            cpu_config,
             devices: Arc::new(Mutex::new(HashMap::new())),
             stats: Arc::new(RwLock::new(stats)),
             running: Arc::new(RwLock::new(false)),
@@ -153,130 +292,534 @@ impl SoftwareMiningCore {
             cpu_affinity_manager: None,
             result_receiver: Arc::new(Mutex::new(Some(receiver))),
             result_sender: Some(sender),
-            collected_results: Arc::new(Mutex::new(Vec::new())),
+            collected_results,
+            recent_work: Arc::new(Mutex::new(VecDeque::with_capacity(RECENT_WORK_LIMIT))),
+            event_bus: EventBus::default(),
+            retired_totals: Arc::new(RwLock::new(RetiredTotals::default())),
+            share_rejections: Arc::new(RwLock::new(ShareRejections::default())),
+            load_balancer_task: None,
+            cgroup_limits,
+            usable_cpu_override: None,
+            cpu_info,
+            temperature_manager: None,
+            thermal_task: None,
+            power_meter: Arc::new(RwLock::new(None)),
+            power_sampler_task: None,
+            power_cap_task: None,
+            pause_flags: PauseFlags::default(),
+            profitability: Arc::new(RwLock::new(ProfitabilityEstimator::new(Default::default()))),
+            profitability_task: None,
+            idle_detector: Arc::new(RwLock::new(IdleDetector::new(Default::default()))),
+            load_source: None,
+            idle_task: None,
+            clock: Arc::new(SystemClock),
+            schedule_state: Arc::new(RwLock::new(None)),
+            schedule_task: None,
+            tuning_cache: Arc::new(RwLock::new(None)),
+            stats_journal: Arc::new(RwLock::new(StatsJournal::open(StatsJournalConfig::default()))),
+            stats_journal_task: None,
+            hash_benchmark: None,
+        }
+    }
+
+    /// 获取单个设备的控制句柄
+    pub async fn device(&self, device_id: u32) -> Option<DeviceHandle> {
+        let devices = self.devices.lock().await;
+        devices.contains_key(&device_id)
+            .then(|| DeviceHandle::new(device_id, self.devices.clone(), self.collected_results.clone()))
+    }
+
+    /// 获取所有设备的控制句柄（按设备ID排序）
+    pub async fn device_handles(&self) -> Vec<DeviceHandle> {
+        let devices = self.devices.lock().await;
+        let mut device_ids: Vec<u32> = devices.keys().copied().collect();
+        device_ids.sort_unstable();
+        device_ids.into_iter()
+            .map(|device_id| DeviceHandle::new(device_id, self.devices.clone(), self.collected_results.clone()))
+            .collect()
+    }
+
+    /// 订阅挖矿事件流
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MiningEvent> {
+        self.event_bus.subscribe()
+    }
+
+    /// 获取事件总线
+    pub fn event_bus(&self) -> &EventBus {
+        &self.event_bus
+    }
+
+    /// 获取CPU绑定统计信息（包含每个设备的绑定结果），未启用CPU绑定时返回 `None`
+    pub fn cpu_affinity_stats(&self) -> Option<CpuAffinityStats> {
+        let manager = self.cpu_affinity_manager.as_ref()?;
+        manager.read().ok().map(|manager| manager.get_affinity_stats())
+    }
+
+    /// 按NUMA节点汇总设备算力
+    ///
+    /// 没有NUMA信息或设备未绑定核心时，设备归入 `node_id` 为 `None` 的一组。
+    pub async fn numa_node_stats(&self) -> Vec<NumaNodeStats> {
+        let handles = self.device_handles().await;
+        let device_nodes: HashMap<u32, u32> = {
+            let manager = self.cpu_affinity_manager.as_ref().and_then(|manager| manager.read().ok());
+            handles.iter()
+                .filter_map(|handle| {
+                    let node = manager.as_ref().and_then(|manager| manager.device_node(handle.id()))?;
+                    Some((handle.id(), node))
+                })
+                .collect()
+        };
+
+        let mut node_stats: Vec<NumaNodeStats> = Vec::new();
+        for handle in handles {
+            let node_id = device_nodes.get(&handle.id()).copied();
+            let device_stats = match handle.stats().await {
+                Ok(device_stats) => device_stats,
+                Err(e) => {
+                    warn!("获取设备 {} 统计失败: {}", handle.id(), e);
+                    continue;
+                }
+            };
+
+            let index = match node_stats.iter().position(|stats| stats.node_id == node_id) {
+                Some(index) => index,
+                None => {
+                    node_stats.push(NumaNodeStats {
+                        node_id,
+                        device_ids: Vec::new(),
+                        hashrate: 0.0,
+                        total_hashes: 0,
+                    });
+                    node_stats.len() - 1
+                }
+            };
+            let stats = &mut node_stats[index];
+            stats.device_ids.push(handle.id());
+            stats.hashrate += device_stats.current_hashrate.hashes_per_second;
+            stats.total_hashes += device_stats.total_hashes;
         }
+
+        node_stats.sort_by_key(|stats| stats.node_id);
+        node_stats
+    }
+
+    /// 获取结果缓冲统计信息（包含丢弃计数）
+    pub fn result_buffer_stats(&self) -> ResultBufferStats {
+        self.collected_results.stats()
+    }
+
+    /// 按配置重建结果缓冲和结果通道（必须在创建设备之前调用）
+    ///
+    /// 结果收集任务启动时取走接收端，每次初始化都重建通道，重新初始化后结果仍能送达；
+    /// 旧的收集任务在旧设备和旧发送端释放后自行结束。
+    async fn configure_result_buffer(&mut self, buffer_config: ResultBufferConfig) {
+        info!("结果缓冲: 容量={}, 溢出策略={:?}", buffer_config.capacity, buffer_config.overflow_policy);
+        let collected_results = Arc::new(ResultBuffer::new(buffer_config));
+        let (sender, receiver) = collected_results.channel();
+
+        *self.result_receiver.lock().await = Some(receiver);
+        self.result_sender = Some(sender);
+        self.collected_results = collected_results;
     }
 
     /// 创建软算法设备
-    async fn create_software_devices(&self, config: &CoreConfig) -> Result<Vec<Box<dyn MiningDevice>>, CoreError> {
+    async fn create_software_devices(&self, config: &CoreConfig) -> Result<Vec<SoftwareDevice>, CoreError> {
         let mut devices = Vec::new();
 
         // 从配置中获取设备数量（支持环境变量覆盖）
-        let requested_device_count = self.get_device_count_from_config_with_params(config);
+        let requested_device_count = self.cpu_config.device_count;
 
-        // CPU挖矿优化：限制设备数量为CPU核心数，避免不必要的开销
-        let cpu_cores = num_cpus::get() as u32;
+        // CPU挖矿优化：限制设备数量为可用CPU核心数（扣除预留核心），避免不必要的开销
+        let cpu_cores = self.usable_cpu_count();
+        if cpu_cores == 0 {
+            return Err(CoreError::config("预留核心后没有可用的CPU核心"));
+        }
         let device_count = if requested_device_count > cpu_cores {
-            info!("⚠️  请求的设备数量 {} 超过CPU核心数 {}，自动限制为CPU核心数以获得最佳性能",
+            info!("⚠️  请求的设备数量 {} 超过可用CPU核心数 {}，自动限制为可用核心数以获得最佳性能",
                   requested_device_count, cpu_cores);
             cpu_cores
         } else {
             requested_device_count
         };
 
-        info!("实际设备数量: {} (CPU核心数: {})", device_count, cpu_cores);
+        info!("实际设备数量: {} (可用CPU核心数: {})", device_count, cpu_cores);
         debug!("完整配置参数: {:?}", config.custom_params);
 
-        // 获取算力范围 - 提高到您期望的35MH/s水平
-        let min_hashrate = config.custom_params
-            .get("min_hashrate")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(30_000_000.0); // 30 MH/s
-
-        let max_hashrate = config.custom_params
-            .get("max_hashrate")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(40_000_000.0); // 40 MH/s
-
-        let error_rate = config.custom_params
-            .get("error_rate")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.01); // 1%
-
-        let batch_size = config.custom_params
-            .get("batch_size")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(1_000_000) as u32; // 增加批次大小到100万，提高实际算力
+        let params = &self.cpu_config;
 
         info!("🔥 创建 {} 个优化CPU设备 (CPU核心数: {})，算力范围: {:.2} - {:.2} MH/s",
               device_count,
               cpu_cores,
-              min_hashrate / 1_000_000.0,
-              max_hashrate / 1_000_000.0);
+              params.min_hashrate / 1_000_000.0,
+              params.max_hashrate / 1_000_000.0);
 
         for i in 0..device_count {
-            // 为每个设备分配不同的算力
-            let device_hashrate = min_hashrate +
-                (max_hashrate - min_hashrate) * (i as f64 / device_count.max(1) as f64);
-
-            let mut device_config = if (i as usize) < config.devices.len() {
-                config.devices[i as usize].clone()
-            } else {
-                cgminer_core::DeviceConfig {
-                    chain_id: i as u8,
-                    enabled: true,
-                    frequency: 600 + (i * 50), // 递增频率
-                    voltage: 900 + (i * 20),   // 递增电压
-                    auto_tune: false,
-                    chip_count: 64,
-                    temperature_limit: 80.0,
-                    fan_speed: Some(50 + i * 5),
-                }
-            };
+            let device = self.build_device(config, params, i, device_count).await?;
+            device.set_nonce_range(NonceRange::partition(i, device_count));
+            devices.push(device);
+        }
+
+        Ok(devices)
+    }
 
-            // 应用性能优化
-            if let Some(optimizer) = &self.performance_optimizer {
+    /// 创建第 `index` 个软算法设备（设备ID为 1000 + index）
+    async fn build_device(
+        &self,
+        config: &CoreConfig,
+        params: &CpuCoreConfig,
+        index: u32,
+        device_count: u32,
+    ) -> Result<SoftwareDevice, CoreError> {
+        let i = index;
+
+        // 为每个设备分配不同的算力
+        let device_hashrate = params.min_hashrate +
+            (params.max_hashrate - params.min_hashrate) * (i as f64 / device_count.max(1) as f64).min(1.0);
+        let device_hashrate = params.throttling.apply(device_hashrate);
+
+        let mut device_config = if (i as usize) < config.devices.len() {
+            config.devices[i as usize].clone()
+        } else {
+            config::default_device_config(i)
+        };
+
+        // 应用性能优化
+        if let Some(optimizer) = &self.performance_optimizer {
+            if let Ok(optimizer) = optimizer.read() {
                 optimizer.apply_to_device_config(&mut device_config, 1000 + i);
             }
+        }
 
-            let device_info = DeviceInfo::new(
-                1000 + i,
-                format!("Software Device {}", i),
-                "software".to_string(),
-                i as u8,
-            );
+        let device_info = DeviceInfo::new(
+            1000 + i,
+            format!("Software Device {}", i),
+            "software".to_string(),
+            i as u8,
+        );
 
-            let mut device = if let Some(cpu_affinity) = &self.cpu_affinity_manager {
-                // 为CPU绑定管理器分配设备
-                {
-                    let mut affinity_manager = cpu_affinity.write().map_err(|e| {
+        let mut device = if let Some(cpu_affinity) = &self.cpu_affinity_manager {
+            // 为CPU绑定管理器分配设备
+            {
+                let mut affinity_manager = cpu_affinity.write().map_err(|e| {
+                    CoreError::runtime(format!("Failed to acquire write lock: {}", e))
+                })?;
+                affinity_manager.assign_cpu_core(1000 + i);
+            }
+
+            SoftwareDevice::new_with_cpu_affinity(
+                device_info,
+                device_config,
+                device_hashrate,
+                params.error_rate,
+                params.batch_size,
+                cpu_affinity.clone(),
+            ).await?
+        } else {
+            SoftwareDevice::new(
+                device_info,
+                device_config,
+                device_hashrate,
+                params.error_rate,
+                params.batch_size,
+            ).await?
+        };
+
+        // 只有显式配置的单设备上限才限制连续计算的算力，0表示不限制
+        device.set_hashrate_cap(params.throttling.max_device_hashrate);
+
+        // 设置cgminer风格的结果发送通道
+        if let Some(ref sender) = self.result_sender {
+            device.set_result_sender(sender.clone());
+        }
+        device.set_event_bus(self.event_bus.clone());
+        match self.temperature_manager {
+            Some(ref manager) => device.set_temperature_manager(manager.clone()),
+            None => device.set_temperature_config(params.temperature.clone()),
+        }
+        device.set_priority_config(params.priority.clone());
+        device.set_pause_flags(self.pause_flags.clone());
+        device.set_hash_implementation(self.hash_implementation())?;
+        if let Some(state) = self.schedule_state().filter(|state| state.active) {
+            device.set_schedule_duty_cycle(state.duty_cycle);
+        }
+        if params.tuning.enabled {
+            let cache = self.tuning_cache.read().ok().and_then(|cache| cache.clone());
+            device.set_batch_tuner(match cache {
+                Some(ref cache) => BatchTuner::from_cache(params.tuning.clone(), cache),
+                None => BatchTuner::new(params.tuning.clone()),
+            });
+        }
+
+        Ok(device)
+    }
+
+    /// 按设备ID顺序重新划分nonce空间（`devices` 为按ID排序的设备表快照）
+    async fn rebalance_nonce_space(devices: &[(u32, SharedDevice)]) {
+        let device_count = devices.len() as u32;
+        for (index, (_, device)) in devices.iter().enumerate() {
+            device.lock().await.set_nonce_range(NonceRange::partition(index as u32, device_count));
+        }
+    }
+
+    /// 运行时增加设备，返回新设备的ID
+    ///
+    /// 新设备会分配CPU核心，所有设备重新划分nonce空间；核心运行中时新设备立即开始挖矿。
+    pub async fn add_devices(&mut self, count: u32) -> Result<Vec<u32>, CoreError> {
+        let config = self.config.clone()
+            .ok_or_else(|| CoreError::runtime("核心未初始化，无法增加设备"))?;
+        let params = self.cpu_config.clone();
+        let running = self.is_running();
+
+        let mut added = Vec::new();
+        {
+            let mut devices = self.devices.lock().await;
+            let cpu_cores = self.usable_cpu_count();
+            let current = devices.len() as u32;
+            let target = (current + count).min(cpu_cores.max(current));
+            if target < current + count {
+                warn!("⚠️  请求增加 {} 个设备，受CPU核心数 {} 限制只增加 {} 个",
+                      count, cpu_cores, target - current);
+            }
+
+            let mut next_index = devices.keys().max().map(|id| id - 1000 + 1).unwrap_or(0);
+            for _ in current..target {
+                let device = self.build_device(&config, &params, next_index, target).await?;
+                let device_id = device.device_id();
+                devices.insert(device_id, Arc::new(Mutex::new(device)));
+                added.push(device_id);
+                next_index += 1;
+            }
+        }
+
+        let devices = device_handle::snapshot(&self.devices).await;
+        Self::rebalance_nonce_space(&devices).await;
+        self.apply_cpu_budget(&devices).await;
+
+        // 新设备接收当前工作并开始挖矿。启动前先应用当前的功率上限分配和温度调节占空比
+        // （时间表占空比在创建设备时已设置），避免在下一个调节周期之前超出功率上限或温度阈值
+        let latest_work = self.recent_work.lock().await.back().cloned();
+        let allocation = if running { self.power_allocation() } else { None };
+        let mut running_index = 0;
+        for (device_id, device) in &devices {
+            let mut device = device.lock().await;
+            if !added.contains(device_id) {
+                if device.is_running() {
+                    running_index += 1;
+                }
+                continue;
+            }
+            if let Some(ref work) = latest_work {
+                device.submit_work(Arc::clone(work)).await?;
+            }
+            if running {
+                // 与功率上限任务相同，运行中的设备按ID顺序分配线程
+                if let Some(ref allocation) = allocation {
+                    device.set_power_duty_cycle(allocation.duty_for(running_index));
+                }
+                if let Err(e) = device.thermal_tick(Duration::ZERO) {
+                    debug!("设备 {} 温度调节失败: {}", device_id, e);
+                }
+                device.start_continuous_mining().await?;
+                running_index += 1;
+            }
+        }
+
+        info!("➕ 增加了 {} 个设备，当前设备数: {}", added.len(), devices.len());
+        Ok(added)
+    }
+
+    /// 运行时移除设备（优先移除ID最大的设备），返回被移除设备的ID
+    ///
+    /// 被移除的设备先停止并排空，其统计数据累加到退役总计中，保证核心总计单调递增。
+    pub async fn remove_devices(&mut self, count: u32) -> Result<Vec<u32>, CoreError> {
+        // 先从设备表中取出被移除的设备，之后停止设备时不再持有表锁
+        let taken: Vec<(u32, SharedDevice)> = {
+            let mut devices = self.devices.lock().await;
+            let mut device_ids: Vec<u32> = devices.keys().copied().collect();
+            device_ids.sort_unstable_by(|a, b| b.cmp(a));
+            let keep_at_least = 1;
+            let removable = devices.len().saturating_sub(keep_at_least).min(count as usize);
+            device_ids.into_iter()
+                .take(removable)
+                .filter_map(|device_id| devices.remove(&device_id).map(|device| (device_id, device)))
+                .collect()
+        };
+
+        let mut removed = Vec::new();
+        for (device_id, device) in taken {
+            // 停止设备会等待其工作线程退出，之后读取的统计不再变化
+            let mut device = device.lock().await;
+            if let Err(e) = device.stop().await {
+                warn!("停止被移除的设备 {} 失败: {}", device_id, e);
+            }
+
+            match device.get_stats().await {
+                Ok(device_stats) => {
+                    let mut retired = self.retired_totals.write().map_err(|e| {
                         CoreError::runtime(format!("Failed to acquire write lock: {}", e))
                     })?;
-                    affinity_manager.assign_cpu_core(1000 + i);
+                    retired.absorb(&device_stats);
                 }
+                Err(e) => warn!("获取被移除设备 {} 的统计失败: {}", device_id, e),
+            }
 
-                SoftwareDevice::new_with_cpu_affinity(
-                    device_info,
-                    device_config,
-                    device_hashrate,
-                    error_rate,
-                    batch_size,
-                    cpu_affinity.clone(),
-                ).await?
-            } else {
-                SoftwareDevice::new(
-                    device_info,
-                    device_config,
-                    device_hashrate,
-                    error_rate,
-                    batch_size,
-                ).await?
-            };
+            if let Some(cpu_affinity) = &self.cpu_affinity_manager {
+                if let Ok(mut manager) = cpu_affinity.write() {
+                    manager.release_device(device_id);
+                }
+            }
+            removed.push(device_id);
+        }
+
+        let devices = device_handle::snapshot(&self.devices).await;
+        Self::rebalance_nonce_space(&devices).await;
+        self.apply_cpu_budget(&devices).await;
+
+        info!("➖ 移除了 {} 个设备，当前设备数: {}", removed.len(), devices.len());
+        Ok(removed)
+    }
 
-            // 设置cgminer风格的结果发送通道
-            if let Some(ref sender) = self.result_sender {
-                device.set_result_sender(sender.clone());
+    /// 将设备数量调整为 `target`
+    pub async fn set_device_count(&mut self, target: u32) -> Result<u32, CoreError> {
+        let current = self.devices.lock().await.len() as u32;
+        if target > current {
+            self.add_devices(target - current).await?;
+        } else if target < current {
+            self.remove_devices(current - target).await?;
+        }
+        Ok(self.devices.lock().await.len() as u32)
+    }
+
+    /// 应用进程CPU掩码、预留核心和cgroup限制后的可用CPU数量
+    fn usable_cpu_count(&self) -> u32 {
+        if let Some(count) = self.usable_cpu_override {
+            return count;
+        }
+        let cpus = cpu_affinity::usable_cpus(&self.cpu_config.affinity.reservation());
+        match self.cgroup_limits {
+            Some(ref limits) => {
+                let cpus = limits.restrict(&cpus).len() as u32;
+                limits.cpu_budget().map_or(cpus, |budget| cpus.min(budget.ceil().max(1.0) as u32))
             }
+            None => cpus.len() as u32,
+        }
+    }
 
-            devices.push(Box::new(device) as Box<dyn MiningDevice>);
+    /// 按cgroup配额设置每个设备的占空比，使全部设备的CPU占用不超过配额
+    async fn apply_cpu_budget(&self, devices: &[(u32, SharedDevice)]) {
+        let limits = match self.cgroup_limits {
+            Some(ref limits) => limits,
+            None => return,
+        };
+
+        let duty_cycle = limits.duty_cycle(devices.len() as u32);
+        if duty_cycle < 1.0 {
+            info!("cgroup配额 {:.2} 个CPU由 {} 个设备共享，设备占空比 {:.0}%",
+                  limits.cpu_budget().unwrap_or_default(), devices.len(), duty_cycle * 100.0);
+        }
+        for (_, device) in devices {
+            device.lock().await.set_duty_cycle(duty_cycle);
         }
+    }
 
-        Ok(devices)
+    /// 获取cgroup v2 CPU限制
+    pub fn cgroup_limits(&self) -> Option<&CgroupLimits> {
+        self.cgroup_limits.as_ref()
+    }
+
+    /// 使用指定的cgroup限制（替换自动检测的结果），需在初始化之前调用
+    pub fn set_cgroup_limits(&mut self, limits: Option<CgroupLimits>) {
+        if let Some(cpu_capabilities) = self.capabilities.cpu_capabilities.as_mut() {
+            let host_cpus = num_cpus::get() as u32;
+            cpu_capabilities.logical_cores = limits.as_ref()
+                .and_then(CgroupLimits::cpu_limit)
+                .map_or(host_cpus, |limit| limit.min(host_cpus));
+            cpu_capabilities.physical_cores = (num_cpus::get_physical() as u32).min(cpu_capabilities.logical_cores);
+        }
+        self.cgroup_limits = limits;
+    }
+
+    /// 使用指定的可用CPU数量限制设备数量（替换按CPU掩码和cgroup计算的结果），`None` 恢复自动计算
+    pub fn set_usable_cpu_count(&mut self, count: Option<u32>) {
+        self.usable_cpu_override = count;
+    }
+
+    /// 核心是否正在运行
+    fn is_running(&self) -> bool {
+        self.running.read().map(|running| *running).unwrap_or(false)
+    }
+
+    /// 获取已移除设备的累计统计
+    pub fn retired_totals(&self) -> RetiredTotals {
+        self.retired_totals.read().map(|totals| totals.clone()).unwrap_or_default()
+    }
+
+    /// 获取核心复算拒绝的份额计数（无效和过期）
+    pub fn share_rejections(&self) -> ShareRejections {
+        self.share_rejections.read().map(|rejections| *rejections).unwrap_or_default()
+    }
+
+    /// 最近一次的RAPL功耗报告（封装功率、设备分摊和J/TH），不支持RAPL时返回 `None`
+    pub fn power_report(&self) -> Option<PowerReport> {
+        self.power_meter.read().ok()?.as_ref()?.last_report().cloned()
+    }
+
+    /// 采样一次RAPL功耗，把分摊到设备的功率写入设备统计
+    ///
+    /// 只由功耗采样任务调用，其他读者通过 `PowerMeter::last_report` 读取结果。
+    async fn sample_power(devices: &DeviceMap, power_meter: &RwLock<Option<PowerMeter>>) -> Option<PowerReport> {
+        let devices = device_handle::snapshot(devices).await;
+        let mut inputs = Vec::with_capacity(devices.len());
+        for (device_id, device) in &devices {
+            let device = device.lock().await;
+            let total_hashes = device.get_stats().await.map(|stats| stats.total_hashes).unwrap_or(0);
+            inputs.push((*device_id, device.package_id(), total_hashes));
+        }
+
+        let report = {
+            let mut meter = power_meter.write().ok()?;
+            match meter.as_mut()?.update(&inputs) {
+                Ok(report) => report?,
+                Err(e) => {
+                    debug!("RAPL功耗采样失败: {}", e);
+                    return None;
+                }
+            }
+        };
+
+        for (device_id, device) in &devices {
+            if let Some(watts) = report.device_watts.get(device_id) {
+                device.lock().await.set_power_consumption(*watts);
+            }
+        }
+        Some(report)
+    }
+
+    /// 启动功耗采样任务（仅RAPL可用时），每 `power.interval_ms` 采样一次
+    fn start_power_sampler(&mut self) {
+        let has_meter = self.power_meter.read().map(|meter| meter.is_some()).unwrap_or(false);
+        if !has_meter {
+            return;
+        }
+
+        if let Some(task) = self.power_sampler_task.take() {
+            task.abort();
+        }
+        let devices = self.devices.clone();
+        let power_meter = self.power_meter.clone();
+        let interval = Duration::from_millis(self.cpu_config.power.interval_ms.max(100));
+        self.power_sampler_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                Self::sample_power(&devices, &power_meter).await;
+            }
+        }));
     }
 
     /// 更新核心统计信息 - 核心层负责算力计算
     async fn update_stats(&self) -> Result<(), CoreError> {
-        let devices = self.devices.lock().await;
+        let devices = device_handle::snapshot(&self.devices).await;
         let mut total_hashrate = 0.0;
         let mut total_accepted = 0;
         let mut total_rejected = 0;
@@ -289,8 +832,9 @@ impl SoftwareMiningCore {
             .unwrap_or_default()
             .as_nanos() as u64;
 
-        for device in devices.values() {
+        for (_, device) in &devices {
             // 获取设备的原始统计数据
+            let device = device.lock().await;
             if let Ok(device_stats) = device.get_stats().await {
                 total_accepted += device_stats.accepted_work;
                 total_rejected += device_stats.rejected_work;
@@ -305,6 +849,8 @@ impl SoftwareMiningCore {
             }
         }
 
+        let power_report = self.power_report();
+
         // 计算核心级别的算力
         let core_start_time = self.start_time.map(|t|
             t.duration_since(SystemTime::UNIX_EPOCH)
@@ -319,6 +865,18 @@ impl SoftwareMiningCore {
             0.0
         };
 
+        // 累加已移除设备的统计，保证总计单调递增
+        let retired = self.retired_totals();
+        total_accepted += retired.accepted_work;
+        total_rejected += retired.rejected_work;
+        total_errors += retired.hardware_errors;
+        total_hashes += retired.total_hashes;
+
+        // 设备把上报的结果都计为接受，核心复算拒绝的份额从接受移到拒绝
+        let rejections = self.share_rejections();
+        total_accepted = total_accepted.saturating_sub(rejections.total());
+        total_rejected += rejections.total();
+
         let mut stats = self.stats.write().map_err(|e| {
             CoreError::runtime(format!("Failed to acquire write lock: {}", e))
         })?;
@@ -341,122 +899,667 @@ impl SoftwareMiningCore {
 
         debug!("核心统计更新: 设备数={}, 活跃={}, 当前算力={:.2} H/s, 平均算力={:.2} H/s",
                stats.device_count, stats.active_devices, stats.total_hashrate, stats.average_hashrate);
+        if let Some(report) = power_report {
+            debug!("核心功耗: {:.1} W, 能效 {}", report.total_watts,
+                   report.joules_per_terahash.map_or("N/A".to_string(), |efficiency| format!("{:.0} J/TH", efficiency)));
+        }
 
         Ok(())
     }
 
-    /// 从配置获取设备数量（带配置参数）
-    fn get_device_count_from_config_with_params(&self, config: &CoreConfig) -> u32 {
-        // 优先级：环境变量 > 配置文件 > 默认值
+    /// 启动立即上报的结果收集任务
+    async fn start_result_collection(&self) -> Result<(), CoreError> {
+        let receiver = {
+            let mut receiver_guard = self.result_receiver.lock().await;
+            receiver_guard.take()
+        };
 
-        // 1. 检查环境变量
-        if let Ok(count_str) = std::env::var("CGMINER_SOFTWARE_DEVICE_COUNT") {
-            if let Ok(count) = count_str.parse::<u32>() {
-                if count > 0 && count <= 1000 {
-                    info!("从环境变量读取优化CPU设备数量: {}", count);
-                    return count;
-                } else {
-                    warn!("环境变量中的设备数量 {} 超出范围，使用配置文件值", count);
+        if let Some(mut receiver) = receiver {
+            let collected_results = self.collected_results.clone();
+            let share_rejections = self.share_rejections.clone();
+            let recent_work = self.recent_work.clone();
+            let event_bus = self.event_bus.clone();
+            let stats_journal = self.stats_journal.clone();
+
+            tokio::spawn(async move {
+                while let Some(result) = receiver.recv().await {
+                    // 立即处理收到的结果
+                    debug!("💎 设备 {} 找到解: nonce={:08x}",
+                          result.device_id, result.nonce);
+
+                    // 复算哈希验证份额，拒绝时区分无效和过期
+                    // 拒绝只记录在 `share_rejections`，由 `get_stats` 和统计日志从设备的接受数中扣除
+                    let work_id = result.work_id.to_string();
+                    let verified = {
+                        let works = recent_work.lock().await;
+                        match works.iter().find(|work| work.id == result.work_id) {
+                            Some(work) => device::share_hash(work, result.nonce)
+                                .filter(|hash| cgminer_core::meets_target(hash, &work.target))
+                                .ok_or((false, "哈希不满足目标难度")),
+                            None => Err((true, "工作已过期或未知")),
+                        }
+                    };
+
+                    let hash = match verified {
+                        Ok(hash) => hash,
+                        Err((stale, reason)) => {
+                            warn!("❌ 设备 {} 份额被拒绝: nonce={:08x}, 原因: {}",
+                                  result.device_id, result.nonce, reason);
+                            if let Ok(mut rejections) = share_rejections.write() {
+                                if stale {
+                                    rejections.stale += 1;
+                                } else {
+                                    rejections.invalid += 1;
+                                }
+                            }
+                            event_bus.emit(result.device_id, MiningEventKind::ShareRejected {
+                                work_id,
+                                nonce: result.nonce,
+                                reason: reason.to_string(),
+                            });
+                            continue;
+                        }
+                    };
+                    if let Ok(mut journal) = stats_journal.write() {
+                        journal.observe_share(profitability::difficulty_from_hash(&hash));
+                    }
+
+                    event_bus.emit(result.device_id, MiningEventKind::ShareValidated {
+                        work_id,
+                        nonce: result.nonce,
+                    });
+
+                    // 缓存结果供collect_results使用，满载时按溢出策略处理
+                    collected_results.push(result).await;
+                }
+            });
+
+            info!("立即上报结果收集任务已启动");
+        }
+
+        Ok(())
+    }
+
+    /// `LoadBalanced` 策略下启动后台负载均衡任务
+    fn start_load_balancer(&mut self) {
+        let manager = match self.cpu_affinity_manager {
+            Some(ref manager) => manager.clone(),
+            None => return,
+        };
+        let load_balanced = manager.read()
+            .map(|m| m.is_enabled() && matches!(m.strategy(), CpuAffinityStrategy::LoadBalanced))
+            .unwrap_or(false);
+        if !load_balanced {
+            return;
+        }
+
+        if let Some(task) = self.load_balancer_task.take() {
+            task.abort();
+        }
+        let config = self.cpu_config.affinity.load_balancer.clone();
+        self.load_balancer_task = Some(tokio::spawn(load_balancer::run(manager, config)));
+    }
+
+    /// 启动闭环温度调节任务，周期性地让每个运行中的设备读取温度并调整占空比
+    ///
+    /// 温度来源是外部命令时，每个周期先运行一次命令，所有设备读取同一个结果；
+    /// 此时即使没有启用温度调节也会启动任务，以便定期更新设备温度。
+    fn start_thermal_governor(&mut self) {
+        let governor_config = &self.cpu_config.temperature.governor;
+        let temperature_manager = match self.temperature_manager {
+            Some(ref manager) if governor_config.enabled || manager.needs_refresh() => manager.clone(),
+            _ => return,
+        };
+
+        if let Some(task) = self.thermal_task.take() {
+            task.abort();
+        }
+        let devices = self.devices.clone();
+        let interval = Duration::from_millis(governor_config.interval_ms.max(100));
+        self.thermal_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut last_tick = tokio::time::Instant::now();
+            loop {
+                ticker.tick().await;
+                let elapsed = last_tick.elapsed();
+                last_tick = tokio::time::Instant::now();
+                temperature_manager.refresh().await;
+
+                // 先取设备快照并释放设备表锁，温度调节期间不阻塞设备增减
+                for (device_id, device) in device_handle::snapshot(&devices).await {
+                    let device = device.lock().await;
+                    if !device.is_running() {
+                        continue;
+                    }
+                    if let Err(e) = device.thermal_tick(elapsed) {
+                        debug!("设备 {} 温度调节失败: {}", device_id, e);
+                    }
                 }
             }
+        }));
+    }
+
+    /// 启动功率上限调节任务（仅配置了 `[power.cap]` 时）
+    ///
+    /// 每个周期读取功耗采样任务最近一次测得的封装功率（没有RAPL时使用功耗模型），由性能优化器计算活跃线程数和占空比，
+    /// 按设备ID顺序分配：前面的设备按占空比运行，其余设备暂停。
+    fn start_power_cap(&mut self) {
+        let cap = match self.cpu_config.power.cap {
+            Some(ref cap) if cfg!(feature = "power-management") => cap.clone(),
+            _ => return,
+        };
+        let optimizer = match self.performance_optimizer {
+            Some(ref optimizer) => optimizer.clone(),
+            None => return,
+        };
+        let has_meter = self.power_meter.read().map(|meter| meter.is_some()).unwrap_or(false);
+        if !has_meter && cap.model.is_none() {
+            warn!("功率上限 {:.0} W 需要RAPL功耗读数或功耗模型，已禁用", cap.budget_watts);
+            return;
         }
 
-        // 2. 从传入的配置参数读取
-        debug!("传入的配置参数: {:?}", config.custom_params);
-        if let Some(device_count) = config.custom_params.get("device_count") {
-            debug!("找到device_count参数: {:?}", device_count);
-            if let Some(count) = device_count.as_u64() {
-                let count = count as u32;
-                debug!("解析device_count为: {}", count);
-                if count > 0 && count <= 1000 {
-                    info!("从配置文件读取优化CPU设备数量: {}", count);
-                    return count;
+        if let Some(task) = self.power_cap_task.take() {
+            task.abort();
+        }
+        info!("⚡ 功率上限 {:.0} W ({})", cap.budget_watts, if has_meter { "RAPL" } else { "功耗模型" });
+
+        let devices = self.devices.clone();
+        let power_meter = self.power_meter.clone();
+        let interval = Duration::from_millis(cap.interval_ms.max(100));
+        self.power_cap_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut active_threads = None;
+            loop {
+                ticker.tick().await;
+
+                // 设备快照按ID排序，运行中的设备按ID顺序分配线程
+                let devices = device_handle::snapshot(&devices).await;
+                let mut running = Vec::new();
+                for (_, device) in &devices {
+                    if device.lock().await.is_running() {
+                        running.push(device.clone());
+                    }
+                }
+                let measured_watts = power_meter.read().ok()
+                    .and_then(|meter| meter.as_ref()?.last_report().map(|report| report.total_watts));
+
+                let allocation = match optimizer.write() {
+                    Ok(mut optimizer) => optimizer.apply_power_cap(measured_watts, running.len()),
+                    Err(_) => None,
+                };
+                let allocation = match allocation {
+                    Some(allocation) => allocation,
+                    None => continue,
+                };
+
+                for (index, device) in running.iter().enumerate() {
+                    device.lock().await.set_power_duty_cycle(allocation.duty_for(index));
+                }
+
+                if active_threads != Some(allocation.active_threads) {
+                    info!("⚡ 功率上限: 实测 {}，{} 个活跃线程，占空比 {:.0}%",
+                          measured_watts.map_or("N/A".to_string(), |watts| format!("{:.1} W", watts)),
+                          allocation.active_threads, allocation.duty_cycle * 100.0);
+                    active_threads = Some(allocation.active_threads);
                 } else {
-                    warn!("配置文件中的设备数量 {} 超出范围，使用默认值", count);
+                    debug!("功率上限: 实测 {:?} W，占空比 {:.2}", measured_watts, allocation.duty_cycle);
                 }
-            } else {
-                warn!("device_count参数无法解析为数字: {:?}", device_count);
             }
+        }));
+    }
+
+    /// 当前功率上限下的线程分配，未配置功率上限或尚未调节时返回 `None`
+    pub fn power_allocation(&self) -> Option<PowerAllocation> {
+        let optimizer = self.performance_optimizer.as_ref()?.read().ok()?;
+        optimizer.power_governor()?.units().map(PowerAllocation::from_units)
+    }
+
+    /// 以指定原因暂停所有设备，所有原因解除后设备自动恢复
+    pub fn pause(&self, reason: PauseReason) {
+        Self::set_paused(&self.pause_flags, &self.event_bus, reason, true);
+    }
+
+    /// 解除指定的暂停原因
+    pub fn resume(&self, reason: PauseReason) {
+        Self::set_paused(&self.pause_flags, &self.event_bus, reason, false);
+    }
+
+    /// 当前的暂停原因（为空表示正在挖矿）
+    pub fn pause_reasons(&self) -> Vec<PauseReason> {
+        self.pause_flags.reasons()
+    }
+
+    /// 设置或解除暂停原因，状态变化时记录日志并发布事件
+    fn set_paused(pause_flags: &PauseFlags, event_bus: &EventBus, reason: PauseReason, paused: bool) {
+        if !pause_flags.set(reason, paused) {
+            return;
+        }
+
+        if paused {
+            info!("⏸️  挖矿暂停: {:?}", reason);
+            event_bus.emit(CORE_EVENT_DEVICE_ID, MiningEventKind::MiningPaused { reason });
         } else {
-            warn!("未找到device_count参数，可用参数: {:?}", config.custom_params.keys().collect::<Vec<_>>());
+            info!("▶️  暂停原因解除: {:?}，剩余 {:?}", reason, pause_flags.reasons());
+            event_bus.emit(CORE_EVENT_DEVICE_ID, MiningEventKind::MiningResumed { reason });
         }
+    }
 
-        // 3. 使用默认值
-        info!("使用默认优化CPU设备数量: 4");
-        4u32
+    /// 最近一次的收益估算，尚未估算（没有难度或算力）时返回 `None`
+    pub fn profitability(&self) -> Option<ProfitabilityEstimate> {
+        self.profitability.read().ok()?.last_estimate().cloned()
     }
 
-    /// 从配置获取设备数量
-    fn get_device_count_from_config(&self) -> u32 {
-        // 优先级：环境变量 > 配置文件 > 默认值
+    /// 立即用当前算力和功率估算一次收益，启用自动暂停时同时更新暂停状态
+    pub async fn estimate_profitability(&self) -> Option<ProfitabilityEstimate> {
+        Self::profitability_tick(&self.devices, &self.power_meter, &self.profitability,
+                                 &self.pause_flags, &self.event_bus).await
+    }
 
-        // 1. 检查环境变量
-        if let Ok(count_str) = std::env::var("CGMINER_SOFTWARE_DEVICE_COUNT") {
-            if let Ok(count) = count_str.parse::<u32>() {
-                if count > 0 && count <= 1000 {
-                    info!("从环境变量读取优化CPU设备数量: {}", count);
-                    return count;
-                } else {
-                    warn!("环境变量中的设备数量 {} 超出范围，使用配置文件值", count);
-                }
+    /// 修改电价 (每kWh)，下一次估算生效
+    pub fn set_electricity_price(&self, price: f64) {
+        if let Ok(mut estimator) = self.profitability.write() {
+            estimator.set_electricity_price(price);
+        }
+    }
+
+    /// 修改BTC价格，下一次估算生效
+    pub fn set_btc_price(&self, price: f64) {
+        if let Ok(mut estimator) = self.profitability.write() {
+            estimator.set_btc_price(price);
+        }
+    }
+
+    /// 收益估算一个周期：汇总算力和功率，更新估算，按需暂停或恢复并发布事件
+    async fn profitability_tick(
+        devices: &DeviceMap,
+        power_meter: &RwLock<Option<PowerMeter>>,
+        estimator: &RwLock<ProfitabilityEstimator>,
+        pause_flags: &PauseFlags,
+        event_bus: &EventBus,
+    ) -> Option<ProfitabilityEstimate> {
+        let mut hashrate = 0.0;
+        for (_, device) in device_handle::snapshot(devices).await {
+            if let Ok(stats) = device.lock().await.get_stats().await {
+                hashrate += stats.current_hashrate.hashes_per_second;
             }
         }
+        // 功率来自功耗采样任务的最近一次报告，估算不自行采样
+        let measured_watts = power_meter.read().ok()
+            .and_then(|meter| meter.as_ref()?.last_report().map(|report| report.total_watts));
+
+        // 暂停期间的算力不代表挖矿时的收益，由估算器沿用最近一次挖矿时的样本
+        if pause_flags.is_paused() {
+            hashrate = 0.0;
+        }
+
+        let (estimate, was_profitable, was_paused, paused) = {
+            let mut estimator = estimator.write().ok()?;
+            let was_profitable = estimator.last_estimate().map(|estimate| estimate.profitable);
+            let was_paused = estimator.should_pause();
+            let estimate = estimator.update(hashrate, measured_watts)?.clone();
+            (estimate, was_profitable, was_paused, estimator.should_pause())
+        };
+
+        Self::set_paused(pause_flags, event_bus, PauseReason::Unprofitable, paused);
+        if was_profitable != Some(estimate.profitable) || was_paused != paused {
+            info!("💰 收益估算: {:.8} BTC/天，收入 {:.4}，电费 {:.4}，{}",
+                  estimate.btc_per_day, estimate.revenue_per_day, estimate.cost_per_day,
+                  if estimate.profitable { "盈利" } else { "亏损" });
+            event_bus.emit(CORE_EVENT_DEVICE_ID, MiningEventKind::ProfitabilityChanged {
+                profitable: estimate.profitable,
+                profit_per_day: estimate.profit_per_day,
+                break_even_electricity_price: estimate.break_even_electricity_price,
+                paused,
+            });
+        } else {
+            debug!("收益估算: 利润 {:.4}/天", estimate.profit_per_day);
+        }
+
+        Some(estimate)
+    }
 
-        // 2. 从配置文件读取
-        if let Some(config) = &self.config {
-            if let Some(device_count) = config.custom_params.get("device_count") {
-                if let Some(count) = device_count.as_u64() {
-                    let count = count as u32;
-                    if count > 0 && count <= 1000 {
-                        info!("从配置文件读取优化CPU设备数量: {}", count);
-                        return count;
-                    } else {
-                        warn!("配置文件中的设备数量 {} 超出范围，使用默认值", count);
+    /// 启动周期性收益估算任务（仅启用 `[profitability]` 时）
+    fn start_profitability(&mut self) {
+        let config = &self.cpu_config.profitability;
+        if !config.enabled {
+            return;
+        }
+
+        if let Some(task) = self.profitability_task.take() {
+            task.abort();
+        }
+        let devices = self.devices.clone();
+        let power_meter = self.power_meter.clone();
+        let estimator = self.profitability.clone();
+        let pause_flags = self.pause_flags.clone();
+        let event_bus = self.event_bus.clone();
+        let interval = Duration::from_millis(config.interval_ms.max(100));
+        self.profitability_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                Self::profitability_tick(&devices, &power_meter, &estimator, &pause_flags, &event_bus).await;
+            }
+        }));
+    }
+
+    /// 设置空闲检测使用的系统负载来源，下一次启动核心时生效
+    pub fn set_load_source(&mut self, source: impl LoadSource + 'static) {
+        self.load_source = Some(Arc::new(std::sync::Mutex::new(Box::new(source))));
+    }
+
+    /// 空闲检测统计（当前状态、累计挖矿和暂停时间），未启用空闲检测时返回 `None`
+    pub fn idle_stats(&self) -> Option<IdleStats> {
+        if !self.cpu_config.idle.enabled {
+            return None;
+        }
+        self.idle_detector.read().ok().map(|detector| detector.stats())
+    }
+
+    /// 启动空闲检测任务（仅启用 `[idle]` 时），其他程序占用CPU时暂停所有设备
+    fn start_idle_detection(&mut self) {
+        let config = self.cpu_config.idle.clone();
+        if !config.enabled {
+            return;
+        }
+
+        if let Some(task) = self.idle_task.take() {
+            task.abort();
+        }
+        let source = self.load_source
+            .get_or_insert_with(|| Arc::new(std::sync::Mutex::new(Box::new(SysinfoLoadSource::new()))))
+            .clone();
+        let description = source.lock().map(|source| source.describe()).unwrap_or_default();
+        info!("💤 空闲检测: 其他程序CPU占用超过 {:.0}% 时暂停 ({})",
+              config.max_foreign_load * 100.0, description);
+
+        if let Ok(mut detector) = self.idle_detector.write() {
+            *detector = IdleDetector::new(config.clone());
+        }
+        let detector = self.idle_detector.clone();
+        let devices = self.devices.clone();
+        let pause_flags = self.pause_flags.clone();
+        let event_bus = self.event_bus.clone();
+        let interval = Duration::from_millis(config.sample_interval_ms.max(10));
+        self.idle_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let load = source.lock().ok().and_then(|mut source| source.sample());
+                // 其他原因（时间表、收益、温度、功率）导致没有设备在挖矿的时间不计入挖矿时间
+                let held = pause_flags.reasons().iter().any(|reason| *reason != PauseReason::SystemBusy)
+                    || !Self::any_device_mining(&devices).await;
+                let paused = match detector.write() {
+                    Ok(mut detector) => {
+                        detector.set_held(held);
+                        detector.update(load)
                     }
-                }
+                    Err(_) => continue,
+                };
+                Self::set_paused(&pause_flags, &event_bus, PauseReason::SystemBusy, paused);
+            }
+        }));
+    }
+
+    /// 是否有运行中的设备以非零占空比挖矿（温度、功率和时间表占空比都计入）
+    async fn any_device_mining(devices: &DeviceMap) -> bool {
+        for (_, device) in device_handle::snapshot(devices).await {
+            let device = device.lock().await;
+            if device.is_running() && device.effective_duty_cycle() > 0.0 {
+                return true;
             }
         }
+        false
+    }
 
-        // 3. 使用默认值
-        info!("使用默认优化CPU设备数量: 4");
-        4u32
+    /// 设置挖矿时间表使用的时钟（默认为系统时钟）
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Arc::new(clock);
     }
 
-    /// 启动立即上报的结果收集任务
-    async fn start_result_collection(&self) -> Result<(), CoreError> {
-        let receiver = {
-            let mut receiver_guard = self.result_receiver.lock().await;
-            receiver_guard.take()
+    /// 挖矿时间表的当前状态，未启用时间表或尚未计算时返回 `None`
+    pub fn schedule_state(&self) -> Option<ScheduleState> {
+        self.schedule_state.read().ok()?.clone()
+    }
+
+    /// 立即按当前时钟计算并应用挖矿时间表，未启用时间表时返回 `None`
+    pub async fn evaluate_schedule(&self) -> Option<ScheduleState> {
+        if !self.cpu_config.schedule.enabled {
+            return None;
+        }
+        let schedule = Schedule::from_config(&self.cpu_config.schedule).ok()?;
+        Some(Self::apply_schedule(&self.devices, &schedule, self.clock.as_ref(), &self.schedule_state,
+                                  &self.pause_flags, &self.event_bus).await)
+    }
+
+    /// 按时间表设置设备的占空比：占空比为0时暂停，否则按比例降速
+    async fn apply_schedule(
+        devices: &DeviceMap,
+        schedule: &Schedule,
+        clock: &dyn Clock,
+        schedule_state: &RwLock<Option<ScheduleState>>,
+        pause_flags: &PauseFlags,
+        event_bus: &EventBus,
+    ) -> ScheduleState {
+        let state = schedule.evaluate(clock.now());
+        let duty_cycle = if state.active { state.duty_cycle } else { 1.0 };
+        for (_, device) in device_handle::snapshot(devices).await {
+            device.lock().await.set_schedule_duty_cycle(duty_cycle);
+        }
+        Self::set_paused(pause_flags, event_bus, PauseReason::OutsideSchedule, !state.active);
+
+        let previous = schedule_state.write().ok().and_then(|mut current| current.replace(state.clone()));
+        let changed = previous.map_or(true, |previous| {
+            previous.window != state.window || previous.duty_cycle != state.duty_cycle
+        });
+        if changed {
+            info!("🕒 挖矿时间表: {}，占空比 {:.0}%",
+                  state.window.as_deref().unwrap_or("不在时间段内"), state.duty_cycle * 100.0);
+            event_bus.emit(CORE_EVENT_DEVICE_ID, MiningEventKind::ScheduleChanged {
+                window: state.window.clone(),
+                duty_cycle: state.duty_cycle,
+                active: state.active,
+            });
+        }
+
+        state
+    }
+
+    /// 启动挖矿时间表任务（仅启用 `[schedule]` 时）
+    fn start_schedule(&mut self) {
+        let config = &self.cpu_config.schedule;
+        if !config.enabled {
+            return;
+        }
+        let schedule = match Schedule::from_config(config) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!("挖矿时间表无效，已禁用: {}", e);
+                return;
+            }
         };
 
-        if let Some(mut receiver) = receiver {
-            let collected_results = self.collected_results.clone();
-            let stats = self.stats.clone();
+        if let Some(task) = self.schedule_task.take() {
+            task.abort();
+        }
+        let devices = self.devices.clone();
+        let clock = self.clock.clone();
+        let schedule_state = self.schedule_state.clone();
+        let pause_flags = self.pause_flags.clone();
+        let event_bus = self.event_bus.clone();
+        let interval = Duration::from_millis(config.interval_ms.max(10));
+        self.schedule_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                Self::apply_schedule(&devices, &schedule, clock.as_ref(), &schedule_state, &pause_flags, &event_bus).await;
+            }
+        }));
+    }
 
-            tokio::spawn(async move {
-                while let Some(result) = receiver.recv().await {
-                    // 立即处理收到的结果
-                    debug!("💎 设备 {} 找到解: nonce={:08x}",
-                          result.device_id, result.nonce);
+    /// 设备使用的哈希实现：运行过基准测试时为最快的实现，否则为 `sha2`
+    pub fn hash_implementation(&self) -> HashImplementation {
+        self.hash_benchmark.as_ref().map_or_else(HashImplementation::default, |report| report.selected)
+    }
 
-                    // 更新统计信息
-                    {
-                        let mut stats_guard = stats.write().unwrap();
-                        stats_guard.accepted_work += 1;
-                    }
+    /// 哈希实现基准测试结果，未运行基准测试时返回 `None`
+    pub fn hash_benchmark(&self) -> Option<&BenchmarkReport> {
+        self.hash_benchmark.as_ref()
+    }
 
-                    // 缓存结果供collect_results使用
-                    {
-                        let mut results_guard = collected_results.lock().await;
-                        results_guard.push(result);
-                    }
-                }
-            });
+    /// 批次调优缓存使用的CPU标识：运行过基准测试时沿用测试时的标识，否则重新检测
+    fn cpu_identity(&self) -> CpuIdentity {
+        self.hash_benchmark.as_ref().map_or_else(CpuIdentity::detect, |report| report.cpu.clone())
+    }
 
-            info!("立即上报结果收集任务已启动");
+    /// 检测到的CPU信息（厂商、型号、特性标志和缓存大小）
+    pub fn cpu_info(&self) -> &CpuInfo {
+        &self.cpu_info
+    }
+
+    /// 使用基准测试选出的哈希实现，并把结果记录到核心信息的描述中
+    ///
+    /// 必须在 `initialize` 之前调用，之后创建的设备才会使用选出的实现。
+    pub fn set_hash_benchmark(&mut self, report: BenchmarkReport) {
+        info!("⚡ 哈希实现: {}{}", report.summary(), if report.cached { " (缓存)" } else { "" });
+        self.core_info.description = format!("{} [哈希基准: {}]", self.core_info.description, report.summary());
+        self.hash_benchmark = Some(report);
+    }
+
+    /// 各设备批次大小调优的当前结果（按设备ID排序），未启用调优时为空
+    pub async fn batch_tuning(&self) -> Vec<(u32, TuningResult)> {
+        let mut results = Vec::new();
+        for (device_id, device) in device_handle::snapshot(&self.devices).await {
+            if let Some(result) = device.lock().await.batch_tuning() {
+                results.push((device_id, result));
+            }
         }
+        results
+    }
 
-        Ok(())
+    /// 把已收敛的批次调优结果保存到 `tuning.cache_path`
+    ///
+    /// 返回保存的结果；没有设备收敛或没有配置缓存文件时返回 `Ok(None)`。
+    pub async fn save_batch_tuning(&self) -> Result<Option<TuningCache>, CoreError> {
+        let config = &self.cpu_config.tuning;
+        if !config.enabled {
+            return Ok(None);
+        }
+        let results: Vec<TuningResult> = self.batch_tuning().await.into_iter().map(|(_, result)| result).collect();
+        let threads = device_handle::snapshot(&self.devices).await.len() as u32;
+        let cache = match TuningCache::from_results(config, self.cpu_identity(), threads, &results) {
+            Some(cache) => cache,
+            None => return Ok(None),
+        };
+        if let Ok(mut current) = self.tuning_cache.write() {
+            *current = Some(cache.clone());
+        }
+
+        let path = match config.cache_path {
+            Some(ref path) => path,
+            None => return Ok(None),
+        };
+        cache.save(path).map_err(|e| {
+            CoreError::runtime(format!("保存批次调优结果到 {} 失败: {}", path.display(), e))
+        })?;
+        info!("📐 批次调优结果已保存: 批次 {}，{:.2} MH/s，延迟 {:.1} ms",
+              cache.batch_size, cache.hashrate / 1_000_000.0, cache.latency_ms);
+        Ok(Some(cache))
+    }
+
+    /// 跨重启的累计统计（日志基线加上本次会话），未启用 `[stats_journal]` 时返回 `None`
+    pub async fn lifetime_stats(&self) -> Option<LifetimeStats> {
+        let session = Self::session_counters(&self.devices, &self.retired_totals, &self.share_rejections, &self.power_meter).await;
+        let journal = self.stats_journal.read().ok()?;
+        journal.is_enabled().then(|| journal.lifetime_at(&session, Instant::now()))
+    }
+
+    /// 把累计统计写入 `stats_journal.path`，返回写入的统计；未启用时返回 `Ok(None)`
+    pub async fn save_stats_journal(&self) -> Result<Option<LifetimeStats>, CoreError> {
+        Self::write_stats_journal(&self.devices, &self.retired_totals, &self.share_rejections,
+                                  &self.power_meter, &self.stats_journal).await
+    }
+
+    /// 汇总本次会话的计数：设备统计、已移除设备的统计、核心拒绝的份额和RAPL累计能耗
+    async fn session_counters(
+        devices: &DeviceMap,
+        retired_totals: &RwLock<RetiredTotals>,
+        share_rejections: &RwLock<ShareRejections>,
+        power_meter: &RwLock<Option<PowerMeter>>,
+    ) -> SessionCounters {
+        let mut session = SessionCounters::default();
+        for (_, device) in device_handle::snapshot(devices).await {
+            if let Ok(stats) = device.lock().await.get_stats().await {
+                session.total_hashes += stats.total_hashes;
+                session.accepted_work += stats.accepted_work;
+                session.rejected_work += stats.rejected_work;
+                session.hardware_errors += stats.hardware_errors;
+            }
+        }
+        if let Ok(retired) = retired_totals.read() {
+            session.total_hashes += retired.total_hashes;
+            session.accepted_work += retired.accepted_work;
+            session.rejected_work += retired.rejected_work;
+            session.hardware_errors += retired.hardware_errors;
+        }
+        if let Ok(rejections) = share_rejections.read() {
+            session.accepted_work = session.accepted_work.saturating_sub(rejections.total());
+            session.rejected_work += rejections.total();
+        }
+        session.energy_joules = power_meter.read().ok()
+            .and_then(|meter| meter.as_ref().map(PowerMeter::total_energy_joules))
+            .unwrap_or(0.0);
+        session
+    }
+
+    async fn write_stats_journal(
+        devices: &DeviceMap,
+        retired_totals: &RwLock<RetiredTotals>,
+        share_rejections: &RwLock<ShareRejections>,
+        power_meter: &RwLock<Option<PowerMeter>>,
+        journal: &RwLock<StatsJournal>,
+    ) -> Result<Option<LifetimeStats>, CoreError> {
+        let session = Self::session_counters(devices, retired_totals, share_rejections, power_meter).await;
+        let journal = journal.read().map_err(|e| {
+            CoreError::runtime(format!("Failed to acquire read lock: {}", e))
+        })?;
+        journal.save_at(&session, Instant::now()).map_err(|e| {
+            let path = journal.config().path.as_deref().unwrap_or(std::path::Path::new(""));
+            CoreError::runtime(format!("写入统计日志 {} 失败: {}", path.display(), e))
+        })
+    }
+
+    /// 启动统计日志任务（仅启用 `[stats_journal]` 时），周期性地采样功耗并写入累计统计
+    fn start_stats_journal(&mut self) {
+        let config = &self.cpu_config.stats_journal;
+        if !config.enabled {
+            return;
+        }
+
+        if let Some(task) = self.stats_journal_task.take() {
+            task.abort();
+        }
+        let devices = self.devices.clone();
+        let retired_totals = self.retired_totals.clone();
+        let share_rejections = self.share_rejections.clone();
+        let power_meter = self.power_meter.clone();
+        let journal = self.stats_journal.clone();
+        let interval = Duration::from_millis(config.interval_ms.max(100));
+        self.stats_journal_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // 第一次tick立即返回，跳过以免刚启动就写入
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                // 能耗由功耗采样任务累计，这里只读取
+                match Self::write_stats_journal(&devices, &retired_totals, &share_rejections, &power_meter, &journal).await {
+                    Ok(Some(lifetime)) => debug!("📒 统计日志已写入: 累计 {} 个哈希，{} 个份额",
+                                                 lifetime.total_hashes, lifetime.accepted_work),
+                    Ok(None) => {}
+                    Err(e) => warn!("{}", e),
+                }
+            }
+        }));
     }
 
     /// 启动连续计算模式 - 让所有设备进入高性能连续计算状态
@@ -469,24 +1572,25 @@ impl SoftwareMiningCore {
         }
 
         // 启动所有设备的连续计算模式
-        let mut devices = self.devices.lock().await;
+        let devices = device_handle::snapshot(&self.devices).await;
         let mut success_count = 0;
         let device_count = devices.len();
 
-        for (device_id, device) in devices.iter_mut() {
-            // 尝试将设备转换为SoftwareDevice
-            if let Some(software_device) = device.as_any_mut().downcast_mut::<crate::device::SoftwareDevice>() {
-                match software_device.start_continuous_mining().await {
-                    Ok(()) => {
-                        success_count += 1;
-                        info!("✅ 设备 {} 连续计算模式启动成功", device_id);
-                    }
-                    Err(e) => {
-                        warn!("❌ 设备 {} 连续计算模式启动失败: {}", device_id, e);
-                    }
+        for (device_id, device) in &devices {
+            let mut device = device.lock().await;
+            if !device.is_enabled() {
+                debug!("设备 {} 已禁用，跳过连续计算模式", device_id);
+                continue;
+            }
+
+            match device.start_continuous_mining().await {
+                Ok(()) => {
+                    success_count += 1;
+                    info!("✅ 设备 {} 连续计算模式启动成功", device_id);
+                }
+                Err(e) => {
+                    warn!("❌ 设备 {} 连续计算模式启动失败: {}", device_id, e);
                 }
-            } else {
-                warn!("⚠️ 设备 {} 不是SoftwareDevice类型，跳过连续计算模式", device_id);
             }
         }
 
@@ -535,24 +1639,94 @@ impl MiningCore for SoftwareMiningCore {
 
         // 验证配置
         debug!("验证配置...");
-        self.validate_config(&config)?;
+        self.cpu_config = config::validate_core_config(&config)?;
         debug!("配置验证通过");
 
-        // 初始化性能优化器
-        let mut perf_config = crate::performance::PerformanceConfig::default();
-        let mut optimizer = PerformanceOptimizer::new(perf_config.clone());
-        optimizer.optimize_for_system();
-        perf_config = optimizer.get_config().clone();
-        self.performance_optimizer = Some(optimizer);
+        // 配置有界结果缓冲
+        self.configure_result_buffer(self.cpu_config.result_buffer()).await;
 
-        // 初始化CPU绑定管理器
-        if perf_config.cpu_affinity.enabled {
-            let strategy = CpuAffinityStrategy::Intelligent; // 简化为固定策略
+        // 所有设备共用一个温度管理器，外部温度命令每个周期只运行一次
+        let temperature_manager = TemperatureManager::new(self.cpu_config.temperature.clone());
+        temperature_manager.refresh().await;
+        self.temperature_manager = Some(temperature_manager);
 
+        // 初始化性能优化器
+        let mut optimizer = PerformanceOptimizer::new(crate::performance::PerformanceConfig {
+            power_cap: self.cpu_config.power.cap.clone(),
+            ..Default::default()
+        });
+        optimizer.optimize_for_system();
+        self.performance_optimizer = Some(Arc::new(RwLock::new(optimizer)));
+
+        // 初始化CPU绑定管理器 - 使用配置中的策略
+        let affinity_config = self.cpu_config.affinity.to_cpu_affinity_config();
+        self.cpu_affinity_manager = if !affinity_config.enabled {
+            info!("CPU绑定已在配置中禁用");
+            None
+        } else if cfg!(target_os = "macos") {
             // 在macOS上，当绑定所有核心时，core_affinity可能会挂起。临时禁用它。
-            let cpu_manager = CpuAffinityManager::new(false, strategy);
-            self.cpu_affinity_manager = Some(Arc::new(RwLock::new(cpu_manager)));
             info!("ℹ️ CPU绑定管理器已临时禁用以排查macOS挂起问题");
+            None
+        } else {
+            let cpu_manager = CpuAffinityManager::from_config(&affinity_config);
+            Some(Arc::new(RwLock::new(cpu_manager)))
+        };
+
+        // 初始化RAPL功耗测量
+        let power_meter = PowerMeter::from_config(&self.cpu_config.power);
+        match power_meter {
+            Some(ref meter) => info!("⚡ RAPL功耗测量: {} 个CPU封装", meter.domains().len()),
+            None => debug!("RAPL功耗测量不可用"),
+        }
+        if let Ok(mut current) = self.power_meter.write() {
+            *current = power_meter;
+        }
+
+        // 初始化收益估算器
+        if let Ok(mut estimator) = self.profitability.write() {
+            *estimator = ProfitabilityEstimator::new(self.cpu_config.profitability.clone());
+        }
+
+        // 读取上一次保存的批次调优结果
+        let tuning = &self.cpu_config.tuning;
+        let tuning_cache = match tuning.cache_path {
+            Some(ref path) if tuning.enabled => TuningCache::load(path),
+            _ => None,
+        };
+        // 缓存只适用于同一CPU和同样的线程数（即将创建的设备数）
+        let threads = self.cpu_config.device_count.min(self.usable_cpu_count());
+        let tuning_cache = match tuning_cache {
+            Some(cache) if cache.matches(&self.cpu_identity(), threads) => {
+                info!("📐 使用缓存的批次调优结果: 批次 {} (延迟上限 {} ms)", cache.batch_size, cache.max_latency_ms);
+                Some(cache)
+            }
+            Some(cache) => {
+                info!("📐 批次调优缓存来自其他CPU或线程数 ({}, {} 线程)，重新调优", cache.cpu.model, cache.threads);
+                None
+            }
+            None => None,
+        };
+        if let Ok(mut current) = self.tuning_cache.write() {
+            *current = tuning_cache;
+        }
+
+        // 读取上一次保存的累计统计
+        let journal = StatsJournal::open(self.cpu_config.stats_journal.clone());
+        if journal.is_enabled() && journal.baseline().sessions > 0 {
+            let baseline = journal.baseline();
+            info!("📒 读取累计统计: {} 次会话，{} 个哈希，接受 {} / 拒绝 {}，运行 {:.0} 秒",
+                  baseline.sessions, baseline.total_hashes, baseline.accepted_work,
+                  baseline.rejected_work, baseline.uptime_secs);
+        }
+        if let Ok(mut current) = self.stats_journal.write() {
+            *current = journal;
+        }
+        // 重新初始化时开始新的会话：日志基线已经包含上一次会话的统计，本次会话的计数从0开始
+        if let Ok(mut retired) = self.retired_totals.write() {
+            *retired = RetiredTotals::default();
+        }
+        if let Ok(mut rejections) = self.share_rejections.write() {
+            *rejections = ShareRejections::default();
         }
 
         // 创建设备
@@ -560,14 +1734,16 @@ impl MiningCore for SoftwareMiningCore {
         let devices = self.create_software_devices(&config).await?;
         info!("优化CPU设备创建完成，共创建 {} 个设备", devices.len());
 
-        // 存储设备
+        // 存储设备（重新初始化时替换上一次创建的设备）
         {
             let mut device_map = self.devices.lock().await;
+            device_map.clear();
             for device in devices {
                 let device_id = device.device_id();
-                device_map.insert(device_id, device);
+                device_map.insert(device_id, Arc::new(Mutex::new(device)));
             }
         }
+        self.apply_cpu_budget(&device_handle::snapshot(&self.devices).await).await;
         self.config = Some(config);
         Ok(())
     }
@@ -591,21 +1767,34 @@ impl MiningCore for SoftwareMiningCore {
         self.start_result_collection().await?;
 
         // 启动所有设备 - 🚀 切换到高性能连续计算模式
-        {
-            let mut devices = self.devices.lock().await;
-            for (device_id, device) in devices.iter_mut() {
-                // 使用 as_any_mut 和 downcast_mut 来安全地调用具体类型的实现
-                if let Some(sw_device) = device.as_any_mut().downcast_mut::<SoftwareDevice>() {
-                    if let Err(e) = sw_device.start_continuous_mining().await {
-                        error!("启动设备 {} 的连续计算模式失败: {}", device_id, e);
-                    }
-                } else {
-                    error!("设备 {} 不是一个有效的 SoftwareDevice 实例", device_id);
-                }
+        for (device_id, device) in device_handle::snapshot(&self.devices).await {
+            let mut device = device.lock().await;
+            if !device.is_enabled() {
+                debug!("设备 {} 已禁用，跳过启动", device_id);
+                continue;
+            }
+
+            if let Err(e) = device.start_continuous_mining().await {
+                error!("启动设备 {} 的连续计算模式失败: {}", device_id, e);
+                self.event_bus.emit(device_id, MiningEventKind::DeviceFailed {
+                    reason: e.to_string(),
+                });
             }
         }
 
+        self.start_load_balancer();
+        self.start_thermal_governor();
+        self.start_power_sampler();
+        self.start_power_cap();
+        self.start_profitability();
+        self.start_idle_detection();
+        self.start_schedule();
+        self.start_stats_journal();
+
         self.start_time = Some(SystemTime::now());
+        if let Ok(mut journal) = self.stats_journal.write() {
+            journal.mark_started_at(Instant::now());
+        }
         info!("优化CPU挖矿核心启动完成 - 🚀 已切换到高性能连续计算模式");
         Ok(())
     }
@@ -621,16 +1810,55 @@ impl MiningCore for SoftwareMiningCore {
             *running = false;
         }
 
+        if let Some(task) = self.load_balancer_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.thermal_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.power_sampler_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.power_cap_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.profitability_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.idle_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.schedule_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.stats_journal_task.take() {
+            task.abort();
+        }
+
         // 停止所有设备
-        {
-            let mut devices = self.devices.lock().await;
-            for (device_id, device) in devices.iter_mut() {
-                if let Err(e) = device.stop().await {
-                    error!("停止设备 {} 失败: {}", device_id, e);
-                }
+        for (device_id, device) in device_handle::snapshot(&self.devices).await {
+            if let Err(e) = device.lock().await.stop().await {
+                error!("停止设备 {} 失败: {}", device_id, e);
             }
         }
 
+        // 保存批次调优结果供下一次启动使用
+        if let Err(e) = self.save_batch_tuning().await {
+            warn!("{}", e);
+        }
+
+        // 写入累计统计，重启后继续累加
+        if let Ok(mut journal) = self.stats_journal.write() {
+            journal.mark_stopped_at(Instant::now());
+        }
+        match self.save_stats_journal().await {
+            Ok(Some(lifetime)) => info!("📒 累计统计已保存: {} 个哈希，接受 {} / 拒绝 {}，运行 {:.0} 秒",
+                                        lifetime.total_hashes, lifetime.accepted_work,
+                                        lifetime.rejected_work, lifetime.uptime_secs),
+            Ok(None) => {}
+            Err(e) => warn!("{}", e),
+        }
+
         info!("优化CPU挖矿核心已停止");
         Ok(())
     }
@@ -649,26 +1877,25 @@ impl MiningCore for SoftwareMiningCore {
         debug!("扫描优化CPU设备");
 
         // 如果设备已经创建，返回现有设备信息
-        let devices = self.devices.lock().await;
+        let devices = device_handle::snapshot(&self.devices).await;
         if !devices.is_empty() {
             let mut device_infos = Vec::new();
-            for device in devices.values() {
-                match device.get_info().await {
+            for (_, device) in devices {
+                match device.lock().await.get_info().await {
                     Ok(info) => device_infos.push(info),
                     Err(e) => warn!("获取设备信息失败: {}", e),
                 }
             }
             return Ok(device_infos);
         }
-        drop(devices);
 
         // 如果设备未创建，根据配置生成应该创建的设备信息
-        let requested_device_count = self.get_device_count_from_config();
+        let requested_device_count = self.cpu_config.device_count;
 
-        // CPU挖矿优化：限制设备数量为CPU核心数
-        let cpu_cores = num_cpus::get() as u32;
+        // CPU挖矿优化：限制设备数量为可用CPU核心数
+        let cpu_cores = self.usable_cpu_count();
         let device_count = if requested_device_count > cpu_cores {
-            info!("⚠️  环境变量设置的设备数量 {} 超过CPU核心数 {}，自动限制为CPU核心数",
+            info!("⚠️  请求的设备数量 {} 超过可用CPU核心数 {}，自动限制为可用核心数",
                   requested_device_count, cpu_cores);
             cpu_cores
         } else {
@@ -697,39 +1924,27 @@ impl MiningCore for SoftwareMiningCore {
 
         let device_config = cgminer_core::DeviceConfig::default();
 
-        // 从配置中获取参数，如果没有配置则使用合理的默认值
-        let default_config = CoreConfig::default();
-        let config = self.config.as_ref().unwrap_or(&default_config);
-
-        let target_hashrate = config.custom_params
-            .get("max_hashrate")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(2_000_000_000.0); // 2 GH/s 默认算力
-
-        let error_rate = config.custom_params
-            .get("error_rate")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.01); // 1% 错误率
-
-        let batch_size = config.custom_params
-            .get("batch_size")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(1000) as u32; // 批次大小
+        let params = &self.cpu_config;
+        let target_hashrate = params.throttling.apply(params.max_hashrate);
 
         let device = SoftwareDevice::new(
             device_info,
             device_config,
             target_hashrate,
-            error_rate,
-            batch_size,
+            params.error_rate,
+            params.batch_size,
         ).await?;
+        device.set_hashrate_cap(params.throttling.max_device_hashrate);
 
         Ok(Box::new(device))
     }
 
-    /// 获取所有设备
+    /// 获取所有设备 - 返回共享核心设备表的句柄
     async fn get_devices(&self) -> Result<Vec<Box<dyn MiningDevice>>, CoreError> {
-        Err(CoreError::runtime("get_devices 方法暂未实现"))
+        let handles = self.device_handles().await;
+        Ok(handles.into_iter()
+            .map(|handle| Box::new(handle) as Box<dyn MiningDevice>)
+            .collect())
     }
 
     /// 获取设备数量
@@ -740,12 +1955,33 @@ impl MiningCore for SoftwareMiningCore {
 
     /// 提交工作到所有设备
     async fn submit_work(&mut self, work: std::sync::Arc<Work>) -> Result<(), CoreError> {
-        let mut devices = self.devices.lock().await;
+        // 记录最近的工作，供结果收集任务验证份额
+        {
+            let mut recent_work = self.recent_work.lock().await;
+            if recent_work.len() >= RECENT_WORK_LIMIT {
+                recent_work.pop_front();
+            }
+            recent_work.push_back(Arc::clone(&work));
+        }
+
+        // 从区块头的nBits推算全网难度，供收益估算使用
+        if let Some(difficulty) = profitability::difficulty_from_header(&work.header[..]) {
+            if let Ok(mut estimator) = self.profitability.write() {
+                estimator.observe_difficulty(difficulty);
+            }
+        }
+
+        let devices = device_handle::snapshot(&self.devices).await;
         let device_count = devices.len();
         let mut success_count = 0;
         let mut failed_devices = Vec::new();
 
-        for (device_id, device) in devices.iter_mut() {
+        for (device_id, device) in &devices {
+            let mut device = device.lock().await;
+            if !device.is_enabled() {
+                continue;
+            }
+
             match device.submit_work(Arc::clone(&work)).await {
                 Ok(()) => {
                     success_count += 1;
@@ -770,9 +2006,8 @@ impl MiningCore for SoftwareMiningCore {
 
     /// 收集所有设备的挖矿结果 - 从缓存获取立即上报的结果
     async fn collect_results(&mut self) -> Result<Vec<MiningResult>, CoreError> {
-        // 从缓存中获取已经立即上报的结果
-        let mut results_guard = self.collected_results.lock().await;
-        let results = results_guard.drain(..).collect::<Vec<_>>();
+        // 从缓存中获取已经立即上报的结果，同时释放背压
+        let results = self.collected_results.drain();
 
         if !results.is_empty() {
             debug!("🎯 从缓存收集到 {} 个结果", results.len());
@@ -792,11 +2027,11 @@ impl MiningCore for SoftwareMiningCore {
 
     /// 健康检查
     async fn health_check(&self) -> Result<bool, CoreError> {
-        let devices = self.devices.lock().await;
+        let devices = device_handle::snapshot(&self.devices).await;
         let mut healthy_devices = 0;
 
-        for device in devices.values() {
-            match device.health_check().await {
+        for (_, device) in &devices {
+            match device.lock().await.health_check().await {
                 Ok(true) => healthy_devices += 1,
                 Ok(false) => {},
                 Err(e) => warn!("设备健康检查失败: {}", e),
@@ -810,78 +2045,12 @@ impl MiningCore for SoftwareMiningCore {
 
     /// 验证配置
     fn validate_config(&self, config: &CoreConfig) -> Result<(), CoreError> {
-        if config.name.is_empty() {
-            return Err(CoreError::config("核心名称不能为空"));
-        }
-
-        // 验证设备数量
-        if let Some(device_count) = config.custom_params.get("device_count") {
-            if let Some(count) = device_count.as_u64() {
-                if count == 0 {
-                    return Err(CoreError::config("软算法设备数量不能为0"));
-                }
-                if count > 1000 {
-                    return Err(CoreError::config("软算法设备数量不能超过1000"));
-                }
-
-                // 对于大量设备的警告
-                if count > 32 {
-                    warn!("配置了 {} 个软算法设备，这可能会消耗大量系统资源", count);
-                }
-
-                // 检查系统资源
-                if count > 64 {
-                    let cpu_count = num_cpus::get();
-                    if count as usize > cpu_count * 4 {
-                        warn!("设备数量 ({}) 远超CPU核心数 ({})，可能影响性能", count, cpu_count);
-                    }
-                }
-            }
-        }
-
-        // 验证算力配置
-        if let Some(min_hashrate) = config.custom_params.get("min_hashrate") {
-            if let Some(max_hashrate) = config.custom_params.get("max_hashrate") {
-                if let (Some(min), Some(max)) = (min_hashrate.as_f64(), max_hashrate.as_f64()) {
-                    if min >= max {
-                        return Err(CoreError::config("最小算力不能大于等于最大算力"));
-                    }
-                    if min <= 0.0 || max <= 0.0 {
-                        return Err(CoreError::config("算力值必须大于0"));
-                    }
-                }
-            }
-        }
-
-        // 验证错误率
-        if let Some(error_rate) = config.custom_params.get("error_rate") {
-            if let Some(rate) = error_rate.as_f64() {
-                if rate < 0.0 || rate > 1.0 {
-                    return Err(CoreError::config("错误率必须在0.0到1.0之间"));
-                }
-            }
-        }
-
-        Ok(())
+        config::validate_core_config(config).map(|_| ())
     }
 
     /// 获取默认配置
     fn default_config(&self) -> CoreConfig {
-        use std::collections::HashMap;
-
-        let mut custom_params = HashMap::new();
-        custom_params.insert("device_count".to_string(), serde_json::Value::Number(serde_json::Number::from(4)));
-        custom_params.insert("min_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(1_000_000_000.0).unwrap()));
-        custom_params.insert("max_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(5_000_000_000.0).unwrap()));
-        custom_params.insert("error_rate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(0.01).unwrap()));
-        custom_params.insert("batch_size".to_string(), serde_json::Value::Number(serde_json::Number::from(1000)));
-
-        CoreConfig {
-            name: "software-core".to_string(),
-            enabled: true,
-            devices: vec![cgminer_core::DeviceConfig::default(); 4],
-            custom_params,
-        }
+        CpuCoreConfig::default().to_core_config("software-core")
     }
 
     /// 关闭核心
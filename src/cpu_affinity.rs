@@ -35,12 +35,30 @@
 //! CpuAffinityStrategy:
 //! ├── RoundRobin        # 轮询: 设备0→核心0, 设备1→核心1, ...
 //! ├── Manual(mapping)   # 手动: 用户指定映射关系
-//! ├── PerformanceFirst  # 性能: 优先使用前50%核心
+//! ├── PerformanceFirst  # 性能: 只使用性能核心 (P核)
 //! ├── PhysicalCoresOnly # 物理: 跳过超线程，只用物理核心
-//! ├── Intelligent       # 智能: 基于CPU数量自动选择策略
+//! ├── Intelligent       # 智能: P核主线程 → E核 → 超线程
 //! └── LoadBalanced      # 负载: 动态监控CPU使用率
 //! ```
 //!
+//! `PerformanceFirst`、`PhysicalCoresOnly` 和 `Intelligent` 在Linux上依据
+//! [`crate::topology::CpuTopology`] 读取的真实拓扑分配核心，并优先选择尚未被
+//! 其他设备占用的核心；无法读取拓扑时回退到基于核心编号的简化分配。
+//!
+//! 在多NUMA节点系统上，除 `Manual` 外的策略都会先选择已分配设备最少的节点，
+//! 再在该节点的候选核心中分配，使设备均匀分布到各个节点。
+//!
+//! `LoadBalanced` 初始分配与 `RoundRobin` 相同；核心启动后由
+//! [`crate::load_balancer`] 在后台采样每个CPU的外部负载，通过
+//! [`CpuAffinityManager::migrate_device`] 把设备迁移到空闲核心，
+//! 工作线程在下一个批次通过 [`CpuAffinityManager::take_rebind_request`] 重新绑定。
+//!
+//! ### 预留核心
+//! [`CoreReservation`] 用于与其他工作负载共享的机器：排除指定的核心，并额外留出
+//! 若干空闲核心。可用核心先与进程继承的CPU掩码（`sched_getaffinity`，包含cgroup
+//! cpuset限制）求交集，再应用预留；所有策略都只在剩余核心中分配，核心创建设备时
+//! 也以剩余核心数作为设备数量上限。
+//!
 //! ### 配置和统计
 //! - [`CpuAffinityConfig`]: 配置构建器，提供便捷的配置方法
 //! - [`CpuAffinityStats`]: 详细的绑定状态和统计信息
@@ -94,7 +112,13 @@
 //! 4. **监控状态**: 定期检查绑定状态和性能统计
 //! 5. **容错处理**: 绑定失败时应有适当的降级处理
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Mutex;
+use crate::load_balancer::WorkerThread;
+use crate::numa::NumaTopology;
+use crate::topology::{parse_cpu_list, CpuTopology};
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn, debug};
 use core_affinity::{CoreId, get_core_ids, set_for_current};
 
@@ -109,6 +133,18 @@ pub struct CpuAffinityManager {
     enabled: bool,
     /// CPU绑定策略
     strategy: CpuAffinityStrategy,
+    /// 每个设备最近一次线程绑定的结果
+    binding_results: Mutex<HashMap<u32, DeviceBindingStatus>>,
+    /// 系统CPU拓扑（仅Linux可用）
+    topology: Option<CpuTopology>,
+    /// NUMA拓扑（仅Linux可用）
+    numa: Option<NumaTopology>,
+    /// 设备挖矿工作线程的操作系统线程ID（仅Linux）
+    worker_threads: Mutex<HashMap<u32, i32>>,
+    /// 已迁移、等待工作线程重新绑定的设备
+    pending_rebinds: Mutex<HashSet<u32>>,
+    /// 负载均衡迁移次数
+    migrations: u64,
 }
 
 /// CPU绑定策略
@@ -116,7 +152,7 @@ pub struct CpuAffinityManager {
 pub enum CpuAffinityStrategy {
     /// 轮询分配：按顺序将设备分配到不同的CPU核心
     RoundRobin,
-    /// 手动指定：手动指定每个设备的CPU核心
+    /// 手动指定：手动指定每个设备的CPU核心（设备ID → 逻辑CPU编号）
     Manual(HashMap<u32, usize>),
     /// 性能核心优先：优先使用性能核心（在支持的系统上）
     PerformanceFirst,
@@ -131,11 +167,74 @@ pub enum CpuAffinityStrategy {
 impl CpuAffinityManager {
     /// 创建新的CPU绑定管理器
     pub fn new(enabled: bool, strategy: CpuAffinityStrategy) -> Self {
-        let available_cores = get_core_ids().unwrap_or_else(|| {
+        let mut available_cores = get_core_ids().unwrap_or_else(|| {
             warn!("无法获取系统CPU核心信息，CPU绑定功能将被禁用");
             Vec::new()
         });
 
+        // 只使用进程继承的CPU掩码中的核心
+        if let Some(allowed) = process_allowed_cpus() {
+            available_cores.retain(|core| allowed.contains(&core.id));
+        }
+
+        Self::with_cores(enabled, strategy, available_cores, CpuTopology::detect())
+            .with_numa(NumaTopology::detect())
+    }
+
+    /// 从绑定配置创建管理器（应用预留核心）
+    pub fn from_config(config: &CpuAffinityConfig) -> Self {
+        Self::new(config.enabled, config.strategy.clone()).with_reservation(&config.reservation)
+    }
+
+    /// 应用预留核心，从可用核心中移除排除的核心和留空的核心
+    pub fn with_reservation(mut self, reservation: &CoreReservation) -> Self {
+        if reservation.is_empty() {
+            return self;
+        }
+
+        let ids = self.available_core_ids();
+        let usable = reservation.apply(&ids);
+        info!("预留核心后可用CPU核心: {:?} (排除 {:?}，留空 {} 个)",
+              usable, reservation.excluded_cores, reservation.headroom);
+        self.available_cores.retain(|core| usable.contains(&core.id));
+
+        if self.available_cores.is_empty() && self.enabled {
+            warn!("预留核心后没有可用的CPU核心，CPU绑定功能将被禁用");
+            self.enabled = false;
+        }
+        self
+    }
+
+    /// 使用给定的逻辑CPU编号创建管理器（不读取系统拓扑）
+    pub fn with_core_ids(enabled: bool, strategy: CpuAffinityStrategy, core_ids: Vec<usize>) -> Self {
+        let available_cores = core_ids.into_iter().map(|id| CoreId { id }).collect();
+        Self::with_cores(enabled, strategy, available_cores, None)
+    }
+
+    /// 设置NUMA拓扑
+    pub fn with_numa(mut self, numa: Option<NumaTopology>) -> Self {
+        if let Some(ref numa) = numa {
+            if numa.is_numa() {
+                info!("检测到 {} 个NUMA节点，设备将均匀分布到各节点", numa.node_count());
+            }
+        }
+        self.numa = numa;
+        self
+    }
+
+    /// 使用给定的CPU拓扑创建管理器，可用核心为拓扑中的全部逻辑CPU
+    pub fn with_topology(enabled: bool, strategy: CpuAffinityStrategy, topology: CpuTopology) -> Self {
+        let available_cores = topology.cpus().iter().map(|cpu| CoreId { id: cpu.id }).collect();
+        Self::with_cores(enabled, strategy, available_cores, Some(topology))
+    }
+
+    fn with_cores(
+        enabled: bool,
+        strategy: CpuAffinityStrategy,
+        available_cores: Vec<CoreId>,
+        topology: Option<CpuTopology>,
+    ) -> Self {
+
         info!("系统检测到 {} 个CPU核心", available_cores.len());
 
         let is_enabled = enabled && !available_cores.is_empty();
@@ -155,6 +254,12 @@ impl CpuAffinityManager {
             device_core_mapping: HashMap::new(),
             enabled: is_enabled,
             strategy,
+            binding_results: Mutex::new(HashMap::new()),
+            topology,
+            numa: None,
+            worker_threads: Mutex::new(HashMap::new()),
+            pending_rebinds: Mutex::new(HashSet::new()),
+            migrations: 0,
         }
     }
 
@@ -178,6 +283,16 @@ impl CpuAffinityManager {
         self.available_cores.len()
     }
 
+    /// 获取可用的CPU核心ID
+    pub fn available_core_ids(&self) -> Vec<usize> {
+        self.available_cores.iter().map(|core| core.id).collect()
+    }
+
+    /// 获取绑定策略
+    pub fn strategy(&self) -> &CpuAffinityStrategy {
+        &self.strategy
+    }
+
     /// 为设备分配CPU核心
     pub fn assign_cpu_core(&mut self, device_id: u32) -> Option<CoreId> {
         if !self.enabled {
@@ -189,6 +304,20 @@ impl CpuAffinityManager {
             return None;
         }
 
+        if let Some(core_id) = self.assign_numa_local_core(device_id) {
+            self.device_core_mapping.insert(device_id, core_id);
+            info!("设备 {} 分配到NUMA节点 {:?} 的CPU核心 {:?}",
+                  device_id, self.device_node(device_id), core_id);
+            return Some(core_id);
+        }
+
+        if let Some(candidates) = self.topology_candidates() {
+            let core_id = self.pick_unused_core(device_id, &candidates);
+            self.device_core_mapping.insert(device_id, core_id);
+            info!("设备 {} 按CPU拓扑分配到CPU核心 {:?}", device_id, core_id);
+            return Some(core_id);
+        }
+
         let core_id = match &self.strategy {
             CpuAffinityStrategy::RoundRobin => {
                 // 轮询分配
@@ -196,12 +325,12 @@ impl CpuAffinityManager {
                 self.available_cores[index]
             }
             CpuAffinityStrategy::Manual(mapping) => {
-                // 手动指定
-                if let Some(&core_index) = mapping.get(&device_id) {
-                    if core_index < self.available_cores.len() {
-                        self.available_cores[core_index]
+                // 手动指定：映射中的值是逻辑CPU编号，不是可用核心列表的索引
+                if let Some(&cpu) = mapping.get(&device_id) {
+                    if let Some(&core) = self.available_cores.iter().find(|core| core.id == cpu) {
+                        core
                     } else {
-                        warn!("设备 {} 指定的CPU核心索引 {} 超出范围，使用轮询分配", device_id, core_index);
+                        warn!("设备 {} 指定的CPU {} 不可用（已预留或超出范围），使用轮询分配", device_id, cpu);
                         let index = (device_id as usize) % self.available_cores.len();
                         self.available_cores[index]
                     }
@@ -263,35 +392,216 @@ impl CpuAffinityManager {
         Some(core_id)
     }
 
+    /// 按当前策略和CPU拓扑计算候选核心（按偏好排序）
+    ///
+    /// 没有拓扑信息或策略不依赖拓扑时返回 `None`。
+    fn topology_candidates(&self) -> Option<Vec<CoreId>> {
+        let topology = self.topology.as_ref()?;
+
+        let ordered = topology.preferred_order();
+        let candidates: Vec<usize> = match &self.strategy {
+            CpuAffinityStrategy::PerformanceFirst => {
+                let performance_cpus = topology.performance_cpus();
+                ordered.into_iter().filter(|cpu| performance_cpus.contains(cpu)).collect()
+            }
+            CpuAffinityStrategy::PhysicalCoresOnly => {
+                let physical_cores = topology.physical_cores();
+                ordered.into_iter().filter(|cpu| physical_cores.contains(cpu)).collect()
+            }
+            CpuAffinityStrategy::Intelligent => ordered,
+            _ => return None,
+        };
+
+        let candidates: Vec<CoreId> = candidates.into_iter()
+            .filter(|cpu| self.available_cores.iter().any(|core| core.id == *cpu))
+            .map(|id| CoreId { id })
+            .collect();
+
+        if candidates.is_empty() {
+            None
+        } else {
+            Some(candidates)
+        }
+    }
+
+    /// 选择第一个未被其他设备占用的候选核心，全部占用时按设备ID轮询
+    fn pick_unused_core(&self, device_id: u32, candidates: &[CoreId]) -> CoreId {
+        let used: Vec<CoreId> = self.device_core_mapping.iter()
+            .filter(|(&other, _)| other != device_id)
+            .map(|(_, &core)| core)
+            .collect();
+
+        candidates.iter()
+            .find(|core| !used.contains(core))
+            .copied()
+            .unwrap_or(candidates[device_id as usize % candidates.len()])
+    }
+
+    /// 多NUMA节点时，在已分配设备最少的节点上选择核心
+    fn assign_numa_local_core(&self, device_id: u32) -> Option<CoreId> {
+        let numa = self.numa.as_ref().filter(|numa| numa.is_numa())?;
+        if matches!(self.strategy, CpuAffinityStrategy::Manual(_)) {
+            return None;
+        }
+
+        let candidates = self.topology_candidates().unwrap_or_else(|| self.available_cores.clone());
+
+        let node_id = numa.nodes().iter()
+            .filter(|node| candidates.iter().any(|core| node.cpus.contains(&core.id)))
+            .map(|node| {
+                let devices_on_node = self.device_core_mapping.iter()
+                    .filter(|(&other, core)| other != device_id && node.cpus.contains(&core.id))
+                    .count();
+                (devices_on_node, node.id)
+            })
+            .min()
+            .map(|(_, node_id)| node_id)?;
+
+        let local: Vec<CoreId> = candidates.into_iter()
+            .filter(|core| numa.node_of_cpu(core.id) == Some(node_id))
+            .collect();
+        Some(self.pick_unused_core(device_id, &local))
+    }
+
+    /// 设备所在的NUMA节点
+    pub fn device_node(&self, device_id: u32) -> Option<u32> {
+        let core_id = self.get_device_core(device_id)?;
+        self.numa.as_ref()?.node_of_cpu(core_id.id)
+    }
+
+    /// 获取NUMA拓扑
+    pub fn numa(&self) -> Option<&NumaTopology> {
+        self.numa.as_ref()
+    }
+
+    /// 获取CPU拓扑
+    pub fn topology(&self) -> Option<&CpuTopology> {
+        self.topology.as_ref()
+    }
+
     /// 获取设备的CPU核心分配
     pub fn get_device_core(&self, device_id: u32) -> Option<CoreId> {
         self.device_core_mapping.get(&device_id).copied()
     }
 
-    /// 为当前线程设置CPU绑定
+    /// 手动将设备重新分配到指定的CPU（逻辑CPU编号），工作线程在下一个批次重新绑定
+    pub fn set_device_core(&mut self, device_id: u32, cpu: usize) -> Result<CoreId, String> {
+        let core_id = self.available_cores.iter()
+            .find(|core| core.id == cpu)
+            .copied()
+            .ok_or_else(|| format!("CPU核心 {} 不可用", cpu))?;
+
+        self.device_core_mapping.insert(device_id, core_id);
+        if let Ok(mut pending) = self.pending_rebinds.lock() {
+            pending.insert(device_id);
+        }
+        info!("设备 {} 重新分配到CPU核心 {:?}", device_id, core_id);
+        Ok(core_id)
+    }
+
+    /// 负载均衡迁移：把设备移到指定的CPU核心，工作线程在下一个批次重新绑定
+    pub fn migrate_device(&mut self, device_id: u32, core_id: usize) -> Result<(), String> {
+        let core = self.available_cores.iter()
+            .find(|core| core.id == core_id)
+            .copied()
+            .ok_or_else(|| format!("CPU核心 {} 不可用", core_id))?;
+        if !self.device_core_mapping.contains_key(&device_id) {
+            return Err(format!("设备 {} 没有分配CPU核心", device_id));
+        }
+
+        self.device_core_mapping.insert(device_id, core);
+        if let Ok(mut pending) = self.pending_rebinds.lock() {
+            pending.insert(device_id);
+        }
+        self.migrations += 1;
+        Ok(())
+    }
+
+    /// 取走设备的重新绑定请求，返回是否需要重新绑定
+    pub fn take_rebind_request(&self, device_id: u32) -> bool {
+        self.pending_rebinds.lock()
+            .map(|mut pending| pending.remove(&device_id))
+            .unwrap_or(false)
+    }
+
+    /// 已注册的挖矿工作线程及其当前核心
+    pub fn worker_threads(&self) -> Vec<WorkerThread> {
+        let threads = match self.worker_threads.lock() {
+            Ok(threads) => threads,
+            Err(_) => return Vec::new(),
+        };
+        let mut workers: Vec<WorkerThread> = threads.iter()
+            .filter_map(|(&device_id, &tid)| {
+                let core = self.get_device_core(device_id)?.id;
+                Some(WorkerThread { device_id, tid, core })
+            })
+            .collect();
+        workers.sort_by_key(|worker| worker.device_id);
+        workers
+    }
+
+    /// 释放设备的CPU核心分配
+    pub fn release_device(&mut self, device_id: u32) -> Option<CoreId> {
+        if let Ok(mut results) = self.binding_results.lock() {
+            results.remove(&device_id);
+        }
+        if let Ok(mut threads) = self.worker_threads.lock() {
+            threads.remove(&device_id);
+        }
+        if let Ok(mut pending) = self.pending_rebinds.lock() {
+            pending.remove(&device_id);
+        }
+        self.device_core_mapping.remove(&device_id)
+    }
+
+    /// 为当前线程设置CPU绑定，并记录该设备的绑定结果
+    ///
+    /// 必须在设备的挖矿工作线程上调用。
     pub fn bind_current_thread(&self, device_id: u32) -> Result<(), String> {
         if !self.enabled {
             debug!("CPU绑定已禁用，跳过线程绑定");
             return Ok(());
         }
 
-        if let Some(core_id) = self.get_device_core(device_id) {
-            match set_for_current(core_id) {
-                true => {
-                    info!("线程成功绑定到CPU核心 {:?} (设备 {})", core_id, device_id);
-                    Ok(())
-                }
-                false => {
-                    let error_msg = format!("无法将线程绑定到CPU核心 {:?} (设备 {})", core_id, device_id);
-                    warn!("{}", error_msg);
-                    Err(error_msg)
-                }
+        let core_id = self.get_device_core(device_id);
+        let result = match core_id {
+            Some(core_id) if set_for_current(core_id) => {
+                info!("线程成功绑定到CPU核心 {:?} (设备 {})", core_id, device_id);
+                Ok(())
             }
-        } else {
-            let error_msg = format!("设备 {} 没有分配CPU核心", device_id);
-            warn!("{}", error_msg);
-            Err(error_msg)
+            Some(core_id) => {
+                let error_msg = format!("无法将线程绑定到CPU核心 {:?} (设备 {})", core_id, device_id);
+                warn!("{}", error_msg);
+                Err(error_msg)
+            }
+            None => {
+                let error_msg = format!("设备 {} 没有分配CPU核心", device_id);
+                warn!("{}", error_msg);
+                Err(error_msg)
+            }
+        };
+
+        let status = DeviceBindingStatus {
+            device_id,
+            core_id: core_id.map(|core| core.id),
+            success: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+            thread_name: std::thread::current().name().map(str::to_string),
+        };
+        if let Ok(mut results) = self.binding_results.lock() {
+            results.insert(device_id, status);
         }
+        #[cfg(target_os = "linux")]
+        if let Ok(mut threads) = self.worker_threads.lock() {
+            threads.insert(device_id, current_tid());
+        }
+
+        result
+    }
+
+    /// 获取设备最近一次线程绑定的结果
+    pub fn binding_status(&self, device_id: u32) -> Option<DeviceBindingStatus> {
+        self.binding_results.lock().ok()?.get(&device_id).cloned()
     }
 
     /// 显示CPU绑定状态
@@ -318,17 +628,111 @@ impl CpuAffinityManager {
 
     /// 获取CPU绑定统计信息
     pub fn get_affinity_stats(&self) -> CpuAffinityStats {
+        let mut device_bindings: Vec<DeviceBindingStatus> = self.binding_results.lock()
+            .map(|results| results.values().cloned().collect())
+            .unwrap_or_default();
+        device_bindings.sort_by_key(|status| status.device_id);
+
         CpuAffinityStats {
             total_cpu_cores: Self::get_cpu_count(),
-            physical_cpu_cores: Self::get_physical_cpu_count(),
+            physical_cpu_cores: self.topology.as_ref()
+                .map(|topology| topology.physical_core_count())
+                .unwrap_or_else(Self::get_physical_cpu_count),
             available_cores: self.available_core_count(),
             enabled: self.enabled,
             bound_devices: self.device_core_mapping.len(),
             strategy: self.strategy.clone(),
+            successful_bindings: device_bindings.iter().filter(|status| status.success).count(),
+            failed_bindings: device_bindings.iter().filter(|status| !status.success).count(),
+            device_bindings,
+            migrations: self.migrations,
         }
     }
 }
 
+/// 预留核心配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoreReservation {
+    /// 永不使用的逻辑CPU编号
+    pub excluded_cores: Vec<usize>,
+    /// 在排除之外额外留空的核心数量（从编号最小的核心开始留空）
+    pub headroom: usize,
+}
+
+impl CoreReservation {
+    /// 是否没有任何预留
+    pub fn is_empty(&self) -> bool {
+        self.excluded_cores.is_empty() && self.headroom == 0
+    }
+
+    /// 从给定的CPU中去掉预留核心，返回剩余的CPU（保持原顺序）
+    pub fn apply(&self, cpus: &[usize]) -> Vec<usize> {
+        let remaining: Vec<usize> = cpus.iter()
+            .copied()
+            .filter(|cpu| !self.excluded_cores.contains(cpu))
+            .collect();
+
+        // 编号小的核心通常承担中断和系统任务，优先留给其他工作负载
+        let mut by_id = remaining.clone();
+        by_id.sort_unstable();
+        let left_free: Vec<usize> = by_id.into_iter().take(self.headroom).collect();
+
+        remaining.into_iter().filter(|cpu| !left_free.contains(cpu)).collect()
+    }
+}
+
+/// 调用线程的内核线程ID（仅Linux）
+#[cfg(target_os = "linux")]
+pub(crate) fn current_tid() -> libc::pid_t {
+    // SAFETY: gettid 没有参数也不会失败
+    unsafe { libc::syscall(libc::SYS_gettid) as libc::pid_t }
+}
+
+/// 解析 `/proc/<pid>/status` 中的 `Cpus_allowed_list`
+pub fn parse_cpus_allowed(status: &str) -> Option<Vec<usize>> {
+    let list = status.lines().find_map(|line| line.strip_prefix("Cpus_allowed_list:"))?;
+    let cpus = parse_cpu_list(list.trim());
+    if cpus.is_empty() {
+        None
+    } else {
+        Some(cpus)
+    }
+}
+
+/// 进程继承的CPU掩码（仅Linux）
+///
+/// 读取主线程的掩码而不是当前线程，避免在已绑定的挖矿线程上调用时只得到一个核心。
+/// 掩码已包含cgroup cpuset的限制。
+pub fn process_allowed_cpus() -> Option<Vec<usize>> {
+    if cfg!(target_os = "linux") {
+        fs::read_to_string("/proc/self/status").ok().and_then(|status| parse_cpus_allowed(&status))
+    } else {
+        None
+    }
+}
+
+/// 应用进程CPU掩码和预留后可用于挖矿的逻辑CPU
+pub fn usable_cpus(reservation: &CoreReservation) -> Vec<usize> {
+    let cpus = process_allowed_cpus().unwrap_or_else(|| (0..num_cpus::get()).collect());
+    reservation.apply(&cpus)
+}
+
+/// 单个设备的线程绑定结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceBindingStatus {
+    /// 设备ID
+    pub device_id: u32,
+    /// 绑定的CPU核心ID
+    pub core_id: Option<usize>,
+    /// 是否绑定成功
+    pub success: bool,
+    /// 失败原因
+    pub error: Option<String>,
+    /// 执行绑定的线程名称
+    pub thread_name: Option<String>,
+}
+
 /// CPU绑定统计信息
 #[derive(Debug, Clone)]
 pub struct CpuAffinityStats {
@@ -344,6 +748,14 @@ pub struct CpuAffinityStats {
     pub bound_devices: usize,
     /// 绑定策略
     pub strategy: CpuAffinityStrategy,
+    /// 绑定成功的设备数量
+    pub successful_bindings: usize,
+    /// 绑定失败的设备数量
+    pub failed_bindings: usize,
+    /// 每个设备的绑定结果（按设备ID排序）
+    pub device_bindings: Vec<DeviceBindingStatus>,
+    /// 负载均衡迁移次数
+    pub migrations: u64,
 }
 
 /// CPU绑定配置
@@ -353,8 +765,10 @@ pub struct CpuAffinityConfig {
     pub enabled: bool,
     /// 绑定策略
     pub strategy: CpuAffinityStrategy,
-    /// 手动核心映射（仅在Manual策略下使用）
+    /// 手动核心映射：设备ID → 逻辑CPU编号（仅在Manual策略下使用）
     pub manual_mapping: Option<HashMap<u32, usize>>,
+    /// 预留核心
+    pub reservation: CoreReservation,
 }
 
 impl Default for CpuAffinityConfig {
@@ -363,6 +777,7 @@ impl Default for CpuAffinityConfig {
             enabled: true,
             strategy: CpuAffinityStrategy::RoundRobin,
             manual_mapping: None,
+            reservation: CoreReservation::default(),
         }
     }
 }
@@ -374,6 +789,7 @@ impl CpuAffinityConfig {
             enabled: true,
             strategy: CpuAffinityStrategy::RoundRobin,
             manual_mapping: None,
+            reservation: CoreReservation::default(),
         }
     }
 
@@ -383,6 +799,7 @@ impl CpuAffinityConfig {
             enabled: true,
             strategy: CpuAffinityStrategy::Manual(mapping.clone()),
             manual_mapping: Some(mapping),
+            reservation: CoreReservation::default(),
         }
     }
 
@@ -392,6 +809,7 @@ impl CpuAffinityConfig {
             enabled: true,
             strategy: CpuAffinityStrategy::PerformanceFirst,
             manual_mapping: None,
+            reservation: CoreReservation::default(),
         }
     }
 
@@ -401,15 +819,23 @@ impl CpuAffinityConfig {
             enabled: true,
             strategy: CpuAffinityStrategy::PhysicalCoresOnly,
             manual_mapping: None,
+            reservation: CoreReservation::default(),
         }
     }
 
+    /// 设置预留核心
+    pub fn with_reservation(mut self, reservation: CoreReservation) -> Self {
+        self.reservation = reservation;
+        self
+    }
+
     /// 禁用CPU绑定
     pub fn disabled() -> Self {
         Self {
             enabled: false,
             strategy: CpuAffinityStrategy::RoundRobin,
             manual_mapping: None,
+            reservation: CoreReservation::default(),
         }
     }
 }
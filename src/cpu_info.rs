@@ -0,0 +1,337 @@
+//! # CPU信息检测模块
+//!
+//! 本模块检测CPU厂商、型号、特性标志和缓存层级，为核心能力中的 `CpuSpecificCapabilities`
+//! 提供真实数据，取代固定的 SSE/AVX/AVX2/SHA 和 32/32/256/8192 KB 缓存大小。
+//!
+//! ## 🚀 数据来源
+//!
+//! | 来源 | 平台 | 内容 |
+//! |------|------|------|
+//! | `/proc/cpuinfo` | Linux | 厂商 (`vendor_id` / `CPU implementer`)、型号 (`model name` / `CPU part`)、特性 (`flags` / `Features`) |
+//! | `/sys/devices/system/cpu/cpu0/cache` | Linux | 各级缓存大小 |
+//! | CPUID (`raw-cpuid`) | x86 | 厂商、品牌字符串、特性和缓存参数，补充Linux来源缺少的信息 |
+//! | `is_*_feature_detected!` | 其他 | 只有特性，没有其他来源时使用 |
+//!
+//! ## 🎯 SIMD扩展
+//!
+//! `simd_support` 只列出与SHA256挖矿相关的扩展：
+//!
+//! | 标志 | 名称 | 标志 | 名称 |
+//! |------|------|------|------|
+//! | `sse` / `sse2` / `ssse3` | SSE / SSE2 / SSSE3 | `sha_ni` | SHA |
+//! | `sse4_1` / `sse4_2` | SSE4.1 / SSE4.2 | `asimd` | NEON |
+//! | `avx` / `avx2` / `avx512f` | AVX / AVX2 / AVX512F | `sha2` / `sve` | SHA2 / SVE |
+//!
+//! ## 🔄 使用示例
+//!
+//! ```rust
+//! use cgminer_cpu_btc_core::cpu_info::CpuInfo;
+//!
+//! let cpu = CpuInfo::detect();
+//! println!("{} {}", cpu.vendor.as_deref().unwrap_or("unknown"), cpu.model.as_deref().unwrap_or("unknown"));
+//! println!("SIMD: {:?}, L2: {:?} KB", cpu.simd_support(), cpu.cache.l2_kb);
+//! ```
+
+use crate::topology::{self, CacheInfo, DEFAULT_SYSFS_CPU_ROOT};
+use cgminer_core::CpuCacheInfo;
+use serde::Serialize;
+use std::path::Path;
+
+/// Linux CPU信息文件
+pub const PROC_CPUINFO: &str = "/proc/cpuinfo";
+
+/// 与挖矿相关的特性标志及其名称（按输出顺序）
+const SIMD_FEATURES: [(&str, &str); 13] = [
+    ("sse", "SSE"),
+    ("sse2", "SSE2"),
+    ("ssse3", "SSSE3"),
+    ("sse4_1", "SSE4.1"),
+    ("sse4_2", "SSE4.2"),
+    ("avx", "AVX"),
+    ("avx2", "AVX2"),
+    ("avx512f", "AVX512F"),
+    ("sha_ni", "SHA"),
+    ("asimd", "NEON"),
+    ("neon", "NEON"),
+    ("sha2", "SHA2"),
+    ("sve", "SVE"),
+];
+
+/// ARM `CPU implementer` 代码对应的厂商
+const ARM_IMPLEMENTERS: [(u32, &str); 10] = [
+    (0x41, "ARM"),
+    (0x42, "Broadcom"),
+    (0x43, "Cavium"),
+    (0x46, "Fujitsu"),
+    (0x48, "HiSilicon"),
+    (0x4e, "NVIDIA"),
+    (0x50, "APM"),
+    (0x51, "Qualcomm"),
+    (0x61, "Apple"),
+    (0xc0, "Ampere"),
+];
+
+/// 各级缓存大小 (KB)，无法检测的级别为None
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheSizes {
+    /// L1数据缓存
+    pub l1_data_kb: Option<u32>,
+    /// L1指令缓存
+    pub l1_instruction_kb: Option<u32>,
+    /// L2缓存
+    pub l2_kb: Option<u32>,
+    /// L3缓存
+    pub l3_kb: Option<u32>,
+}
+
+impl CacheSizes {
+    /// 读取一个逻辑CPU的 `cache/index*` 目录（如 `/sys/devices/system/cpu/cpu0`）
+    pub fn from_sysfs(cpu_dir: impl AsRef<Path>) -> Self {
+        Self::from_caches(&topology::read_caches(&cpu_dir.as_ref().join("cache")))
+    }
+
+    /// 从拓扑模块的缓存列表汇总各级大小（同一级别有多个时取第一个）
+    pub fn from_caches(caches: &[CacheInfo]) -> Self {
+        let mut sizes = Self::default();
+        for cache in caches.iter().filter(|cache| cache.size_kb > 0) {
+            let slot = match (cache.level, cache.cache_type.as_str()) {
+                (1, "Data") => &mut sizes.l1_data_kb,
+                (1, "Instruction") => &mut sizes.l1_instruction_kb,
+                (2, "Instruction") | (3, "Instruction") => continue,
+                (2, _) => &mut sizes.l2_kb,
+                (3, _) => &mut sizes.l3_kb,
+                _ => continue,
+            };
+            slot.get_or_insert(cache.size_kb);
+        }
+        sizes
+    }
+
+    /// 是否没有检测到任何缓存
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 转换为核心能力中的缓存信息（未检测到的级别为0，全部未检测到时为None）
+    pub fn to_cache_info(&self) -> Option<CpuCacheInfo> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(CpuCacheInfo {
+            l1_data_kb: self.l1_data_kb.unwrap_or(0),
+            l1_instruction_kb: self.l1_instruction_kb.unwrap_or(0),
+            l2_kb: self.l2_kb.unwrap_or(0),
+            l3_kb: self.l3_kb.unwrap_or(0),
+        })
+    }
+
+    /// 用另一来源的结果补充未检测到的级别
+    fn fill_missing(&mut self, other: &Self) {
+        self.l1_data_kb = self.l1_data_kb.or(other.l1_data_kb);
+        self.l1_instruction_kb = self.l1_instruction_kb.or(other.l1_instruction_kb);
+        self.l2_kb = self.l2_kb.or(other.l2_kb);
+        self.l3_kb = self.l3_kb.or(other.l3_kb);
+    }
+}
+
+/// CPU信息
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CpuInfo {
+    /// 厂商（如 `GenuineIntel`、`AuthenticAMD`、`ARM`）
+    pub vendor: Option<String>,
+    /// 型号（x86品牌字符串，ARM没有时为 `part 0xd0c` 形式）
+    pub model: Option<String>,
+    /// 特性标志（`/proc/cpuinfo` 的小写名称）
+    pub features: Vec<String>,
+    /// 缓存大小
+    pub cache: CacheSizes,
+}
+
+impl CpuInfo {
+    /// 检测当前CPU：Linux读取 `/proc/cpuinfo` 和 cpu0 的缓存，x86用CPUID补充缺少的信息
+    pub fn detect() -> Self {
+        let mut info = Self::default();
+        if cfg!(target_os = "linux") {
+            if let Ok(content) = std::fs::read_to_string(PROC_CPUINFO) {
+                info = Self::from_cpuinfo(&content);
+            }
+            info.cache = CacheSizes::from_sysfs(Path::new(DEFAULT_SYSFS_CPU_ROOT).join("cpu0"));
+        }
+
+        #[cfg(all(feature = "raw-cpuid", any(target_arch = "x86", target_arch = "x86_64")))]
+        info.fill_missing(&Self::from_cpuid());
+
+        if info.features.is_empty() {
+            info.features = detected_features();
+        }
+        info
+    }
+
+    /// 从 `/proc/cpuinfo` 内容解析第一个处理器的厂商、型号和特性
+    pub fn from_cpuinfo(content: &str) -> Self {
+        let first = first_processor(content);
+        let field = |name: &str| cpuinfo_field(first, name);
+
+        let implementer = field("CPU implementer");
+        let vendor = field("vendor_id").or_else(|| implementer.as_deref().map(arm_vendor));
+        let model = field("model name")
+            .or_else(|| field("CPU part").map(|part| format!("part {}", part)));
+        let features = field("flags")
+            .or_else(|| field("Features"))
+            .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+
+        Self { vendor, model, features, cache: CacheSizes::default() }
+    }
+
+    /// 通过CPUID读取厂商、品牌字符串、特性和缓存参数
+    #[cfg(all(feature = "raw-cpuid", any(target_arch = "x86", target_arch = "x86_64")))]
+    pub fn from_cpuid() -> Self {
+        use raw_cpuid::{CacheType, CpuId};
+
+        let cpuid = CpuId::new();
+        let mut features = Vec::new();
+        if let Some(info) = cpuid.get_feature_info() {
+            for (present, flag) in [
+                (info.has_sse(), "sse"),
+                (info.has_sse2(), "sse2"),
+                (info.has_ssse3(), "ssse3"),
+                (info.has_sse41(), "sse4_1"),
+                (info.has_sse42(), "sse4_2"),
+                (info.has_avx(), "avx"),
+            ] {
+                if present {
+                    features.push(flag.to_string());
+                }
+            }
+        }
+        if let Some(info) = cpuid.get_extended_feature_info() {
+            for (present, flag) in [(info.has_avx2(), "avx2"), (info.has_avx512f(), "avx512f"), (info.has_sha(), "sha_ni")] {
+                if present {
+                    features.push(flag.to_string());
+                }
+            }
+        }
+
+        // 确定性缓存参数（Intel叶4，AMD叶0x8000001D）
+        let mut cache = CacheSizes::default();
+        for parameters in cpuid.get_cache_parameters().into_iter().flatten() {
+            let size_kb = (parameters.associativity()
+                * parameters.physical_line_partitions()
+                * parameters.coherency_line_size()
+                * parameters.sets()
+                / 1024) as u32;
+            let slot = match (parameters.level(), parameters.cache_type()) {
+                (1, CacheType::Data) => &mut cache.l1_data_kb,
+                (1, CacheType::Instruction) => &mut cache.l1_instruction_kb,
+                (2, CacheType::Data | CacheType::Unified) => &mut cache.l2_kb,
+                (3, CacheType::Data | CacheType::Unified) => &mut cache.l3_kb,
+                _ => continue,
+            };
+            if size_kb > 0 {
+                slot.get_or_insert(size_kb);
+            }
+        }
+
+        // 较早的AMD处理器只有扩展叶0x80000005/0x80000006（L3以512KB为单位）
+        let mut legacy = CacheSizes::default();
+        if let Some(l1) = cpuid.get_l1_cache_and_tlb_info() {
+            legacy.l1_data_kb = Some(l1.dcache_size() as u32).filter(|&kb| kb > 0);
+            legacy.l1_instruction_kb = Some(l1.icache_size() as u32).filter(|&kb| kb > 0);
+        }
+        if let Some(l2_l3) = cpuid.get_l2_l3_cache_and_tlb_info() {
+            legacy.l2_kb = Some(l2_l3.l2cache_size() as u32).filter(|&kb| kb > 0);
+            legacy.l3_kb = Some(l2_l3.l3cache_size() as u32 * 512).filter(|&kb| kb > 0);
+        }
+        cache.fill_missing(&legacy);
+
+        Self {
+            vendor: cpuid.get_vendor_info().map(|vendor| vendor.as_str().to_string()),
+            model: cpuid.get_processor_brand_string()
+                .map(|brand| brand.as_str().trim().to_string())
+                .filter(|brand| !brand.is_empty()),
+            features,
+            cache,
+        }
+    }
+
+    /// 用另一来源的结果补充缺少的厂商、型号、特性和缓存级别
+    pub fn fill_missing(&mut self, other: &Self) {
+        if self.vendor.is_none() {
+            self.vendor = other.vendor.clone();
+        }
+        if self.model.is_none() {
+            self.model = other.model.clone();
+        }
+        if self.features.is_empty() {
+            self.features = other.features.clone();
+        }
+        self.cache.fill_missing(&other.cache);
+    }
+
+    /// 是否有指定的特性标志
+    pub fn has_feature(&self, flag: &str) -> bool {
+        self.features.iter().any(|feature| feature == flag)
+    }
+
+    /// 与挖矿相关的SIMD扩展（如 `["SSE2", "AVX2", "SHA"]`）
+    pub fn simd_support(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (flag, name) in SIMD_FEATURES {
+            if self.has_feature(flag) && !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+}
+
+/// `/proc/cpuinfo` 中第一个处理器的段落
+pub(crate) fn first_processor(content: &str) -> &str {
+    content.split("\n\n").find(|block| !block.trim().is_empty()).unwrap_or("")
+}
+
+/// 读取 `/proc/cpuinfo` 段落中的字段（`名称 : 值`）
+pub(crate) fn cpuinfo_field(block: &str, name: &str) -> Option<String> {
+    block.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then(|| value.trim().to_string())
+    })
+}
+
+/// ARM厂商代码（如 `0x41`）对应的名称，未知时保留代码
+fn arm_vendor(implementer: &str) -> String {
+    u32::from_str_radix(implementer.trim_start_matches("0x"), 16)
+        .ok()
+        .and_then(|code| ARM_IMPLEMENTERS.iter().find(|(known, _)| *known == code))
+        .map_or_else(|| implementer.to_string(), |(_, vendor)| vendor.to_string())
+}
+
+/// 标准库运行时检测到的特性（没有 `/proc/cpuinfo` 和CPUID时使用）
+fn detected_features() -> Vec<String> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    let detected = [
+        (std::arch::is_x86_feature_detected!("sse"), "sse"),
+        (std::arch::is_x86_feature_detected!("sse2"), "sse2"),
+        (std::arch::is_x86_feature_detected!("ssse3"), "ssse3"),
+        (std::arch::is_x86_feature_detected!("sse4.1"), "sse4_1"),
+        (std::arch::is_x86_feature_detected!("sse4.2"), "sse4_2"),
+        (std::arch::is_x86_feature_detected!("avx"), "avx"),
+        (std::arch::is_x86_feature_detected!("avx2"), "avx2"),
+        (std::arch::is_x86_feature_detected!("avx512f"), "avx512f"),
+        (std::arch::is_x86_feature_detected!("sha"), "sha_ni"),
+    ];
+    #[cfg(target_arch = "aarch64")]
+    let detected = [
+        (std::arch::is_aarch64_feature_detected!("neon"), "asimd"),
+        (std::arch::is_aarch64_feature_detected!("sha2"), "sha2"),
+        (std::arch::is_aarch64_feature_detected!("sve"), "sve"),
+    ];
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    let detected: [(bool, &str); 0] = [];
+
+    detected.iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, flag)| flag.to_string())
+        .collect()
+}
@@ -19,9 +19,17 @@
 //! ### [`SoftwareDevice`] - 主要设备实现
 //! - 🔧 完整的MiningDevice trait实现
 //! - 🔧 支持CPU亲和性绑定
-//! - 🔧 真实系统温度监控
+//! - 🔧 真实系统温度监控（优先读取绑定核心的温度）
 //! - 🔧 CGMiner风格结果上报
 //!
+//! ### [`NonceRange`] - nonce空间分片
+//! - 🧩 每个设备只搜索自己的nonce区间，避免重复计算
+//! - 🧩 设备数量变化时由核心重新分片，运行中的循环立即切换
+//!
+//! ### [`PauseFlags`] - 暂停标志
+//! - ⏸️ 核心和所有设备共享，按 [`PauseReason`] 记录暂停原因
+//! - ⏸️ 所有原因解除后挖矿循环在下一个批次恢复
+//!
 //! ### [`HashrateTracker`] - CGMiner兼容算力跟踪
 //! - 📈 指数衰减平均算法 (5s/1m/5m/15m)
 //! - 📈 CGMiner标准输出格式
@@ -58,18 +66,29 @@ use cgminer_core::{
     Work, MiningResult, DeviceError, CgminerHashrateTracker
 };
 use crate::cpu_affinity::CpuAffinityManager;
+use crate::events::{EventBus, MiningEventKind};
+use crate::hashing::{HashImplementation, HeaderHasher, HASH_CHUNK};
 use crate::platform_optimization;
-use crate::temperature::{TemperatureManager, TemperatureConfig};
+use crate::priority::{self, PriorityConfig, PriorityStatus};
+use crate::result_buffer::{ResultSender, SendOutcome};
+use crate::temperature::{
+    TemperatureManager, TemperatureConfig, TemperatureError, TemperatureScope, TemperatureSource, TemperatureStatus,
+};
+use crate::thermal::{ThermalGovernor, ThermalState};
+use crate::tuning::{BatchTuner, TuningResult, DEFAULT_BATCH_SIZE};
 use async_trait::async_trait;
 use sha2::Digest;
 use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU32, Ordering};
 use std::time::{Duration, SystemTime};
-use tokio::sync::mpsc;
+use tokio::sync::Notify;
 use tokio::time::Instant;
 use tracing::{debug, info, warn};
 use std::sync::Mutex;
 
+/// 停止设备时等待挖矿工作线程退出的最长时间
+const WORKER_JOIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// 原子统计计数器 - 消除锁竞争
 /// 替换 Arc<RwLock<DeviceStats>> 以提高并发性能
 #[derive(Debug)]
@@ -87,6 +106,7 @@ pub struct AtomicStats {
     // 温度和功耗
     pub temperature: AtomicU32, // 存储为 f32 的位模式
     pub power_consumption: AtomicU32, // 存储为 f32 的位模式
+    pub temperature_scope: AtomicU64, // 温度来源，TemperatureScope::pack
 
     // 时间戳
     pub start_time_nanos: AtomicU64,
@@ -112,6 +132,7 @@ impl AtomicStats {
             average_hashrate: AtomicU64::new(0.0f64.to_bits()),
             temperature: AtomicU32::new(0.0f32.to_bits()),
             power_consumption: AtomicU32::new(0.0f32.to_bits()),
+            temperature_scope: AtomicU64::new(TemperatureScope::Unavailable.pack()),
             start_time_nanos: AtomicU64::new(now),
             last_update_nanos: AtomicU64::new(now),
             device_id,
@@ -159,9 +180,19 @@ impl AtomicStats {
         self.temperature.store(temp.to_bits(), Ordering::Relaxed);
     }
 
-    /// 原子更新功耗
+    /// 原子更新温度来源
+    pub fn update_temperature_scope(&self, scope: TemperatureScope) {
+        self.temperature_scope.store(scope.pack(), Ordering::Relaxed);
+    }
+
+    /// 当前温度的来源
+    pub fn temperature_scope(&self) -> TemperatureScope {
+        TemperatureScope::unpack(self.temperature_scope.load(Ordering::Relaxed))
+    }
+
+    /// 原子更新功耗 (瓦)
     pub fn update_power_consumption(&self, power: f64) {
-        self.power_consumption.store(power.to_bits() as u32, Ordering::Relaxed);
+        self.power_consumption.store((power as f32).to_bits(), Ordering::Relaxed);
     }
 
     /// 转换为 DeviceStats 结构体 - 不包含算力计算，由上层计算
@@ -195,6 +226,27 @@ impl AtomicStats {
         stats
     }
 
+    /// 在当前线程上分配一份新的统计并复制所有计数
+    ///
+    /// 设备的挖矿工作线程绑定CPU之后调用，新的统计按first-touch策略位于绑定节点的本地内存。
+    pub fn relocate(&self) -> Self {
+        let stats = Self::new(self.device_id);
+        let copy_u64 = |to: &AtomicU64, from: &AtomicU64| to.store(from.load(Ordering::Relaxed), Ordering::Relaxed);
+        let copy_u32 = |to: &AtomicU32, from: &AtomicU32| to.store(from.load(Ordering::Relaxed), Ordering::Relaxed);
+        copy_u64(&stats.total_hashes, &self.total_hashes);
+        copy_u64(&stats.accepted_work, &self.accepted_work);
+        copy_u64(&stats.rejected_work, &self.rejected_work);
+        copy_u64(&stats.hardware_errors, &self.hardware_errors);
+        copy_u64(&stats.last_hashrate, &self.last_hashrate);
+        copy_u64(&stats.average_hashrate, &self.average_hashrate);
+        copy_u32(&stats.temperature, &self.temperature);
+        copy_u32(&stats.power_consumption, &self.power_consumption);
+        copy_u64(&stats.temperature_scope, &self.temperature_scope);
+        copy_u64(&stats.start_time_nanos, &self.start_time_nanos);
+        copy_u64(&stats.last_update_nanos, &self.last_update_nanos);
+        stats
+    }
+
     /// 重置所有统计数据
     pub fn reset(&self) {
         let now = SystemTime::now()
@@ -210,6 +262,7 @@ impl AtomicStats {
         self.average_hashrate.store(0.0f64.to_bits(), Ordering::Relaxed);
         self.temperature.store(0.0f32.to_bits(), Ordering::Relaxed);
         self.power_consumption.store(0.0f32.to_bits(), Ordering::Relaxed);
+        self.temperature_scope.store(TemperatureScope::Unavailable.pack(), Ordering::Relaxed);
         self.start_time_nanos.store(now, Ordering::Relaxed);
         self.last_update_nanos.store(now, Ordering::Relaxed);
     }
@@ -304,6 +357,52 @@ impl BatchStatsUpdater {
     }
 }
 
+/// 挖矿循环与其他线程共享的设备状态
+///
+/// 第一次启动工作线程时在绑定CPU之后重新分配（见 [`DeviceState::relocate`]），
+/// 按first-touch策略位于设备所在NUMA节点的本地内存。
+#[derive(Clone)]
+struct DeviceState {
+    atomic_stats: Arc<AtomicStats>,
+    work_queue: Arc<crate::concurrent_optimization::LockFreeWorkQueue>,
+    hashrate_tracker: Arc<CgminerHashrateTracker>,
+    batch_stats_updater: Arc<Mutex<BatchStatsUpdater>>,
+}
+
+impl DeviceState {
+    fn new(device_id: u32) -> Self {
+        // 创建原子统计 - 替换RwLock<DeviceStats>
+        let atomic_stats = Arc::new(AtomicStats::new(device_id));
+        // 创建批量统计更新器，每100ms批量更新
+        let batch_stats_updater = Arc::new(Mutex::new(BatchStatsUpdater::new(atomic_stats.clone(), 100)));
+        Self {
+            atomic_stats,
+            // 创建无锁工作队列 - CGMiner风格：小队列
+            work_queue: Arc::new(crate::concurrent_optimization::LockFreeWorkQueue::new(3)),
+            // 创建cgminer风格的算力追踪器
+            hashrate_tracker: Arc::new(CgminerHashrateTracker::new()),
+            batch_stats_updater,
+        }
+    }
+
+    /// 在当前线程上重新分配：保留统计计数和待处理的工作
+    ///
+    /// 只在设备第一次启动、尚未计算过哈希时调用，算力追踪器因此直接新建。
+    fn relocate(&self) -> Self {
+        if let Ok(mut updater) = self.batch_stats_updater.lock() {
+            updater.force_flush();
+        }
+        let atomic_stats = Arc::new(self.atomic_stats.relocate());
+        let batch_stats_updater = Arc::new(Mutex::new(BatchStatsUpdater::new(atomic_stats.clone(), 100)));
+        Self {
+            atomic_stats,
+            work_queue: Arc::new(self.work_queue.relocate()),
+            hashrate_tracker: Arc::new(CgminerHashrateTracker::new()),
+            batch_stats_updater,
+        }
+    }
+}
+
 /// 优化的SHA256双重哈希计算 - 使用固定大小数组提高性能
 #[inline(always)]
 fn optimized_double_sha256(data: &[u8]) -> [u8; 32] {
@@ -312,6 +411,165 @@ fn optimized_double_sha256(data: &[u8]) -> [u8; 32] {
     second_hash.into()
 }
 
+/// 计算nonce对应的区块头哈希，区块头不足4字节时返回 `None`
+pub fn share_hash(work: &Work, nonce: u32) -> Option<[u8; 32]> {
+    let mut header_data = work.header.clone();
+    if header_data.len() < 4 {
+        return None;
+    }
+    let start_idx = header_data.len() - 4;
+    header_data[start_idx..].copy_from_slice(&nonce.to_le_bytes());
+
+    Some(optimized_double_sha256(&header_data))
+}
+
+/// 复算份额哈希并检查是否满足工作目标
+pub fn verify_share(work: &Work, nonce: u32) -> bool {
+    share_hash(work, nonce).map_or(false, |hash| cgminer_core::meets_target(&hash, &work.target))
+}
+
+/// nonce空间分片（闭区间）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceRange {
+    /// 起始nonce
+    pub start: u32,
+    /// 结束nonce（包含）
+    pub end: u32,
+}
+
+impl NonceRange {
+    /// 完整的32位nonce空间
+    pub fn full() -> Self {
+        Self { start: 0, end: u32::MAX }
+    }
+
+    /// 将nonce空间均分为 `count` 份，返回第 `index` 份
+    pub fn partition(index: u32, count: u32) -> Self {
+        let count = count.max(1) as u64;
+        let index = (index as u64).min(count - 1);
+        let span = (u32::MAX as u64 + 1) / count;
+
+        let start = index * span;
+        let end = if index == count - 1 {
+            u32::MAX as u64
+        } else {
+            start + span - 1
+        };
+
+        Self { start: start as u32, end: end as u32 }
+    }
+
+    /// 区间内的nonce数量
+    pub fn size(&self) -> u64 {
+        self.end as u64 - self.start as u64 + 1
+    }
+
+    /// 区间内第 `offset` 个nonce（超出长度时回绕）
+    #[inline(always)]
+    pub fn nonce_at(&self, offset: u64) -> u32 {
+        self.start.wrapping_add((offset % self.size()) as u32)
+    }
+
+    fn pack(&self) -> u64 {
+        ((self.start as u64) << 32) | self.end as u64
+    }
+
+    fn unpack(packed: u64) -> Self {
+        Self { start: (packed >> 32) as u32, end: packed as u32 }
+    }
+}
+
+/// 暂停原因（可以同时存在多个，全部解除后恢复计算）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    /// 按当前电价挖矿不盈利
+    Unprofitable,
+    /// 其他程序正在使用CPU（空闲检测）
+    SystemBusy,
+    /// 不在挖矿时间表的时间段内
+    OutsideSchedule,
+}
+
+impl PauseReason {
+    /// 所有暂停原因
+    pub const ALL: &'static [PauseReason] = &[
+        PauseReason::Unprofitable,
+        PauseReason::SystemBusy,
+        PauseReason::OutsideSchedule,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// 共享的暂停标志 - 核心持有一份，所有设备的挖矿循环每个批次检查
+#[derive(Debug, Clone, Default)]
+pub struct PauseFlags(Arc<AtomicU32>);
+
+impl PauseFlags {
+    /// 设置或解除一个暂停原因，返回状态是否发生变化
+    pub fn set(&self, reason: PauseReason, paused: bool) -> bool {
+        let previous = if paused {
+            self.0.fetch_or(reason.bit(), Ordering::Relaxed)
+        } else {
+            self.0.fetch_and(!reason.bit(), Ordering::Relaxed)
+        };
+        (previous & reason.bit() != 0) != paused
+    }
+
+    /// 是否因指定原因暂停
+    pub fn contains(&self, reason: PauseReason) -> bool {
+        self.0.load(Ordering::Relaxed) & reason.bit() != 0
+    }
+
+    /// 是否存在任何暂停原因
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed) != 0
+    }
+
+    /// 当前的暂停原因
+    pub fn reasons(&self) -> Vec<PauseReason> {
+        PauseReason::ALL.iter().copied().filter(|reason| self.contains(*reason)).collect()
+    }
+}
+
+/// 挖矿工作线程的停止信号
+///
+/// 紧凑循环轮询标志位；阻塞在结果上报上的等待通过 [`StopSignal::stopped`] 被唤醒。
+#[derive(Debug, Default)]
+pub struct StopSignal {
+    stopped: AtomicBool,
+    notify: Notify,
+}
+
+impl StopSignal {
+    /// 发出停止信号并唤醒所有等待
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+    }
+
+    /// 是否已发出停止信号
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+
+    /// 等待停止信号
+    pub async fn stopped(&self) {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_stopped() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
 /// 软算法设备（阶段2优化版本）
 pub struct SoftwareDevice {
     /// 设备信息
@@ -327,7 +585,11 @@ pub struct SoftwareDevice {
     /// cgminer风格的算力追踪器
     hashrate_tracker: Arc<CgminerHashrateTracker>,
     /// 目标算力 (hashes per second)
-    target_hashrate: f64,
+    target_hashrate: Arc<AtomicU64>,
+    /// 连续计算模式的算力上限 (H/s)，0表示不限制
+    ///
+    /// 只在显式调用 `set_target_hashrate` / `set_hashrate_cap` 时设置，创建设备时的目标算力不作为上限。
+    hashrate_cap: Arc<AtomicU64>,
     /// 错误率
     error_rate: f64,
     /// 批次大小
@@ -344,15 +606,45 @@ pub struct SoftwareDevice {
     temperature_capability_checked: Arc<AtomicBool>,
     temperature_capability_supported: Arc<AtomicBool>,
     /// cgminer风格结果发送通道 - 立即上报
-    result_sender: Option<mpsc::UnboundedSender<MiningResult>>,
+    result_sender: Option<ResultSender>,
+    /// 事件总线
+    event_bus: Option<EventBus>,
+    /// 上一次的温度状态，用于检测状态变化
+    thermal_state: Arc<Mutex<Option<TemperatureStatus>>>,
 
     /// 批量统计更新器
     batch_stats_updater: Arc<std::sync::Mutex<BatchStatsUpdater>>,
 
-    /// 挖矿任务句柄
-    mining_task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 挖矿工作线程句柄
+    mining_task_handle: Arc<Mutex<Option<std::thread::JoinHandle<()>>>>,
     /// 挖矿任务停止信号
-    mining_stop_signal: Arc<AtomicBool>,
+    mining_stop_signal: Arc<StopSignal>,
+    /// 分配给本设备的nonce区间（打包为u64）
+    nonce_range: Arc<AtomicU64>,
+    /// 占空比 (0.0-1.0，f64位存储)，1.0为全速，0.0为暂停
+    duty_cycle: Arc<AtomicU64>,
+    /// 温度调节给出的占空比系数 (f64位存储)，与 `duty_cycle` 相乘
+    thermal_duty: Arc<AtomicU64>,
+    /// 功率上限给出的占空比系数 (f64位存储)，与 `duty_cycle` 相乘
+    power_duty: Arc<AtomicU64>,
+    /// 挖矿时间表给出的占空比系数 (f64位存储)，与 `duty_cycle` 相乘
+    schedule_duty: Arc<AtomicU64>,
+    /// 暂停标志（与核心共享）
+    pause_flags: PauseFlags,
+    /// 闭环温度调节器
+    thermal_governor: Arc<Mutex<ThermalGovernor>>,
+    /// 挖矿工作线程的调度策略、nice值和I/O优先级
+    priority_config: PriorityConfig,
+    /// 最近一次启动工作线程时优先级设置的结果
+    priority_status: Arc<RwLock<Option<PriorityStatus>>>,
+    /// 连续计算循环的批次大小调优器（未启用调优时为None）
+    batch_tuner: Option<Arc<Mutex<BatchTuner>>>,
+    /// 最近一次提交工作的时间，用于测量新工作的响应延迟
+    work_submitted_at: Arc<RwLock<Option<Instant>>>,
+    /// 连续计算循环使用的哈希实现
+    hash_implementation: HashImplementation,
+    /// 共享状态（统计、工作队列、算力追踪器）分配所在的工作线程名，尚未启动过工作线程时为None
+    state_thread: Option<String>,
 }
 
 impl SoftwareDevice {
@@ -366,22 +658,12 @@ impl SoftwareDevice {
     ) -> Result<Self, DeviceError> {
         let device_id = device_info.id;
 
-        // 创建原子统计 - 替换RwLock<DeviceStats>
-        let atomic_stats = Arc::new(AtomicStats::new(device_id));
-
-        // 创建无锁工作队列 - 替换Mutex<Option<Work>>
-        let work_queue = Arc::new(crate::concurrent_optimization::LockFreeWorkQueue::new(3)); // CGMiner风格：小队列
-
-        // 创建批量统计更新器
-        let batch_stats_updater = Arc::new(std::sync::Mutex::new(
-            BatchStatsUpdater::new(atomic_stats.clone(), 100) // 每100ms批量更新
-        ));
-
-        // 创建cgminer风格的算力追踪器
-        let hashrate_tracker = Arc::new(CgminerHashrateTracker::new());
+        // 共享状态先在调用线程上分配，第一次启动工作线程时迁移到绑定节点
+        let DeviceState { atomic_stats, work_queue, hashrate_tracker, batch_stats_updater } = DeviceState::new(device_id);
 
         // 创建温度管理器（仅在支持真实温度监控时）
         let temp_config = TemperatureConfig::default();
+        let thermal_governor = Self::build_thermal_governor(&temp_config, &config);
         let temperature_manager = Some(TemperatureManager::new(temp_config));
 
         Ok(Self {
@@ -391,7 +673,8 @@ impl SoftwareDevice {
             atomic_stats,
             work_queue,
             hashrate_tracker,
-            target_hashrate,
+            target_hashrate: Arc::new(AtomicU64::new(target_hashrate.to_bits())),
+            hashrate_cap: Arc::new(AtomicU64::new(0.0f64.to_bits())),
             error_rate,
             batch_size,
             start_time: None,
@@ -401,9 +684,24 @@ impl SoftwareDevice {
             temperature_capability_checked: Arc::new(AtomicBool::new(false)),
             temperature_capability_supported: Arc::new(AtomicBool::new(false)),
             result_sender: None,
+            event_bus: None,
+            thermal_state: Arc::new(Mutex::new(None)),
             batch_stats_updater,
             mining_task_handle: Arc::new(Mutex::new(None)),
-            mining_stop_signal: Arc::new(AtomicBool::new(false)),
+            mining_stop_signal: Arc::new(StopSignal::default()),
+            nonce_range: Arc::new(AtomicU64::new(NonceRange::full().pack())),
+            duty_cycle: Arc::new(AtomicU64::new(1.0f64.to_bits())),
+            thermal_duty: Arc::new(AtomicU64::new(1.0f64.to_bits())),
+            power_duty: Arc::new(AtomicU64::new(1.0f64.to_bits())),
+            schedule_duty: Arc::new(AtomicU64::new(1.0f64.to_bits())),
+            pause_flags: PauseFlags::default(),
+            thermal_governor: Arc::new(Mutex::new(thermal_governor)),
+            priority_config: PriorityConfig::default(),
+            priority_status: Arc::new(RwLock::new(None)),
+            batch_tuner: None,
+            work_submitted_at: Arc::new(RwLock::new(None)),
+            hash_implementation: HashImplementation::default(),
+            state_thread: None,
         })
     }
 
@@ -416,52 +714,284 @@ impl SoftwareDevice {
         batch_size: u32,
         cpu_affinity: Arc<RwLock<CpuAffinityManager>>,
     ) -> Result<Self, DeviceError> {
-        let device_id = device_info.id;
+        let mut device = Self::new(device_info, config, target_hashrate, error_rate, batch_size).await?;
+        device.cpu_affinity = Some(cpu_affinity);
+        Ok(device)
+    }
 
-        // 创建原子统计
-        let atomic_stats = Arc::new(AtomicStats::new(device_id));
+    /// 设置结果发送通道 - 立即上报
+    pub fn set_result_sender(&mut self, sender: ResultSender) {
+        self.result_sender = Some(sender);
+    }
 
-        // 创建无锁工作队列
-        let work_queue = Arc::new(crate::concurrent_optimization::LockFreeWorkQueue::new(3)); // CGMiner风格：小队列
+    /// 使用指定的温度阈值重建温度管理器
+    pub fn set_temperature_config(&mut self, config: TemperatureConfig) {
+        self.set_temperature_manager(TemperatureManager::new(config));
+    }
 
-        // 创建批量统计更新器
-        let batch_stats_updater = Arc::new(std::sync::Mutex::new(
-            BatchStatsUpdater::new(atomic_stats.clone(), 100)
-        ));
+    /// 注入温度来源，沿用当前的温度配置
+    pub fn set_temperature_source(&mut self, source: impl TemperatureSource + 'static) {
+        let config = self.temperature_manager.as_ref()
+            .map(|manager| manager.config().clone())
+            .unwrap_or_default();
+        self.set_temperature_manager(TemperatureManager::with_source(config, source));
+    }
 
-        // 创建cgminer风格的算力追踪器
-        let hashrate_tracker = Arc::new(CgminerHashrateTracker::new());
+    /// 替换温度管理器，同时按其配置重建温度调节器
+    pub fn set_temperature_manager(&mut self, manager: TemperatureManager) {
+        let governor = self.config.read()
+            .map(|device_config| Self::build_thermal_governor(manager.config(), &device_config))
+            .ok();
+        if let (Some(governor), Ok(mut current)) = (governor, self.thermal_governor.lock()) {
+            *current = governor;
+        }
+        self.thermal_duty.store(1.0f64.to_bits(), Ordering::Relaxed);
+        self.temperature_manager = Some(manager);
+    }
 
-        // 创建温度管理器
-        let temp_config = TemperatureConfig::default();
-        let temperature_manager = Some(TemperatureManager::new(temp_config));
+    /// 警告和危险阈值：危险阈值取温度配置和设备温度限制中较低的一个
+    fn thermal_thresholds(temp_config: &TemperatureConfig, device_config: &DeviceConfig) -> (f32, f32) {
+        let critical = temp_config.critical_threshold.min(device_config.temperature_limit);
+        (temp_config.warning_threshold.min(critical), critical)
+    }
 
-        Ok(Self {
-            device_info: Arc::new(RwLock::new(device_info)),
-            config: Arc::new(RwLock::new(config)),
-            status: Arc::new(RwLock::new(DeviceStatus::Uninitialized)),
-            atomic_stats,
-            work_queue,
-            hashrate_tracker,
-            target_hashrate,
-            error_rate,
-            batch_size,
-            start_time: None,
-            last_mining_time: Arc::new(RwLock::new(None)),
-            cpu_affinity: Some(cpu_affinity),
-            temperature_manager,
-            temperature_capability_checked: Arc::new(AtomicBool::new(false)),
-            temperature_capability_supported: Arc::new(AtomicBool::new(false)),
-            result_sender: None,
-            batch_stats_updater,
-            mining_task_handle: Arc::new(Mutex::new(None)),
-            mining_stop_signal: Arc::new(AtomicBool::new(false)),
-        })
+    fn build_thermal_governor(temp_config: &TemperatureConfig, device_config: &DeviceConfig) -> ThermalGovernor {
+        let (warning, critical) = Self::thermal_thresholds(temp_config, device_config);
+        ThermalGovernor::new(temp_config.governor.clone(), warning, critical)
     }
 
-    /// 设置结果发送通道 - 立即上报
-    pub fn set_result_sender(&mut self, sender: mpsc::UnboundedSender<MiningResult>) {
-        self.result_sender = Some(sender);
+    /// 执行一次闭环温度调节：读取温度，更新占空比，状态变化时记录日志并发布事件
+    pub fn thermal_tick(&self, elapsed: Duration) -> Result<Option<ThermalState>, DeviceError> {
+        let temperature = match self.update_temperature()? {
+            Some(temperature) => temperature,
+            None => return Ok(None),
+        };
+        let (governor_enabled, thresholds) = match self.temperature_manager {
+            Some(ref manager) => {
+                let device_config = self.config.read().map_err(|e| {
+                    DeviceError::hardware_error(format!("Failed to acquire read lock: {}", e))
+                })?;
+                (manager.config().governor.enabled, Self::thermal_thresholds(manager.config(), &device_config))
+            }
+            None => return Ok(None),
+        };
+        if !governor_enabled {
+            return Ok(None);
+        }
+
+        let decision = {
+            let mut governor = self.thermal_governor.lock().map_err(|e| {
+                DeviceError::hardware_error(format!("Failed to acquire mutex: {}", e))
+            })?;
+            governor.set_thresholds(thresholds.0, thresholds.1);
+            governor.update(temperature, elapsed)
+        };
+        self.thermal_duty.store(decision.duty_cycle.to_bits(), Ordering::Relaxed);
+
+        if let Some(previous) = decision.previous {
+            match decision.state {
+                ThermalState::Paused => warn!("🔥 设备 {} 温度 {:.1}°C 达到危险阈值 {:.1}°C，暂停计算",
+                                              self.device_id(), temperature, thresholds.1),
+                ThermalState::Throttled => info!("🌡️  设备 {} 温度 {:.1}°C，{} → 降频 (占空比 {:.0}%)",
+                                                 self.device_id(), temperature, previous, decision.duty_cycle * 100.0),
+                ThermalState::Normal => info!("✅ 设备 {} 温度 {:.1}°C 已恢复，{} → 全速运行",
+                                              self.device_id(), temperature, previous),
+            }
+            self.emit_event(MiningEventKind::ThermalThrottle {
+                previous,
+                current: decision.state,
+                duty_cycle: decision.duty_cycle,
+                temperature,
+            });
+        }
+
+        Ok(Some(decision.state))
+    }
+
+    /// 当前温度调节状态
+    pub fn thermal_state(&self) -> ThermalState {
+        self.thermal_governor.lock()
+            .map(|governor| governor.state())
+            .unwrap_or(ThermalState::Normal)
+    }
+
+    /// 实际生效的占空比（配置占空比 × 温度调节系数 × 功率上限系数 × 时间表系数），暂停时为0
+    pub fn effective_duty_cycle(&self) -> f64 {
+        if self.pause_flags.is_paused() {
+            return 0.0;
+        }
+        self.duty_cycle()
+            * f64::from_bits(self.thermal_duty.load(Ordering::Relaxed))
+            * self.power_duty_cycle()
+            * self.schedule_duty_cycle()
+    }
+
+    /// 使用共享的暂停标志（由核心在创建设备时设置）
+    pub fn set_pause_flags(&mut self, pause_flags: PauseFlags) {
+        self.pause_flags = pause_flags;
+    }
+
+    /// 当前的暂停原因
+    pub fn pause_reasons(&self) -> Vec<PauseReason> {
+        self.pause_flags.reasons()
+    }
+
+    /// 功率上限给出的占空比系数
+    pub fn power_duty_cycle(&self) -> f64 {
+        f64::from_bits(self.power_duty.load(Ordering::Relaxed))
+    }
+
+    /// 设置功率上限的占空比系数 (0.0-1.0)，0表示该线程暂停
+    pub fn set_power_duty_cycle(&self, duty_cycle: f64) {
+        let duty_cycle = if duty_cycle.is_finite() { duty_cycle.clamp(0.0, 1.0) } else { 1.0 };
+        self.power_duty.store(duty_cycle.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 挖矿时间表给出的占空比系数
+    pub fn schedule_duty_cycle(&self) -> f64 {
+        f64::from_bits(self.schedule_duty.load(Ordering::Relaxed))
+    }
+
+    /// 设置挖矿时间表的占空比系数 (0.0-1.0)
+    pub fn set_schedule_duty_cycle(&self, duty_cycle: f64) {
+        let duty_cycle = if duty_cycle.is_finite() { duty_cycle.clamp(0.0, 1.0) } else { 1.0 };
+        self.schedule_duty.store(duty_cycle.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 获取目标算力
+    pub fn target_hashrate(&self) -> f64 {
+        f64::from_bits(self.target_hashrate.load(Ordering::Relaxed))
+    }
+
+    /// 设置目标算力并作为连续计算的算力上限，运行中的挖矿循环会立即生效；0表示不限制
+    pub fn set_target_hashrate(&self, hashrate: f64) {
+        self.target_hashrate.store(hashrate.max(0.0).to_bits(), Ordering::Relaxed);
+        self.set_hashrate_cap(hashrate);
+        info!("设备 {} 目标算力已设置为 {:.2} MH/s", self.device_id(), hashrate / 1_000_000.0);
+    }
+
+    /// 连续计算模式的算力上限 (H/s)，0表示不限制
+    pub fn hashrate_cap(&self) -> f64 {
+        f64::from_bits(self.hashrate_cap.load(Ordering::Relaxed))
+    }
+
+    /// 设置连续计算模式的算力上限 (H/s)，0或非有限值表示不限制
+    pub fn set_hashrate_cap(&self, hashrate: f64) {
+        let hashrate = if hashrate.is_finite() { hashrate.max(0.0) } else { 0.0 };
+        self.hashrate_cap.store(hashrate.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 获取占空比
+    pub fn duty_cycle(&self) -> f64 {
+        f64::from_bits(self.duty_cycle.load(Ordering::Relaxed))
+    }
+
+    /// 设置占空比 (0.0-1.0)，运行中的挖矿循环在下一个批次生效
+    ///
+    /// 每个批次结束后按 `批次耗时 × (1 - 占空比) / 占空比` 休眠；占空比为0时暂停计算。
+    pub fn set_duty_cycle(&self, duty_cycle: f64) {
+        let duty_cycle = if duty_cycle.is_finite() { duty_cycle.clamp(0.0, 1.0) } else { 1.0 };
+        let previous = f64::from_bits(self.duty_cycle.swap(duty_cycle.to_bits(), Ordering::Relaxed));
+        if (previous - duty_cycle).abs() > f64::EPSILON {
+            debug!("设备 {} 占空比: {:.0}% → {:.0}%", self.device_id(), previous * 100.0, duty_cycle * 100.0);
+        }
+    }
+
+    /// 获取分配给本设备的nonce区间
+    pub fn nonce_range(&self) -> NonceRange {
+        NonceRange::unpack(self.nonce_range.load(Ordering::Relaxed))
+    }
+
+    /// 设置nonce区间，运行中的挖矿循环会在下一个批次切换到新区间
+    pub fn set_nonce_range(&self, range: NonceRange) {
+        self.nonce_range.store(range.pack(), Ordering::Relaxed);
+        debug!("设备 {} nonce区间: {:08x}-{:08x}", self.device_id(), range.start, range.end);
+    }
+
+    /// 设备是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.read().map(|config| config.enabled).unwrap_or(false)
+    }
+
+    /// 设置设备启用状态
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), DeviceError> {
+        let mut config = self.config.write().map_err(|e| {
+            DeviceError::hardware_error(format!("Failed to acquire write lock: {}", e))
+        })?;
+        config.enabled = enabled;
+        Ok(())
+    }
+
+    /// 设备是否正在运行
+    pub fn is_running(&self) -> bool {
+        self.status.read()
+            .map(|status| matches!(*status, DeviceStatus::Running))
+            .unwrap_or(false)
+    }
+
+    /// 获取CPU绑定管理器
+    pub fn cpu_affinity(&self) -> Option<&Arc<RwLock<CpuAffinityManager>>> {
+        self.cpu_affinity.as_ref()
+    }
+
+    /// 设置挖矿工作线程的优先级，下一次启动工作线程时生效
+    pub fn set_priority_config(&mut self, config: PriorityConfig) {
+        self.priority_config = config;
+    }
+
+    /// 最近一次启动工作线程时每项优先级设置是否生效，未请求任何设置时返回 `None`
+    pub fn priority_status(&self) -> Option<PriorityStatus> {
+        self.priority_status.read().ok()?.clone()
+    }
+
+    /// 设置连续计算循环使用的哈希实现，下一次启动工作线程时生效
+    ///
+    /// 不支持的实现（如可移植实现的并行路数不在 [`crate::hashing::PORTABLE_LANES`] 中）返回错误。
+    pub fn set_hash_implementation(&mut self, implementation: HashImplementation) -> Result<(), DeviceError> {
+        implementation.validate().map_err(DeviceError::hardware_error)?;
+        self.hash_implementation = implementation;
+        Ok(())
+    }
+
+    /// 连续计算循环使用的哈希实现
+    pub fn hash_implementation(&self) -> HashImplementation {
+        self.hash_implementation
+    }
+
+    /// 设置连续计算循环的批次大小调优器，下一次启动工作线程时生效
+    pub fn set_batch_tuner(&mut self, tuner: BatchTuner) {
+        self.batch_tuner = Some(Arc::new(Mutex::new(tuner)));
+    }
+
+    /// 批次大小调优的当前结果，未启用调优或尚未完成第一步测量时返回 `None`
+    pub fn batch_tuning(&self) -> Option<TuningResult> {
+        self.batch_tuner.as_ref()?.lock().ok()?.result()
+    }
+
+    /// 连续计算循环当前的批次大小
+    pub fn continuous_batch_size(&self) -> u32 {
+        self.batch_tuner.as_ref()
+            .and_then(|tuner| tuner.lock().ok().map(|tuner| tuner.batch_size()))
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+    }
+
+    /// 单个工作挖矿时让出CPU的间隔（哈希次数）：调优收敛后使用调优的批次大小，否则使用平台默认值
+    pub fn yield_frequency(&self) -> u64 {
+        self.batch_tuner.as_ref()
+            .and_then(|tuner| tuner.lock().ok().filter(|tuner| tuner.is_converged()).map(|tuner| tuner.batch_size() as u64))
+            .unwrap_or_else(|| platform_optimization::get_platform_yield_frequency() * 10)
+    }
+
+    /// 设置事件总线
+    pub fn set_event_bus(&mut self, event_bus: EventBus) {
+        self.event_bus = Some(event_bus);
+    }
+
+    /// 发布设备事件
+    fn emit_event(&self, kind: MiningEventKind) {
+        if let Some(ref event_bus) = self.event_bus {
+            event_bus.emit(self.device_id(), kind);
+        }
     }
 
     /// 静态版本的挖矿方法，用于在挖矿循环中调用
@@ -471,10 +1001,13 @@ impl SoftwareDevice {
         target_hashrate: f64,
         error_rate: f64,
         batch_size: u32,
+        yield_frequency: u64,
         atomic_stats: &Arc<AtomicStats>,
         hashrate_tracker: &Arc<CgminerHashrateTracker>,
-        result_sender: &Option<mpsc::UnboundedSender<MiningResult>>,
+        result_sender: &Option<ResultSender>,
+        event_bus: &Option<EventBus>,
         last_mining_time: &Arc<RwLock<Option<Instant>>>,
+        stop_signal: &StopSignal,
     ) -> Result<Option<MiningResult>, DeviceError> {
         let start_time = Instant::now();
         let mut hashes_done = 0u64;
@@ -521,13 +1054,27 @@ impl SoftwareDevice {
                     true,
                 );
 
-                // 立即上报找到的解
+                if let Some(ref event_bus) = event_bus {
+                    event_bus.emit(device_id, MiningEventKind::ShareFound {
+                        work_id: work.id.to_string(),
+                        nonce,
+                    });
+                }
+
+                // 立即上报找到的解，只对真正进入通道的结果计数
                 if let Some(ref sender) = result_sender {
-                    if let Err(_) = sender.send(result.clone()) {
-                        debug!("设备 {} 结果通道已关闭", device_id);
-                        return Ok(None);
+                    match sender.send(result, stop_signal.stopped()).await {
+                        SendOutcome::Sent => {
+                            atomic_stats.increment_accepted();
+                            hashrate_tracker.increment_accepted();
+                            debug!("💎 设备 {} 立即上报解: nonce={:08x}", device_id, nonce);
+                        }
+                        SendOutcome::Dropped => debug!("设备 {} 结果被丢弃: nonce={:08x}", device_id, nonce),
+                        SendOutcome::Closed => {
+                            debug!("设备 {} 结果通道已关闭", device_id);
+                            return Ok(None);
+                        }
                     }
-                    debug!("💎 设备 {} 立即上报解: nonce={:08x}", device_id, nonce);
                 } else {
                     // 如果没有通道，保持原有行为
                     debug!("设备 {} 找到有效解: nonce={:08x}", device_id, nonce);
@@ -537,7 +1084,7 @@ impl SoftwareDevice {
             }
 
             // 减少CPU让出频率以提高算力性能
-            if hashes_done % (platform_optimization::get_platform_yield_frequency() * 10) == 0 {
+            if hashes_done % yield_frequency == 0 {
                 tokio::task::yield_now().await;
             }
         }
@@ -572,6 +1119,7 @@ impl SoftwareDevice {
         let start_time = Instant::now();
         let mut hashes_done = 0u64;
         let mut found_solution = None;
+        let yield_frequency = self.yield_frequency();
 
         // 🔧 修复：使用大批次确保算力稳定，与连续挖矿模式保持一致
         let adjusted_batch_size = if self.batch_size >= 50000 {
@@ -620,7 +1168,7 @@ impl SoftwareDevice {
             }
 
             // 减少CPU让出频率以提高算力性能
-            if hashes_done % (platform_optimization::get_platform_yield_frequency() * 10) == 0 {
+            if hashes_done % yield_frequency == 0 {
                 tokio::task::yield_now().await;
             }
         }
@@ -651,15 +1199,15 @@ impl SoftwareDevice {
         Ok(found_solution)
     }
 
-    /// 更新设备温度（仅支持真实温度读取）
-    fn update_temperature(&self) -> Result<(), DeviceError> {
+    /// 更新设备温度（仅支持真实温度读取），返回读取到的温度
+    fn update_temperature(&self) -> Result<Option<f32>, DeviceError> {
 
         // 尝试从温度管理器读取真实温度
         if let Some(ref temp_manager) = self.temperature_manager {
             if temp_manager.has_temperature_monitoring() {
-                match temp_manager.read_temperature() {
-                    Ok(temperature) => {
-                        debug!("设备 {} 读取到真实温度: {:.1}°C", self.device_id(), temperature);
+                match self.read_device_temperature(temp_manager) {
+                    Ok((temperature, scope)) => {
+                        debug!("设备 {} 读取到真实温度: {:.1}°C ({})", self.device_id(), temperature, scope);
 
                         // 更新设备信息中的温度
                         {
@@ -671,6 +1219,10 @@ impl SoftwareDevice {
 
                         // 更新统计信息中的温度 - 使用原子操作
                         self.atomic_stats.update_temperature(temperature);
+                        self.atomic_stats.update_temperature_scope(scope);
+
+                        self.track_thermal_state(temp_manager.classify(temperature), temperature);
+                        return Ok(Some(temperature));
                     }
                     Err(e) => {
                         debug!("设备 {} 温度读取失败: {}", self.device_id(), e);
@@ -697,7 +1249,198 @@ impl SoftwareDevice {
             // 对于原子统计，没有温度管理器时保持默认值
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// 读取设备温度：优先读取绑定核心的温度，否则回退到封装温度
+    fn read_device_temperature(&self, temp_manager: &TemperatureManager) -> Result<(f32, TemperatureScope), TemperatureError> {
+        if let Some((cpu, package_id, core_id)) = self.pinned_cpu() {
+            if let Some(temperature) = temp_manager.read_core_temperature(package_id, core_id) {
+                return Ok((temperature, TemperatureScope::Core { cpu }));
+            }
+        }
+        temp_manager.read_temperature().map(|temperature| (temperature, TemperatureScope::Package))
+    }
+
+    /// 设备绑定的逻辑CPU及其封装ID和物理核心ID
+    fn pinned_cpu(&self) -> Option<(usize, u32, u32)> {
+        let manager = self.cpu_affinity.as_ref()?.read().ok()?;
+        let core = manager.get_device_core(self.device_id())?;
+        let cpu = manager.topology()?.cpu(core.id)?;
+        Some((cpu.id, cpu.package_id, cpu.core_id))
+    }
+
+    /// 设备绑定的CPU封装ID，未绑定或没有拓扑信息时返回None
+    pub fn package_id(&self) -> Option<u32> {
+        self.pinned_cpu().map(|(_, package_id, _)| package_id)
+    }
+
+    /// 记录分摊到设备的功耗 (瓦)，在 `DeviceStats::power_consumption` 中上报
+    pub fn set_power_consumption(&self, watts: f64) {
+        self.atomic_stats.update_power_consumption(watts);
+    }
+
+    /// 当前温度的来源（绑定核心或封装）
+    pub fn temperature_scope(&self) -> TemperatureScope {
+        self.atomic_stats.temperature_scope()
+    }
+
+    /// 记录温度状态，状态变化时发布事件
+    fn track_thermal_state(&self, current: TemperatureStatus, temperature: f32) {
+        let previous = match self.thermal_state.lock() {
+            Ok(mut state) => {
+                if *state == Some(current) {
+                    return;
+                }
+                state.replace(current)
+            }
+            Err(_) => return,
+        };
+
+        if previous.is_some() {
+            info!("设备 {} 温度状态变化: {:?} → {} ({:.1}°C)",
+                  self.device_id(), previous, current, temperature);
+        }
+
+        self.emit_event(MiningEventKind::ThermalStateChanged {
+            previous,
+            current,
+            temperature,
+        });
+    }
+
+    /// 在专用工作线程上运行挖矿循环
+    ///
+    /// CPU绑定在工作线程启动时应用到该线程本身，而不是调用方所在的异步运行时线程。
+    /// 第一次启动时共享状态在绑定之后由工作线程重新分配并交回设备，挖矿循环在绑定之后由
+    /// `make_loop` 在工作线程上构造，两者因此都分配在绑定节点的本地内存上。
+    /// 返回工作线程的句柄：停止时先设置 `mining_stop_signal`，再通过句柄等待线程退出。
+    async fn spawn_mining_worker<M, F>(&mut self, make_loop: M) -> Result<std::thread::JoinHandle<()>, DeviceError>
+    where
+        M: FnOnce(DeviceState) -> F + Send + 'static,
+        F: std::future::Future<Output = ()>,
+    {
+        let device_id = self.device_id();
+        let cpu_affinity = self.cpu_affinity.clone();
+        let event_bus = self.event_bus.clone();
+        let priority_config = self.priority_config.clone();
+        let priority_status = self.priority_status.clone();
+        let relocate = self.state_thread.is_none();
+        let state = self.state();
+        let (state_tx, state_rx) = tokio::sync::oneshot::channel();
+
+        let worker = std::thread::Builder::new()
+            .name(format!("cpu-miner-{}", device_id))
+            .spawn(move || {
+                if let Some(cpu_affinity) = cpu_affinity {
+                    Self::bind_worker_thread(device_id, &cpu_affinity, &event_bus);
+                }
+                if priority_config.is_requested() {
+                    Self::apply_worker_priority(device_id, &priority_config, &priority_status);
+                }
+
+                let state = if relocate { state.relocate() } else { state };
+                let thread_name = std::thread::current().name().unwrap_or_default().to_string();
+                let _ = state_tx.send((state.clone(), thread_name));
+
+                match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime.block_on(make_loop(state)),
+                    Err(e) => warn!("设备 {} 无法创建挖矿工作线程运行时: {}", device_id, e),
+                }
+            })
+            .map_err(|e| DeviceError::hardware_error(format!("无法创建设备 {} 的挖矿工作线程: {}", device_id, e)))?;
+
+        // 设备此后读取工作线程分配的状态，与挖矿循环使用同一份
+        match state_rx.await {
+            Ok((state, thread_name)) => {
+                self.set_state(state);
+                self.state_thread = Some(thread_name);
+            }
+            Err(_) => warn!("设备 {} 挖矿工作线程在交回共享状态前退出", device_id),
+        }
+        Ok(worker)
+    }
+
+    fn state(&self) -> DeviceState {
+        DeviceState {
+            atomic_stats: self.atomic_stats.clone(),
+            work_queue: self.work_queue.clone(),
+            hashrate_tracker: self.hashrate_tracker.clone(),
+            batch_stats_updater: self.batch_stats_updater.clone(),
+        }
+    }
+
+    fn set_state(&mut self, state: DeviceState) {
+        self.atomic_stats = state.atomic_stats;
+        self.work_queue = state.work_queue;
+        self.hashrate_tracker = state.hashrate_tracker;
+        self.batch_stats_updater = state.batch_stats_updater;
+    }
+
+    /// 共享状态（统计、工作队列、算力追踪器）分配所在的线程名
+    ///
+    /// 第一次启动后为挖矿工作线程的名称 `cpu-miner-<设备ID>`，尚未启动过时返回 `None`。
+    pub fn state_thread(&self) -> Option<&str> {
+        self.state_thread.as_deref()
+    }
+
+    /// 为当前（挖矿工作）线程设置调度策略、nice值和I/O优先级并记录结果
+    fn apply_worker_priority(
+        device_id: u32,
+        config: &PriorityConfig,
+        priority_status: &Arc<RwLock<Option<PriorityStatus>>>,
+    ) {
+        let status = priority::apply_to_current_thread(config);
+
+        // 优先级设置失败不应该阻止设备启动，只是记录警告
+        for (name, outcome) in [("调度策略", &status.policy), ("nice值", &status.nice), ("I/O优先级", &status.ioprio)] {
+            if outcome.is_failure() {
+                warn!("设备 {} 挖矿线程{}设置失败: {:?}", device_id, name, outcome);
+            }
+        }
+        info!("设备 {} 挖矿线程优先级: 调度策略 {:?}, nice {:?}, I/O {:?}",
+              device_id, status.policy, status.nice, status.ioprio);
+
+        if let Ok(mut current) = priority_status.write() {
+            *current = Some(status);
+        }
+    }
+
+    /// 将当前（挖矿工作）线程绑定到设备分配的CPU核心并发布绑定事件
+    fn bind_worker_thread(
+        device_id: u32,
+        cpu_affinity: &Arc<RwLock<CpuAffinityManager>>,
+        event_bus: &Option<EventBus>,
+    ) {
+        let affinity_manager = match cpu_affinity.read() {
+            Ok(manager) => manager,
+            Err(e) => {
+                warn!("设备 {} 获取CPU绑定管理器失败: {}", device_id, e);
+                return;
+            }
+        };
+        if !affinity_manager.is_enabled() {
+            return;
+        }
+
+        let core_id = affinity_manager.get_device_core(device_id).map(|core| core.id);
+        let result = affinity_manager.bind_current_thread(device_id);
+        drop(affinity_manager);
+
+        // CPU绑定失败不应该阻止设备启动，只是记录警告
+        let kind = match result {
+            Ok(()) => {
+                info!("✅ 设备 {} 挖矿线程已绑定到CPU核心 {:?}", device_id, core_id);
+                MiningEventKind::AffinityBinding { core_id, success: true, message: None }
+            }
+            Err(e) => {
+                warn!("设备 {} CPU绑定失败: {}", device_id, e);
+                MiningEventKind::AffinityBinding { core_id, success: false, message: Some(e) }
+            }
+        };
+        if let Some(bus) = event_bus {
+            bus.emit(device_id, kind);
+        }
     }
 
     /// 启动连续计算模式 - 真正的高性能模式
@@ -712,28 +1455,67 @@ impl SoftwareDevice {
             *status = DeviceStatus::Running;
         }
 
-        self.mining_stop_signal.store(false, std::sync::atomic::Ordering::Relaxed);
+        // 每次启动使用新的停止信号，避免尚未退出的旧工作线程被重新激活
+        self.mining_stop_signal = Arc::new(StopSignal::default());
 
         // 启动连续计算循环
-        let work_queue = self.work_queue.clone();
-        let atomic_stats = self.atomic_stats.clone();
-        let hashrate_tracker = self.hashrate_tracker.clone();
         let result_sender = self.result_sender.clone();
+        let event_bus = self.event_bus.clone();
         let stop_signal = self.mining_stop_signal.clone();
-
-        let continuous_mining_task = tokio::spawn(async move {
-            info!("🔥 设备 {} 高性能连续计算循环已启动", device_id);
+        let hashrate_cap = self.hashrate_cap.clone();
+        let nonce_range = self.nonce_range.clone();
+        let cpu_affinity = self.cpu_affinity.clone();
+        let duty_cycle = self.duty_cycle.clone();
+        let thermal_duty = self.thermal_duty.clone();
+        let power_duty = self.power_duty.clone();
+        let schedule_duty = self.schedule_duty.clone();
+        let pause_flags = self.pause_flags.clone();
+        let batch_tuner = self.batch_tuner.clone();
+        let work_submitted_at = self.work_submitted_at.clone();
+        let hash_implementation = self.hash_implementation;
+
+        let continuous_mining_task = self.spawn_mining_worker(move |state| async move {
+            info!("🔥 设备 {} 高性能连续计算循环已启动 (哈希实现: {})", device_id, hash_implementation);
+            let DeviceState { atomic_stats, work_queue, hashrate_tracker, .. } = state;
+
+            // 区块头缓冲在工作线程完成CPU绑定后分配，位于绑定节点的本地内存
+            let mut header_buf: Box<[u8; 80]> = Box::new([0u8; 80]);
 
             let mut current_work: Option<Arc<Work>> = None;
-            let mut nonce_iterator = 0u32;
-
-            while !stop_signal.load(std::sync::atomic::Ordering::Relaxed) {
+            let mut range = NonceRange::unpack(nonce_range.load(Ordering::Relaxed));
+            let mut nonce_offset = 0u64;
+            // 算力上限控制窗口
+            let mut window_start = Instant::now();
+            let mut window_hashes = 0u64;
+            // 上一轮是否在计算（暂停期间的等待不计入响应延迟）
+            let mut hashing = false;
+            // 上一批次后是否按占空比或算力上限休眠（休眠时间与批次大小无关，这样的样本不计入响应延迟）
+            let mut throttled = false;
+
+            while !stop_signal.is_stopped() {
                 // 检查是否有新的工作模板
                 if let Some(new_work) = work_queue.dequeue_work() {
                     if current_work.as_ref().map_or(true, |cw| cw.id != new_work.id) {
                         debug!("设备 {} 切换到新工作模板: {}", device_id, new_work.id);
                         current_work = Some(new_work);
-                        nonce_iterator = 0; // 重置nonce
+                        nonce_offset = 0; // 重置nonce
+
+                        let submitted_at = work_submitted_at.write().ok().and_then(|mut at| at.take());
+                        if let (true, Some(submitted_at), Some(tuner)) = (hashing && !throttled, submitted_at, &batch_tuner) {
+                            if let Ok(mut tuner) = tuner.lock() {
+                                tuner.record_response(submitted_at.elapsed());
+                            }
+                        }
+                    }
+                }
+
+                // 负载均衡迁移或句柄调整核心后重新绑定（没有工作时也处理）
+                if let Some(ref cpu_affinity) = cpu_affinity {
+                    let rebind = cpu_affinity.read()
+                        .map(|manager| manager.take_rebind_request(device_id))
+                        .unwrap_or(false);
+                    if rebind {
+                        Self::bind_worker_thread(device_id, cpu_affinity, &event_bus);
                     }
                 }
 
@@ -746,21 +1528,50 @@ impl SoftwareDevice {
                     }
                 };
 
-                // 🔥 核心紧凑循环 - 在这里最大化算力
-                let batch_size = 100_000u32; // 一次处理一个大批次
-                let mut hashes_done_in_batch = 0u64;
-
-                for i in 0..batch_size {
-                    let nonce = nonce_iterator.wrapping_add(i);
+                // 核心重新分片后切换到新的nonce区间
+                let latest_range = NonceRange::unpack(nonce_range.load(Ordering::Relaxed));
+                if latest_range != range {
+                    range = latest_range;
+                    nonce_offset = 0;
+                }
 
-                    let mut header_data = work_template.header.clone();
-                    let nonce_bytes = nonce.to_le_bytes();
-                    let start_idx = header_data.len() - 4;
-                    header_data[start_idx..].copy_from_slice(&nonce_bytes);
+                // 占空比为0或存在暂停原因时暂停计算（包括温度调节暂停和功率上限暂停）
+                let duty = f64::from_bits(duty_cycle.load(Ordering::Relaxed))
+                    * f64::from_bits(thermal_duty.load(Ordering::Relaxed))
+                    * f64::from_bits(power_duty.load(Ordering::Relaxed))
+                    * f64::from_bits(schedule_duty.load(Ordering::Relaxed));
+                if duty <= 0.0 || pause_flags.is_paused() {
+                    hashing = false;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+                let batch_start = Instant::now();
 
-                    let hash = optimized_double_sha256(&header_data);
+                // 🔥 核心紧凑循环 - 在这里最大化算力
+                // 批次大小决定停止和新工作的响应延迟，启用调优时由调优器决定
+                let batch_size = batch_tuner.as_ref()
+                    .and_then(|tuner| tuner.lock().ok().map(|tuner| tuner.batch_size()))
+                    .unwrap_or(DEFAULT_BATCH_SIZE);
+                let mut hashes_done_in_batch = 0u64;
+                header_buf.copy_from_slice(&work_template.header[..]);
+                let mut hasher = HeaderHasher::new(hash_implementation, &header_buf);
+
+                // 每次交给哈希实现一组nonce，多路实现同时计算
+                let mut nonces = [0u32; HASH_CHUNK];
+                let mut hashes = [[0u8; 32]; HASH_CHUNK];
+                let mut i = 0u32;
+                while i < batch_size {
+                    let count = (batch_size - i).min(HASH_CHUNK as u32) as usize;
+                    for (k, nonce) in nonces[..count].iter_mut().enumerate() {
+                        *nonce = range.nonce_at(nonce_offset + (i as usize + k) as u64);
+                    }
+                    hasher.hash(&nonces[..count], &mut hashes[..count]);
+                    i += count as u32;
 
-                    if cgminer_core::meets_target(&hash, &work_template.target) {
+                    for (&nonce, hash) in nonces[..count].iter().zip(&hashes[..count]) {
+                        if !cgminer_core::meets_target(hash, &work_template.target) {
+                            continue;
+                        }
                         let result = MiningResult::new(
                             work_template.id,
                             device_id,
@@ -769,28 +1580,85 @@ impl SoftwareDevice {
                             true,
                         );
 
+                        if let Some(ref event_bus) = event_bus {
+                            event_bus.emit(device_id, MiningEventKind::ShareFound {
+                                work_id: work_template.id.to_string(),
+                                nonce,
+                            });
+                        }
+
                         if let Some(ref sender) = result_sender {
-                            if sender.send(result.clone()).is_ok() {
-                                hashrate_tracker.increment_accepted();
-                                atomic_stats.increment_accepted();
+                            // 只对真正进入通道的结果计数
+                            match sender.send(result, stop_signal.stopped()).await {
+                                SendOutcome::Sent => {
+                                    hashrate_tracker.increment_accepted();
+                                    atomic_stats.increment_accepted();
+                                }
+                                SendOutcome::Dropped => {}
+                                SendOutcome::Closed => debug!("设备 {} 结果通道已关闭", device_id),
                             }
                         }
                     }
                 }
                 hashes_done_in_batch += batch_size as u64;
-                nonce_iterator = nonce_iterator.wrapping_add(batch_size);
+                nonce_offset = (nonce_offset + batch_size as u64) % range.size();
+                hashing = true;
+
+                // 记录批次的哈希时间，调优器据此调整批次大小
+                if let Some(ref tuner) = batch_tuner {
+                    let hashing_time = batch_start.elapsed();
+                    let step = tuner.lock().ok().and_then(|mut tuner| {
+                        tuner.record_batch(hashes_done_in_batch, hashing_time)
+                            .map(|result| (result, tuner.batch_size()))
+                    });
+                    if let Some((result, next_batch_size)) = step {
+                        debug!("设备 {} 批次 {}: {:.2} MH/s, 延迟 {:.1} ms → 批次 {}", device_id, result.batch_size,
+                               result.hashrate / 1_000_000.0, result.latency_ms, next_batch_size);
+                        if result.converged {
+                            if let Some(ref event_bus) = event_bus {
+                                event_bus.emit(device_id, MiningEventKind::BatchSizeTuned {
+                                    batch_size: result.batch_size,
+                                    hashrate: result.hashrate,
+                                    latency_ms: result.latency_ms,
+                                });
+                            }
+                        }
+                    }
+                }
 
                 // 批次完成后更新统计
                 atomic_stats.record_hashes(hashes_done_in_batch);
                 hashrate_tracker.add_hashes(hashes_done_in_batch);
 
+                // 按占空比休眠
+                throttled = duty < 1.0;
+                if throttled {
+                    tokio::time::sleep(batch_start.elapsed().mul_f64((1.0 - duty) / duty)).await;
+                }
+
+                // 设置了算力上限且超过上限时休眠，窗口每秒重置一次
+                window_hashes += hashes_done_in_batch;
+                let cap = f64::from_bits(hashrate_cap.load(Ordering::Relaxed));
+                if cap > 0.0 {
+                    let min_elapsed = Duration::from_secs_f64(window_hashes as f64 / cap);
+                    let elapsed = window_start.elapsed();
+                    if elapsed < min_elapsed {
+                        throttled = true;
+                        tokio::time::sleep(min_elapsed - elapsed).await;
+                    }
+                }
+                if window_start.elapsed() >= Duration::from_secs(1) {
+                    window_start = Instant::now();
+                    window_hashes = 0;
+                }
+
                 // 在完成一个批次后，让出CPU给其他任务
                 // 这可以防止在高负载下某些任务（如统计）被饿死
                 tokio::task::yield_now().await;
             }
 
             info!("🏁 设备 {} 连续计算完成", device_id);
-        });
+        }).await?;
 
         // 保存任务句柄
         {
@@ -801,11 +1669,20 @@ impl SoftwareDevice {
         }
 
         self.start_time = Some(tokio::time::Instant::now());
+        self.emit_event(MiningEventKind::DeviceStarted);
         info!("✅ 设备 {} 连续计算模式启动完成", device_id);
         Ok(())
     }
 }
 
+impl Drop for SoftwareDevice {
+    fn drop(&mut self) {
+        // 挖矿循环运行在专用线程上，设备被丢弃时通知其退出。
+        // Drop 中无法异步等待，线程句柄随之分离，线程在下一次检查停止信号时自行结束
+        self.mining_stop_signal.stop();
+    }
+}
+
 #[async_trait]
 impl MiningDevice for SoftwareDevice {
     /// 获取设备ID
@@ -877,20 +1754,6 @@ impl MiningDevice for SoftwareDevice {
         let device_id = self.device_id();
         info!("启动软算法设备 {}", device_id);
 
-        // 如果启用了CPU绑定，为当前线程设置CPU绑定
-        if let Some(cpu_affinity) = &self.cpu_affinity {
-            let affinity_manager = cpu_affinity.read().map_err(|e| {
-                DeviceError::hardware_error(format!("Failed to acquire read lock: {}", e))
-            })?;
-
-            if let Err(e) = affinity_manager.bind_current_thread(device_id) {
-                warn!("设备 {} CPU绑定失败: {}", device_id, e);
-                // CPU绑定失败不应该阻止设备启动，只是记录警告
-            } else {
-                info!("✅ 设备 {} 已绑定到指定CPU核心", device_id);
-            }
-        }
-
         // 设置状态为运行中
         {
             let mut status = self.status.write().map_err(|e| {
@@ -899,24 +1762,24 @@ impl MiningDevice for SoftwareDevice {
             *status = DeviceStatus::Running;
         }
 
-        // 重置停止信号
-        self.mining_stop_signal.store(false, std::sync::atomic::Ordering::Relaxed);
+        // 每次启动使用新的停止信号，避免尚未退出的旧工作线程被重新激活
+        self.mining_stop_signal = Arc::new(StopSignal::default());
 
         // 启动持续的挖矿循环任务
-        let work_queue = self.work_queue.clone();
-        let atomic_stats = self.atomic_stats.clone();
-        let hashrate_tracker = self.hashrate_tracker.clone();
         let result_sender = self.result_sender.clone();
-        let target_hashrate = self.target_hashrate;
+        let event_bus = self.event_bus.clone();
+        let target_hashrate = self.target_hashrate();
         let error_rate = self.error_rate;
         let batch_size = self.batch_size;
+        let yield_frequency = self.yield_frequency();
         let stop_signal = self.mining_stop_signal.clone();
         let last_mining_time = self.last_mining_time.clone();
 
-        let mining_task = tokio::spawn(async move {
+        let mining_task = self.spawn_mining_worker(move |state| async move {
             info!("🚀 设备 {} 挖矿循环已启动，目标算力: {:.2} H/s", device_id, target_hashrate);
+            let DeviceState { atomic_stats, work_queue, hashrate_tracker, .. } = state;
 
-            while !stop_signal.load(std::sync::atomic::Ordering::Relaxed) {
+            while !stop_signal.is_stopped() {
                 // 从工作队列获取工作
                 if let Some(work) = work_queue.dequeue_work() {
                     debug!("设备 {} 开始处理工作", device_id);
@@ -928,10 +1791,13 @@ impl MiningDevice for SoftwareDevice {
                         target_hashrate,
                         error_rate,
                         batch_size,
+                        yield_frequency,
                         &atomic_stats,
                         &hashrate_tracker,
                         &result_sender,
+                        &event_bus,
                         &last_mining_time,
+                        &stop_signal,
                     ).await {
                         if result.is_some() {
                             debug!("设备 {} 完成工作处理", device_id);
@@ -946,7 +1812,7 @@ impl MiningDevice for SoftwareDevice {
             }
 
             info!("设备 {} 挖矿循环已停止", device_id);
-        });
+        }).await?;
 
         // 保存任务句柄
         {
@@ -957,6 +1823,7 @@ impl MiningDevice for SoftwareDevice {
         }
 
         self.start_time = Some(Instant::now());
+        self.emit_event(MiningEventKind::DeviceStarted);
         info!("软算法设备 {} 启动完成，挖矿循环已激活", device_id);
         Ok(())
     }
@@ -966,17 +1833,19 @@ impl MiningDevice for SoftwareDevice {
         info!("停止软算法设备 {}", self.device_id());
 
         // 设置停止信号
-        self.mining_stop_signal.store(true, std::sync::atomic::Ordering::Relaxed);
-
-        // 停止挖矿任务
-        {
-            let mut handle = self.mining_task_handle.lock().map_err(|e| {
-                DeviceError::hardware_error(format!("Failed to acquire mutex: {}", e))
-            })?;
-
-            if let Some(task_handle) = handle.take() {
-                task_handle.abort();
-                info!("设备 {} 挖矿任务已停止", self.device_id());
+        self.mining_stop_signal.stop();
+
+        // 等待挖矿工作线程退出（线程会在下一次检查停止信号时结束循环）
+        let worker = self.mining_task_handle.lock().map_err(|e| {
+            DeviceError::hardware_error(format!("Failed to acquire mutex: {}", e))
+        })?.take();
+
+        if let Some(worker) = worker {
+            match tokio::time::timeout(WORKER_JOIN_TIMEOUT, tokio::task::spawn_blocking(move || worker.join())).await {
+                Ok(Ok(Ok(()))) => info!("设备 {} 挖矿工作线程已退出", self.device_id()),
+                Ok(Ok(Err(_))) => warn!("设备 {} 挖矿工作线程异常退出", self.device_id()),
+                Ok(Err(e)) => warn!("设备 {} 等待挖矿工作线程失败: {}", self.device_id(), e),
+                Err(_) => warn!("设备 {} 挖矿工作线程在 {:?} 内未退出", self.device_id(), WORKER_JOIN_TIMEOUT),
             }
         }
 
@@ -991,8 +1860,10 @@ impl MiningDevice for SoftwareDevice {
         let cleared_count = self.work_queue.clear_stale_work(0); // 清除所有旧工作
         if cleared_count > 0 {
             debug!("设备 {} 停止时清除了 {} 个旧工作", self.device_id(), cleared_count);
+            self.emit_event(MiningEventKind::StaleFlush { cleared: cleared_count });
         }
 
+        self.emit_event(MiningEventKind::DeviceStopped);
         info!("软算法设备 {} 已停止", self.device_id());
         Ok(())
     }
@@ -1011,9 +1882,14 @@ impl MiningDevice for SoftwareDevice {
         let device_id = self.device_id();
 
         // 使用无锁工作队列提交工作 - 零拷贝
+        let work_id = work.id.to_string();
         match self.work_queue.enqueue_work(work) {
             Ok(()) => {
                 debug!("设备 {} 成功提交工作到队列", device_id);
+                if let Ok(mut submitted_at) = self.work_submitted_at.write() {
+                    *submitted_at = Some(Instant::now());
+                }
+                self.emit_event(MiningEventKind::NewWork { work_id });
                 Ok(())
             }
             Err(rejected_work) => {
@@ -1034,14 +1910,21 @@ impl MiningDevice for SoftwareDevice {
             // 执行挖矿 - work现在是Arc<Work>，需要解引用
             let result = self.mine_work(&*work).await?;
 
+            if let Some(ref mining_result) = result {
+                self.emit_event(MiningEventKind::ShareFound {
+                    work_id: work.id.to_string(),
+                    nonce: mining_result.nonce,
+                });
+            }
+
             // 如果有结果通道且有结果，则通过通道立即发送
             if let Some(ref sender) = self.result_sender {
                 if let Some(ref mining_result) = result {
-                    if let Err(_) = sender.send(mining_result.clone()) {
-                        warn!("设备 {} 结果通道发送失败", self.device_id());
-                    } else {
-                        debug!("设备 {} 结果已通过通道发送: work_id={}",
-                               self.device_id(), mining_result.work_id);
+                    match sender.send(mining_result.clone(), self.mining_stop_signal.stopped()).await {
+                        SendOutcome::Sent => debug!("设备 {} 结果已通过通道发送: work_id={}",
+                                                    self.device_id(), mining_result.work_id),
+                        SendOutcome::Dropped => debug!("设备 {} 结果通道已满，结果被丢弃", self.device_id()),
+                        SendOutcome::Closed => warn!("设备 {} 结果通道发送失败", self.device_id()),
                     }
                 }
             }
@@ -1166,6 +2049,7 @@ impl MiningDevice for SoftwareDevice {
         let cleared_count = self.work_queue.clear_stale_work(new_version);
         if cleared_count > 0 {
             info!("设备 {} 重置时清理了 {} 个过期工作", self.device_id(), cleared_count);
+            self.emit_event(MiningEventKind::StaleFlush { cleared: cleared_count });
         }
 
         // 重置时间
@@ -1183,11 +2067,19 @@ impl MiningDevice for SoftwareDevice {
         // 检查设备状态
         let status_ok = matches!(status, DeviceStatus::Running | DeviceStatus::Idle);
 
-        // 检查温度
-        let temp_ok = if let Some(temp) = stats.temperature {
-            temp.celsius < 90.0 // 温度不超过90度
-        } else {
-            true
+        // 检查温度：不超过危险阈值（温度配置和设备温度限制中较低的一个）
+        let temp_ok = match stats.temperature {
+            Some(temp) => {
+                let device_config = self.config.read().map_err(|e| {
+                    DeviceError::hardware_error(format!("Failed to acquire read lock: {}", e))
+                })?;
+                let critical = match self.temperature_manager {
+                    Some(ref manager) => Self::thermal_thresholds(manager.config(), &device_config).1,
+                    None => device_config.temperature_limit,
+                };
+                temp.celsius < critical
+            }
+            None => true,
         };
 
         // 检查错误率
@@ -0,0 +1,311 @@
+//! # 设备句柄模块
+//!
+//! 本模块提供基于句柄的单设备访问能力，让调用方在核心运行期间
+//! 对单个设备进行启用/禁用、重启、调整目标算力和CPU绑定等操作，
+//! 无需通过 `as_any_mut` 向下转型。
+//!
+//! ## 🚀 主要功能
+//!
+//! - 🔧 启用/禁用单个设备（禁用的设备不再接收工作）
+//! - 🔧 重启单个设备的连续计算循环
+//! - 🔧 运行时调整目标算力
+//! - 🔧 运行时调整CPU绑定核心（工作线程在下一个批次重新绑定）
+//! - 📊 查询完整的 `DeviceInfo` / `DeviceStats`
+//! - 📊 取出本设备上报到核心结果缓冲中的结果
+//!
+//! ## 🔄 使用示例
+//!
+//! ```rust,no_run
+//! use cgminer_core::MiningCore;
+//! use cgminer_cpu_btc_core::SoftwareMiningCore;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut core = SoftwareMiningCore::new("cpu-core".to_string());
+//! core.initialize(core.default_config()).await?;
+//! core.start().await?;
+//!
+//! if let Some(handle) = core.device(1000).await {
+//!     handle.set_target_hashrate(5_000_000.0).await?;
+//!     handle.disable().await?;
+//!     let stats = handle.stats().await?;
+//!     println!("设备 {} 累计 {} 个哈希", handle.id(), stats.total_hashes);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## ⚙️ 锁的使用
+//!
+//! 设备表只在查找设备时短暂加锁，每个设备有自己的锁：停止、启动等需要等待的操作
+//! 只锁住目标设备，不会阻塞核心对其他设备的统计和工作分发。
+//!
+//! [`DeviceHandle`] 同时实现了 `MiningDevice`，因此
+//! `MiningCore::get_devices` 直接返回句柄。
+
+use crate::device::SoftwareDevice;
+use crate::priority::PriorityStatus;
+use crate::result_buffer::ResultBuffer;
+use crate::temperature::TemperatureScope;
+use crate::tuning::TuningResult;
+use async_trait::async_trait;
+use cgminer_core::{
+    DeviceConfig, DeviceError, DeviceInfo, DeviceStats, DeviceStatus, MiningDevice, MiningResult, Work,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// 设备表中的单个设备（每个设备有自己的锁）
+pub type SharedDevice = Arc<Mutex<SoftwareDevice>>;
+
+/// 核心持有的设备表
+pub type DeviceMap = Arc<Mutex<HashMap<u32, SharedDevice>>>;
+
+/// 复制设备表中的设备引用（按设备ID排序）后立即释放表锁
+///
+/// 需要逐个等待设备的操作（读取统计、停止等）应先取快照，避免持有表锁等待。
+pub(crate) async fn snapshot(devices: &DeviceMap) -> Vec<(u32, SharedDevice)> {
+    let mut snapshot: Vec<(u32, SharedDevice)> = devices.lock().await
+        .iter()
+        .map(|(device_id, device)| (*device_id, device.clone()))
+        .collect();
+    snapshot.sort_unstable_by_key(|(device_id, _)| *device_id);
+    snapshot
+}
+
+/// 单设备句柄
+#[derive(Clone)]
+pub struct DeviceHandle {
+    device_id: u32,
+    devices: DeviceMap,
+    results: Arc<ResultBuffer>,
+}
+
+impl DeviceHandle {
+    /// 创建设备句柄
+    pub(crate) fn new(device_id: u32, devices: DeviceMap, results: Arc<ResultBuffer>) -> Self {
+        Self { device_id, devices, results }
+    }
+
+    /// 设备不存在时的错误
+    fn missing(&self) -> DeviceError {
+        DeviceError::hardware_error(format!("设备 {} 不存在或已被移除", self.device_id))
+    }
+
+    /// 查找句柄指向的设备，返回后设备表锁已释放
+    async fn device(&self) -> Result<SharedDevice, DeviceError> {
+        self.devices.lock().await
+            .get(&self.device_id)
+            .cloned()
+            .ok_or_else(|| self.missing())
+    }
+
+    /// 对句柄指向的设备执行操作
+    async fn with_device<T>(
+        &self,
+        f: impl FnOnce(&mut SoftwareDevice) -> Result<T, DeviceError>,
+    ) -> Result<T, DeviceError> {
+        let device = self.device().await?;
+        let mut device = device.lock().await;
+        f(&mut *device)
+    }
+
+    /// 获取设备ID
+    pub fn id(&self) -> u32 {
+        self.device_id
+    }
+
+    /// 获取设备信息
+    pub async fn info(&self) -> Result<DeviceInfo, DeviceError> {
+        self.device().await?.lock().await.get_info().await
+    }
+
+    /// 获取设备统计信息
+    pub async fn stats(&self) -> Result<DeviceStats, DeviceError> {
+        self.device().await?.lock().await.get_stats().await
+    }
+
+    /// 获取设备状态
+    pub async fn status(&self) -> Result<DeviceStatus, DeviceError> {
+        self.device().await?.lock().await.get_status().await
+    }
+
+    /// 设备是否启用
+    pub async fn is_enabled(&self) -> Result<bool, DeviceError> {
+        self.with_device(|device| Ok(device.is_enabled())).await
+    }
+
+    /// 启用设备并启动连续计算
+    pub async fn enable(&self) -> Result<(), DeviceError> {
+        let device = self.device().await?;
+        let mut device = device.lock().await;
+
+        device.set_enabled(true)?;
+        if !device.is_running() {
+            device.start_continuous_mining().await?;
+        }
+        info!("设备 {} 已启用", self.device_id);
+        Ok(())
+    }
+
+    /// 禁用设备并停止挖矿
+    pub async fn disable(&self) -> Result<(), DeviceError> {
+        let device = self.device().await?;
+        let mut device = device.lock().await;
+
+        device.set_enabled(false)?;
+        device.stop().await?;
+        info!("设备 {} 已禁用", self.device_id);
+        Ok(())
+    }
+
+    /// 重启设备的连续计算循环
+    pub async fn restart(&self) -> Result<(), DeviceError> {
+        let device = self.device().await?;
+        let mut device = device.lock().await;
+
+        device.stop().await?;
+        if device.is_enabled() {
+            device.start_continuous_mining().await?;
+        }
+        Ok(())
+    }
+    /// 获取设备温度的来源（绑定核心或封装）
+    pub async fn temperature_scope(&self) -> Result<TemperatureScope, DeviceError> {
+        self.with_device(|device| Ok(device.temperature_scope())).await
+    }
+
+    /// 获取挖矿线程优先级设置的结果，未请求任何设置时为 `None`
+    pub async fn priority_status(&self) -> Result<Option<PriorityStatus>, DeviceError> {
+        self.with_device(|device| Ok(device.priority_status())).await
+    }
+
+    /// 获取批次大小调优的当前结果，未启用调优时为 `None`
+    pub async fn batch_tuning(&self) -> Result<Option<TuningResult>, DeviceError> {
+        self.with_device(|device| Ok(device.batch_tuning())).await
+    }
+
+    /// 获取连续计算循环当前的批次大小
+    pub async fn continuous_batch_size(&self) -> Result<u32, DeviceError> {
+        self.with_device(|device| Ok(device.continuous_batch_size())).await
+    }
+
+    /// 获取设备当前的占空比 (0.0-1.0)
+    pub async fn duty_cycle(&self) -> Result<f64, DeviceError> {
+        self.with_device(|device| Ok(device.duty_cycle())).await
+    }
+
+    /// 获取设备实际生效的占空比（含温度调节和功率上限）
+    pub async fn effective_duty_cycle(&self) -> Result<f64, DeviceError> {
+        self.with_device(|device| Ok(device.effective_duty_cycle())).await
+    }
+
+    /// 获取连续计算模式的算力上限 (H/s)，0表示不限制
+    pub async fn hashrate_cap(&self) -> Result<f64, DeviceError> {
+        self.with_device(|device| Ok(device.hashrate_cap())).await
+    }
+
+    /// 设置目标算力 (H/s)，同时作为连续计算的算力上限，0表示不限制
+    pub async fn set_target_hashrate(&self, hashrate: f64) -> Result<(), DeviceError> {
+        if !hashrate.is_finite() || hashrate < 0.0 {
+            return Err(DeviceError::hardware_error(format!("无效的目标算力: {}", hashrate)));
+        }
+        self.with_device(|device| {
+            device.set_target_hashrate(hashrate);
+            Ok(())
+        }).await
+    }
+
+    /// 将设备重新绑定到指定的CPU（逻辑CPU编号，必须在可用核心中）
+    ///
+    /// 运行中设备的工作线程在下一个批次重新绑定到新核心（结果通过 `AffinityBinding` 事件上报），
+    /// 未运行的设备在下次启动时绑定。
+    pub async fn set_affinity_core(&self, cpu: usize) -> Result<(), DeviceError> {
+        let cpu_affinity = self.with_device(|device| {
+            device.cpu_affinity().cloned().ok_or_else(|| {
+                DeviceError::hardware_error(format!("设备 {} 未配置CPU绑定", self.device_id))
+            })
+        }).await?;
+
+        let mut manager = cpu_affinity.write().map_err(|e| {
+            DeviceError::hardware_error(format!("Failed to acquire write lock: {}", e))
+        })?;
+        manager.set_device_core(self.device_id, cpu)
+            .map_err(DeviceError::hardware_error)?;
+        Ok(())
+    }
+
+    /// 取出本设备上报到核心结果缓冲中的最早一个结果
+    pub fn take_result(&self) -> Option<MiningResult> {
+        self.results.take_device_result(self.device_id)
+    }
+}
+
+#[async_trait]
+impl MiningDevice for DeviceHandle {
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    async fn get_info(&self) -> Result<DeviceInfo, DeviceError> {
+        self.info().await
+    }
+
+    async fn initialize(&mut self, config: DeviceConfig) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.initialize(config).await
+    }
+
+    async fn start(&mut self) -> Result<(), DeviceError> {
+        self.enable().await
+    }
+
+    async fn stop(&mut self) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.stop().await
+    }
+
+    async fn restart(&mut self) -> Result<(), DeviceError> {
+        DeviceHandle::restart(self).await
+    }
+
+    async fn submit_work(&mut self, work: Arc<Work>) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.submit_work(work).await
+    }
+
+    async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError> {
+        // 连续计算模式下结果经核心复算后进入结果缓冲，从中取出本设备的结果
+        Ok(self.take_result())
+    }
+
+    async fn get_status(&self) -> Result<DeviceStatus, DeviceError> {
+        self.status().await
+    }
+
+    async fn get_stats(&self) -> Result<DeviceStats, DeviceError> {
+        self.stats().await
+    }
+
+    async fn set_frequency(&mut self, frequency: u32) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.set_frequency(frequency).await
+    }
+
+    async fn set_voltage(&mut self, voltage: u32) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.set_voltage(voltage).await
+    }
+
+    async fn set_fan_speed(&mut self, speed: u32) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.set_fan_speed(speed).await
+    }
+
+    async fn reset(&mut self) -> Result<(), DeviceError> {
+        self.device().await?.lock().await.reset().await
+    }
+
+    async fn health_check(&self) -> Result<bool, DeviceError> {
+        self.device().await?.lock().await.health_check().await
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,223 @@
+//! # 挖矿事件流模块
+//!
+//! 本模块为CPU挖矿核心提供结构化的事件广播能力，让嵌入方无需轮询
+//! `collect_results` 和 `get_stats` 就能驱动UI界面和审计日志。
+//!
+//! ## 🚀 事件类型
+//!
+//! | 事件 | 触发时机 |
+//! |------|----------|
+//! | `ShareFound` | 设备挖矿循环找到满足目标的nonce |
+//! | `ShareValidated` | 核心复算哈希后确认份额有效 |
+//! | `ShareRejected` | 核心复算失败或对应工作已过期 |
+//! | `NewWork` | 设备收到新的工作模板 |
+//! | `StaleFlush` | 设备清理过期工作 |
+//! | `DeviceStarted` / `DeviceStopped` | 设备生命周期变化 |
+//! | `DeviceFailed` | 设备启动或运行失败 |
+//! | `ThermalStateChanged` | 温度状态在正常/警告/危险之间切换 |
+//! | `AffinityBinding` | 挖矿线程CPU绑定的结果 |
+//! | `MiningPaused` / `MiningResumed` | 核心因某个原因暂停或恢复所有设备 |
+//! | `ProfitabilityChanged` | 收益估算在盈利/亏损之间切换，或自动暂停状态变化 |
+//! | `ScheduleChanged` | 挖矿时间表进入或离开时间段 |
+//! | `BatchSizeTuned` | 设备的批次大小调优收敛 |
+//!
+//! 每个事件都携带时间戳和设备ID，核心级事件的设备ID为 [`CORE_EVENT_DEVICE_ID`]。
+//!
+//! ## 🔄 使用示例
+//!
+//! ```rust
+//! use cgminer_cpu_btc_core::SoftwareMiningCore;
+//! use cgminer_cpu_btc_core::events::MiningEventKind;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let core = SoftwareMiningCore::new("cpu-core".to_string());
+//! let mut events = core.subscribe_events();
+//!
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         if let MiningEventKind::ShareValidated { nonce, .. } = event.kind {
+//!             println!("设备 {} 份额有效: nonce={:08x}", event.device_id, nonce);
+//!         }
+//!     }
+//! });
+//! # }
+//! ```
+//!
+//! ## ⚙️ 实现特点
+//!
+//! - 基于 `tokio::sync::broadcast`，支持任意数量的订阅者
+//! - 没有订阅者时发送开销极低，不影响挖矿循环
+//! - 慢订阅者只会丢失最旧的事件（`RecvError::Lagged`），不会阻塞挖矿
+
+use crate::device::PauseReason;
+use crate::temperature::TemperatureStatus;
+use crate::thermal::ThermalState;
+use serde::Serialize;
+use std::time::SystemTime;
+use tokio::sync::broadcast;
+
+/// 默认事件缓冲容量
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// 核心级事件（不属于某个设备）使用的设备ID
+pub const CORE_EVENT_DEVICE_ID: u32 = 0;
+
+/// 挖矿事件
+#[derive(Debug, Clone, Serialize)]
+pub struct MiningEvent {
+    /// 事件产生时间
+    pub timestamp: SystemTime,
+    /// 产生事件的设备ID
+    pub device_id: u32,
+    /// 事件内容
+    pub kind: MiningEventKind,
+}
+
+/// 挖矿事件内容
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MiningEventKind {
+    /// 设备找到满足目标的份额
+    ShareFound { work_id: String, nonce: u32 },
+    /// 核心验证份额有效
+    ShareValidated { work_id: String, nonce: u32 },
+    /// 核心拒绝份额
+    ShareRejected { work_id: String, nonce: u32, reason: String },
+    /// 设备收到新工作
+    NewWork { work_id: String },
+    /// 设备清理了过期工作
+    StaleFlush { cleared: usize },
+    /// 设备已启动
+    DeviceStarted,
+    /// 设备已停止
+    DeviceStopped,
+    /// 设备失败
+    DeviceFailed { reason: String },
+    /// 温度状态变化
+    ThermalStateChanged {
+        previous: Option<TemperatureStatus>,
+        current: TemperatureStatus,
+        temperature: f32,
+    },
+    /// 温度调节状态变化（降频/暂停/恢复）
+    ThermalThrottle {
+        previous: ThermalState,
+        current: ThermalState,
+        duty_cycle: f64,
+        temperature: f32,
+    },
+    /// CPU绑定结果
+    AffinityBinding {
+        core_id: Option<usize>,
+        success: bool,
+        message: Option<String>,
+    },
+    /// 核心暂停了所有设备
+    MiningPaused { reason: PauseReason },
+    /// 暂停原因解除
+    MiningResumed { reason: PauseReason },
+    /// 收益估算状态变化
+    ProfitabilityChanged {
+        profitable: bool,
+        profit_per_day: f64,
+        break_even_electricity_price: Option<f64>,
+        paused: bool,
+    },
+    /// 挖矿时间表状态变化
+    ScheduleChanged {
+        window: Option<String>,
+        duty_cycle: f64,
+        active: bool,
+    },
+    /// 批次大小调优收敛
+    BatchSizeTuned {
+        batch_size: u32,
+        hashrate: f64,
+        latency_ms: f64,
+    },
+}
+
+/// 事件总线 - 挖矿核心和设备共享的广播发送端
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<MiningEvent>,
+}
+
+impl EventBus {
+    /// 创建事件总线
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// 订阅事件流
+    pub fn subscribe(&self) -> broadcast::Receiver<MiningEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 发布事件（没有订阅者时直接丢弃）
+    pub fn emit(&self, device_id: u32, kind: MiningEventKind) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        let _ = self.sender.send(MiningEvent {
+            timestamp: SystemTime::now(),
+            device_id,
+            kind,
+        });
+    }
+
+    /// 当前订阅者数量
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_event_bus_broadcast() {
+        let bus = EventBus::new(16);
+        let mut rx1 = bus.subscribe();
+        let mut rx2 = bus.subscribe();
+
+        bus.emit(1000, MiningEventKind::DeviceStarted);
+
+        let event1 = rx1.recv().await.unwrap();
+        let event2 = rx2.recv().await.unwrap();
+        assert_eq!(event1.device_id, 1000);
+        assert_eq!(event1.kind, MiningEventKind::DeviceStarted);
+        assert_eq!(event2.kind, MiningEventKind::DeviceStarted);
+    }
+
+    #[test]
+    fn test_emit_without_subscribers() {
+        let bus = EventBus::new(4);
+        assert_eq!(bus.subscriber_count(), 0);
+        // 没有订阅者时不应该出错
+        bus.emit(1, MiningEventKind::DeviceStopped);
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = MiningEvent {
+            timestamp: SystemTime::UNIX_EPOCH,
+            device_id: 7,
+            kind: MiningEventKind::StaleFlush { cleared: 3 },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["device_id"], 7);
+        assert_eq!(json["kind"]["type"], "stale_flush");
+        assert_eq!(json["kind"]["cleared"], 3);
+    }
+}
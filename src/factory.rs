@@ -17,12 +17,14 @@
 //! ```text
 //! 1. 配置验证 → 检查所有参数的有效性
 //! 2. 核心实例化 → 创建SoftwareMiningCore对象
-//! 3. 初始化配置 → 应用用户配置参数
-//! 4. 返回实例 → 提供可用的挖矿核心
+//! 3. 哈希基准测试 → 启用 `[benchmark]` 时选择最快的哈希实现（有缓存时跳过测试）
+//! 4. 初始化配置 → 应用用户配置参数
+//! 5. 返回实例 → 提供可用的挖矿核心
 //! ```
 //!
 //! ### 配置验证功能
-//! - ✅ 设备数量范围检查 (1-100个设备)
+//! 工厂和核心共用 [`crate::config::validate_core_config`]：
+//! - ✅ 设备数量范围检查 (1-1000个设备)
 //! - ✅ 算力参数验证 (最小/最大算力合理性)
 //! - ✅ 错误率范围验证 (0.0-1.0)
 //! - ✅ 设备配置完整性检查
@@ -30,9 +32,9 @@
 //!
 //! ### 默认配置提供
 //! - 📋 4个虚拟设备的标准配置
-//! - 📋 1-5 GH/s的算力范围
+//! - 📋 30-40 MH/s的算力范围
 //! - 📋 1%的默认错误率
-//! - 📋 1000的批处理大小
+//! - 📋 1000000的批处理大小
 //! - 📋 5秒的工作超时时间
 //!
 //! ## 🔄 使用示例
@@ -53,16 +55,11 @@
 //!
 //! ## ⚙️ 配置参数说明
 //!
-//! | 参数名 | 类型 | 默认值 | 说明 |
-//! |--------|------|--------|------|
-//! | `device_count` | u64 | 4 | 虚拟设备数量 |
-//! | `min_hashrate` | f64 | 1e9 | 最小算力 (H/s) |
-//! | `max_hashrate` | f64 | 5e9 | 最大算力 (H/s) |
-//! | `error_rate` | f64 | 0.01 | 模拟错误率 |
-//! | `batch_size` | u64 | 1000 | 批处理大小 |
-//! | `work_timeout_ms` | u64 | 5000 | 工作超时 (ms) |
+//! 所有参数由 [`crate::config::CpuCoreConfig`] 定义，详见 `config` 模块文档。
 
+use crate::config::{self, CpuCoreConfig};
 use crate::core::SoftwareMiningCore;
+use crate::hashing;
 use cgminer_core::{
     CoreFactory, CoreType, CoreInfo, CoreConfig, MiningCore, CoreError
 };
@@ -118,6 +115,17 @@ impl CoreFactory for SoftwareCoreFactory {
         let mut core = SoftwareMiningCore::new(config.name.clone());
         debug!("✅ 软算法核心对象创建成功");
 
+        let cpu_config = config::validate_core_config(&config)?;
+        if cpu_config.benchmark.enabled {
+            debug!("⏱️ 运行哈希实现基准测试...");
+            let report = tokio::task::spawn_blocking(move || {
+                hashing::select_implementation(&cpu_config.benchmark, cpu_config.backend)
+            })
+            .await
+            .map_err(|e| CoreError::runtime(format!("哈希基准测试失败: {}", e)))?;
+            core.set_hash_benchmark(report);
+        }
+
         debug!("🚀 开始初始化软算法核心...");
         match core.initialize(config).await {
             Ok(()) => {
@@ -135,152 +143,11 @@ impl CoreFactory for SoftwareCoreFactory {
 
     /// 验证配置
     fn validate_config(&self, config: &CoreConfig) -> Result<(), CoreError> {
-        if config.name.is_empty() {
-            return Err(CoreError::config("核心名称不能为空"));
-        }
-
-        // 验证设备配置
-        for (i, device_config) in config.devices.iter().enumerate() {
-            if device_config.frequency == 0 {
-                return Err(CoreError::config(format!(
-                    "设备 {} 的频率不能为0", i
-                )));
-            }
-
-            if device_config.voltage == 0 {
-                return Err(CoreError::config(format!(
-                    "设备 {} 的电压不能为0", i
-                )));
-            }
-
-            if device_config.temperature_limit <= 0.0 {
-                return Err(CoreError::config(format!(
-                    "设备 {} 的温度限制必须大于0", i
-                )));
-            }
-
-            if device_config.chip_count == 0 {
-                return Err(CoreError::config(format!(
-                    "设备 {} 的芯片数量不能为0", i
-                )));
-            }
-        }
-
-        // 验证自定义参数
-        if let Some(device_count) = config.custom_params.get("device_count") {
-            if let Some(count) = device_count.as_u64() {
-                if count == 0 {
-                    return Err(CoreError::config("软算法设备数量不能为0"));
-                }
-                if count > 100 {
-                    return Err(CoreError::config("软算法设备数量不能超过100"));
-                }
-            } else {
-                return Err(CoreError::config("device_count 必须是正整数"));
-            }
-        }
-
-        if let Some(min_hashrate) = config.custom_params.get("min_hashrate") {
-            if let Some(hashrate) = min_hashrate.as_f64() {
-                if hashrate <= 0.0 {
-                    return Err(CoreError::config("最小算力必须大于0"));
-                }
-            } else {
-                return Err(CoreError::config("min_hashrate 必须是正数"));
-            }
-        }
-
-        if let Some(max_hashrate) = config.custom_params.get("max_hashrate") {
-            if let Some(hashrate) = max_hashrate.as_f64() {
-                if hashrate <= 0.0 {
-                    return Err(CoreError::config("最大算力必须大于0"));
-                }
-            } else {
-                return Err(CoreError::config("max_hashrate 必须是正数"));
-            }
-        }
-
-        // 验证最小和最大算力的关系
-        if let (Some(min_val), Some(max_val)) = (
-            config.custom_params.get("min_hashrate").and_then(|v| v.as_f64()),
-            config.custom_params.get("max_hashrate").and_then(|v| v.as_f64()),
-        ) {
-            if min_val >= max_val {
-                return Err(CoreError::config("最小算力必须小于最大算力"));
-            }
-        }
-
-        if let Some(error_rate) = config.custom_params.get("error_rate") {
-            if let Some(rate) = error_rate.as_f64() {
-                if rate < 0.0 || rate > 1.0 {
-                    return Err(CoreError::config("错误率必须在0.0到1.0之间"));
-                }
-            } else {
-                return Err(CoreError::config("error_rate 必须是0.0到1.0之间的数值"));
-            }
-        }
-
-        Ok(())
+        config::validate_core_config(config).map(|_| ())
     }
 
     /// 获取默认配置
     fn default_config(&self) -> CoreConfig {
-        use std::collections::HashMap;
-
-        let mut custom_params = HashMap::new();
-        custom_params.insert("device_count".to_string(), serde_json::Value::Number(serde_json::Number::from(4)));
-        custom_params.insert("min_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(1000000000.0).unwrap())); // 1 GH/s
-        custom_params.insert("max_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(5000000000.0).unwrap())); // 5 GH/s
-        custom_params.insert("error_rate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(0.01).unwrap())); // 1% 错误率
-        custom_params.insert("batch_size".to_string(), serde_json::Value::Number(serde_json::Number::from(1000)));
-        custom_params.insert("work_timeout_ms".to_string(), serde_json::Value::Number(serde_json::Number::from(5000)));
-
-        CoreConfig {
-            name: "software-core".to_string(),
-            enabled: true,
-            devices: vec![
-                cgminer_core::DeviceConfig {
-                    chain_id: 0,
-                    enabled: true,
-                    frequency: 600,
-                    voltage: 900,
-                    auto_tune: false,
-                    chip_count: 64,
-                    temperature_limit: 80.0,
-                    fan_speed: Some(50),
-                },
-                cgminer_core::DeviceConfig {
-                    chain_id: 1,
-                    enabled: true,
-                    frequency: 650,
-                    voltage: 920,
-                    auto_tune: false,
-                    chip_count: 64,
-                    temperature_limit: 80.0,
-                    fan_speed: Some(55),
-                },
-                cgminer_core::DeviceConfig {
-                    chain_id: 2,
-                    enabled: true,
-                    frequency: 700,
-                    voltage: 950,
-                    auto_tune: false,
-                    chip_count: 64,
-                    temperature_limit: 80.0,
-                    fan_speed: Some(60),
-                },
-                cgminer_core::DeviceConfig {
-                    chain_id: 3,
-                    enabled: true,
-                    frequency: 750,
-                    voltage: 980,
-                    auto_tune: false,
-                    chip_count: 64,
-                    temperature_limit: 80.0,
-                    fan_speed: Some(65),
-                },
-            ],
-            custom_params,
-        }
+        CpuCoreConfig::default().to_core_config("software-core")
     }
 }
@@ -0,0 +1,472 @@
+//! # 哈希实现与启动基准测试模块
+//!
+//! 本模块提供挖矿循环使用的区块头双重SHA256实现，以及启动时选择最快实现的微基准测试。
+//! 不同CPU上最快的实现不同（有SHA扩展时 `sha2` 的硬件路径最快，没有时多路并行的
+//! 可移植实现可能更快），因此由基准测试按机器选择，结果按CPU型号和微码版本缓存。
+//!
+//! ## 🚀 哈希实现
+//!
+//! | 实现 | 说明 |
+//! |------|------|
+//! | `sha2` | sha2 库，运行时检测 SHA-NI / ARMv8 SHA 扩展并使用硬件加速 |
+//! | `portable-x1` | 本库的可移植实现，复用区块头前64字节的中间状态 |
+//! | `portable-x2` / `portable-x4` / `portable-x8` | 可移植实现，同时计算2/4/8个nonce，由编译器向量化 |
+//!
+//! ## ⚙️ 基准测试
+//!
+//! - 每个实现先预热，再连续计算 `duration_ms` 毫秒，按实际哈希数计算算力
+//! - 选择算力最高的实现，结果记录在 `CoreInfo` 的描述和 [`BenchmarkReport`] 中
+//! - `cache_path` 中已有相同CPU型号、微码版本和实现列表的结果时跳过基准测试
+//! - `backend = "hardware_accelerated"` 时候选实现只有 `sha2`
+//!
+//! ## 🔄 配置示例
+//!
+//! ```toml
+//! [benchmark]
+//! enabled = true
+//! duration_ms = 50
+//! cache_path = "/var/lib/cgminer/hash-benchmark.json"
+//! ```
+
+use crate::config::{self, HashBackend};
+use crate::cpu_info;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 可移植实现编译进来的并行路数
+pub const PORTABLE_LANES: [u32; 4] = [1, 2, 4, 8];
+
+/// 挖矿循环每次交给哈希实现的nonce数量（不小于最大并行路数）
+pub const HASH_CHUNK: usize = 8;
+
+/// 基准测试每次计算的nonce数量
+const BENCHMARK_CHUNK: usize = 256;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// 哈希实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HashImplementation {
+    /// sha2 库（有SHA扩展时使用硬件加速）
+    #[default]
+    Sha2,
+    /// 可移植实现，同时计算 `lanes` 个nonce
+    Portable { lanes: u32 },
+}
+
+impl HashImplementation {
+    /// 哈希后端允许的候选实现
+    pub fn candidates(backend: HashBackend) -> Vec<Self> {
+        match backend {
+            HashBackend::HardwareAccelerated => vec![Self::Sha2],
+            HashBackend::Auto => std::iter::once(Self::Sha2)
+                .chain(PORTABLE_LANES.iter().map(|&lanes| Self::Portable { lanes }))
+                .collect(),
+        }
+    }
+
+    /// 实现名称（如 `sha2`、`portable-x4`）
+    pub fn name(&self) -> String {
+        match self {
+            Self::Sha2 => "sha2".to_string(),
+            Self::Portable { lanes } => format!("portable-x{}", lanes),
+        }
+    }
+
+    /// 检查实现是否编译进来，可移植实现只支持 [`PORTABLE_LANES`] 中的并行路数
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Portable { lanes } if !PORTABLE_LANES.contains(lanes) => {
+                Err(format!("可移植实现不支持 {} 路并行 (支持: {:?})", lanes, PORTABLE_LANES))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 是否使用CPU的SHA硬件扩展
+    pub fn is_hardware_accelerated(&self) -> bool {
+        matches!(self, Self::Sha2) && HashBackend::HardwareAccelerated.is_supported()
+    }
+}
+
+impl fmt::Display for HashImplementation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// 区块头哈希器：为一个区块头准备好中间状态，计算不同nonce的双重SHA256
+#[derive(Debug, Clone)]
+pub struct HeaderHasher {
+    implementation: HashImplementation,
+    header: [u8; 80],
+    /// 区块头前64字节压缩后的中间状态
+    midstate: [u32; 8],
+    /// 区块头第64-75字节（第二个块的前3个字）
+    tail: [u32; 3],
+}
+
+impl HeaderHasher {
+    /// 为区块头创建哈希器（区块头的nonce字段会被忽略）
+    ///
+    /// # Panics
+    ///
+    /// 实现未通过 [`HashImplementation::validate`] 时panic
+    pub fn new(implementation: HashImplementation, header: &[u8; 80]) -> Self {
+        if let Err(e) = implementation.validate() {
+            panic!("{}", e);
+        }
+        let mut first_block = [[0u32; 1]; 16];
+        for (word, bytes) in first_block.iter_mut().zip(header[..64].chunks_exact(4)) {
+            word[0] = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        let mut state = IV.map(|word| [word]);
+        compress_lanes(&mut state, &first_block);
+
+        let mut tail = [0u32; 3];
+        for (word, bytes) in tail.iter_mut().zip(header[64..76].chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        Self {
+            implementation,
+            header: *header,
+            midstate: state.map(|word| word[0]),
+            tail,
+        }
+    }
+
+    /// 使用的哈希实现
+    pub fn implementation(&self) -> HashImplementation {
+        self.implementation
+    }
+
+    /// 计算每个nonce对应的区块头双重SHA256，结果按顺序写入 `hashes`（长度与 `nonces` 相同）
+    pub fn hash(&mut self, nonces: &[u32], hashes: &mut [[u8; 32]]) {
+        debug_assert_eq!(nonces.len(), hashes.len());
+        match self.implementation {
+            HashImplementation::Sha2 => {
+                for (nonce, hash) in nonces.iter().zip(hashes.iter_mut()) {
+                    self.header[76..].copy_from_slice(&nonce.to_le_bytes());
+                    let first_hash = sha2::Sha256::digest(self.header);
+                    *hash = sha2::Sha256::digest(first_hash).into();
+                }
+            }
+            HashImplementation::Portable { lanes: 1 } => self.hash_portable::<1>(nonces, hashes),
+            HashImplementation::Portable { lanes: 2 } => self.hash_portable::<2>(nonces, hashes),
+            HashImplementation::Portable { lanes: 4 } => self.hash_portable::<4>(nonces, hashes),
+            HashImplementation::Portable { lanes: 8 } => self.hash_portable::<8>(nonces, hashes),
+            HashImplementation::Portable { lanes } => unreachable!("可移植实现不支持 {} 路并行", lanes),
+        }
+    }
+
+    fn hash_portable<const N: usize>(&self, nonces: &[u32], hashes: &mut [[u8; 32]]) {
+        for (nonces, hashes) in nonces.chunks(N).zip(hashes.chunks_mut(N)) {
+            // 最后一组不足N个时重复最后一个nonce
+            let mut lanes = [nonces[nonces.len() - 1]; N];
+            lanes[..nonces.len()].copy_from_slice(nonces);
+
+            let state = double_sha256_lanes(&self.midstate, &self.tail, &lanes);
+            for (lane, hash) in hashes.iter_mut().enumerate() {
+                for (word, bytes) in state.iter().zip(hash.chunks_exact_mut(4)) {
+                    bytes.copy_from_slice(&word[lane].to_be_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// 同时计算N个nonce的区块头双重SHA256，返回按字、路排列的最终状态
+#[inline(always)]
+fn double_sha256_lanes<const N: usize>(midstate: &[u32; 8], tail: &[u32; 3], nonces: &[u32; N]) -> [[u32; N]; 8] {
+    // 第一次哈希的第二个块：区块头剩余16字节 + 填充，消息长度640位
+    let mut block = [[0u32; N]; 16];
+    for (word, value) in block.iter_mut().zip(tail) {
+        *word = [*value; N];
+    }
+    for (lane, nonce) in nonces.iter().enumerate() {
+        // 区块头中的nonce是小端序，SHA256按大端读取
+        block[3][lane] = nonce.swap_bytes();
+    }
+    block[4] = [0x8000_0000; N];
+    block[15] = [640; N];
+    let mut state = midstate.map(|word| [word; N]);
+    compress_lanes(&mut state, &block);
+
+    // 第二次哈希：32字节 + 填充，消息长度256位
+    let mut block = [[0u32; N]; 16];
+    block[..8].copy_from_slice(&state);
+    block[8] = [0x8000_0000; N];
+    block[15] = [256; N];
+    let mut state = IV.map(|word| [word; N]);
+    compress_lanes(&mut state, &block);
+    state
+}
+
+/// SHA256压缩函数，N路数据按 `[字][路]` 排列以便编译器向量化
+#[inline(always)]
+fn compress_lanes<const N: usize>(state: &mut [[u32; N]; 8], block: &[[u32; N]; 16]) {
+    let mut w = [[0u32; N]; 64];
+    w[..16].copy_from_slice(block);
+    for t in 16..64 {
+        for lane in 0..N {
+            let s0 = w[t - 15][lane].rotate_right(7) ^ w[t - 15][lane].rotate_right(18) ^ (w[t - 15][lane] >> 3);
+            let s1 = w[t - 2][lane].rotate_right(17) ^ w[t - 2][lane].rotate_right(19) ^ (w[t - 2][lane] >> 10);
+            w[t][lane] = w[t - 16][lane].wrapping_add(s0).wrapping_add(w[t - 7][lane]).wrapping_add(s1);
+        }
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..64 {
+        let mut t1 = [0u32; N];
+        let mut t2 = [0u32; N];
+        for lane in 0..N {
+            let s1 = e[lane].rotate_right(6) ^ e[lane].rotate_right(11) ^ e[lane].rotate_right(25);
+            let ch = (e[lane] & f[lane]) ^ (!e[lane] & g[lane]);
+            t1[lane] = h[lane].wrapping_add(s1).wrapping_add(ch).wrapping_add(K[t]).wrapping_add(w[t][lane]);
+            let s0 = a[lane].rotate_right(2) ^ a[lane].rotate_right(13) ^ a[lane].rotate_right(22);
+            let maj = (a[lane] & b[lane]) ^ (a[lane] & c[lane]) ^ (b[lane] & c[lane]);
+            t2[lane] = s0.wrapping_add(maj);
+        }
+        h = g;
+        g = f;
+        f = e;
+        for lane in 0..N {
+            e[lane] = d[lane].wrapping_add(t1[lane]);
+        }
+        d = c;
+        c = b;
+        b = a;
+        for lane in 0..N {
+            a[lane] = t1[lane].wrapping_add(t2[lane]);
+        }
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        for lane in 0..N {
+            word[lane] = word[lane].wrapping_add(value[lane]);
+        }
+    }
+}
+
+/// 基准测试配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchmarkConfig {
+    /// 是否在创建核心时运行基准测试
+    pub enabled: bool,
+    /// 每个实现的测量时间 (毫秒)
+    pub duration_ms: u64,
+    /// 基准测试结果缓存文件，None表示不缓存
+    pub cache_path: Option<PathBuf>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_ms: 50,
+            cache_path: None,
+        }
+    }
+}
+
+/// CPU标识（基准测试结果的缓存键）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuIdentity {
+    /// CPU型号
+    pub model: String,
+    /// 微码版本（无法读取时为None）
+    pub microcode: Option<String>,
+}
+
+impl CpuIdentity {
+    /// 检测当前CPU（Linux读取 `/proc/cpuinfo`，其他平台只记录架构）
+    pub fn detect() -> Self {
+        std::fs::read_to_string(cpu_info::PROC_CPUINFO)
+            .ok()
+            .and_then(|content| Self::from_cpuinfo(&content))
+            .unwrap_or_else(|| Self {
+                model: std::env::consts::ARCH.to_string(),
+                microcode: None,
+            })
+    }
+
+    /// 从 `/proc/cpuinfo` 内容解析第一个处理器的型号和微码版本
+    ///
+    /// x86使用 `model name`，ARM没有型号名称时使用 `CPU implementer` 和 `CPU part`。
+    pub fn from_cpuinfo(content: &str) -> Option<Self> {
+        let first = cpu_info::first_processor(content);
+        let field = |name: &str| cpu_info::cpuinfo_field(first, name);
+
+        let model = field("model name").or_else(|| {
+            let implementer = field("CPU implementer")?;
+            let part = field("CPU part")?;
+            Some(format!("{} {}", implementer, part))
+        })?;
+        Some(Self { model, microcode: field("microcode") })
+    }
+}
+
+/// 单个实现的基准测试结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    /// 哈希实现
+    pub implementation: HashImplementation,
+    /// 测得的单线程算力 (H/s)
+    pub hashrate: f64,
+    /// 是否使用SHA硬件扩展
+    pub hardware_accelerated: bool,
+}
+
+/// 基准测试报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// 运行基准测试的CPU
+    pub cpu: CpuIdentity,
+    /// 各实现的结果（按候选顺序）
+    pub results: Vec<BenchmarkResult>,
+    /// 选中的实现（算力最高）
+    pub selected: HashImplementation,
+    /// 是否来自缓存
+    #[serde(skip)]
+    pub cached: bool,
+}
+
+impl BenchmarkReport {
+    /// 选中实现的结果
+    pub fn selected_result(&self) -> Option<&BenchmarkResult> {
+        self.results.iter().find(|result| result.implementation == self.selected)
+    }
+
+    /// 一行摘要，如 `sha2 12.50 MH/s (硬件加速), portable-x4 8.10 MH/s → sha2`
+    pub fn summary(&self) -> String {
+        let results: Vec<String> = self.results.iter()
+            .map(|result| format!("{} {:.2} MH/s{}", result.implementation, result.hashrate / 1_000_000.0,
+                                  if result.hardware_accelerated { " (硬件加速)" } else { "" }))
+            .collect();
+        format!("{} → {}", results.join(", "), self.selected)
+    }
+
+    fn matches(&self, cpu: &CpuIdentity, candidates: &[HashImplementation]) -> bool {
+        // 选中的实现必须是当前编译进来的候选实现，否则重新运行基准测试
+        self.cpu == *cpu
+            && candidates.contains(&self.selected)
+            && self.results.len() == candidates.len()
+            && self.results.iter().zip(candidates).all(|(result, candidate)| result.implementation == *candidate)
+    }
+}
+
+/// 基准测试结果缓存文件（每个CPU型号和微码版本一条）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkCache {
+    /// 缓存的报告
+    pub reports: Vec<BenchmarkReport>,
+}
+
+impl BenchmarkCache {
+    /// 读取缓存文件，文件不存在或无法解析时返回 `None`
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 写入缓存文件（原子替换）
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        config::write_file_atomic(path, &content)
+    }
+
+    /// 查找同一CPU、同一候选实现列表的报告
+    pub fn find(&self, cpu: &CpuIdentity, candidates: &[HashImplementation]) -> Option<BenchmarkReport> {
+        self.reports.iter()
+            .find(|report| report.matches(cpu, candidates))
+            .map(|report| BenchmarkReport { cached: true, ..report.clone() })
+    }
+
+    /// 加入报告，替换同一CPU的旧报告
+    pub fn insert(&mut self, report: BenchmarkReport) {
+        self.reports.retain(|existing| existing.cpu != report.cpu);
+        self.reports.push(report);
+    }
+}
+
+/// 测量一个实现的单线程算力 (H/s)
+pub fn measure(implementation: HashImplementation, duration: Duration) -> f64 {
+    let header: [u8; 80] = std::array::from_fn(|i| (i as u8).wrapping_mul(31));
+    let mut hasher = HeaderHasher::new(implementation, &header);
+    let mut nonces = [0u32; BENCHMARK_CHUNK];
+    let mut hashes = [[0u8; 32]; BENCHMARK_CHUNK];
+    let mut next_nonce = 0u32;
+    let mut run_chunk = |hasher: &mut HeaderHasher| {
+        for nonce in nonces.iter_mut() {
+            *nonce = next_nonce;
+            next_nonce = next_nonce.wrapping_add(1);
+        }
+        hasher.hash(&nonces, &mut hashes);
+        std::hint::black_box(&hashes);
+    };
+
+    // 预热（缓存、分支预测和CPU频率）
+    run_chunk(&mut hasher);
+
+    let start = Instant::now();
+    let mut hashes_done = 0u64;
+    while start.elapsed() < duration || hashes_done == 0 {
+        run_chunk(&mut hasher);
+        hashes_done += BENCHMARK_CHUNK as u64;
+    }
+    hashes_done as f64 / start.elapsed().as_secs_f64()
+}
+
+/// 对候选实现运行基准测试并选择最快的实现
+pub fn run_benchmark(cpu: CpuIdentity, candidates: &[HashImplementation], duration: Duration) -> BenchmarkReport {
+    let results: Vec<BenchmarkResult> = candidates.iter()
+        .map(|&implementation| BenchmarkResult {
+            implementation,
+            hashrate: measure(implementation, duration),
+            hardware_accelerated: implementation.is_hardware_accelerated(),
+        })
+        .collect();
+    let selected = results.iter()
+        .max_by(|a, b| a.hashrate.total_cmp(&b.hashrate))
+        .map_or(HashImplementation::Sha2, |result| result.implementation);
+    BenchmarkReport { cpu, results, selected, cached: false }
+}
+
+/// 按配置选择哈希实现：优先使用缓存的结果，否则运行基准测试并写入缓存
+pub fn select_implementation(config: &BenchmarkConfig, backend: HashBackend) -> BenchmarkReport {
+    let candidates = HashImplementation::candidates(backend);
+    let cpu = CpuIdentity::detect();
+    let mut cache = config.cache_path.as_deref().and_then(BenchmarkCache::load).unwrap_or_default();
+    if let Some(report) = cache.find(&cpu, &candidates) {
+        return report;
+    }
+
+    let report = run_benchmark(cpu, &candidates, Duration::from_millis(config.duration_ms));
+    if let Some(ref path) = config.cache_path {
+        cache.insert(report.clone());
+        if let Err(e) = cache.save(path) {
+            tracing::warn!("保存哈希基准测试结果到 {} 失败: {}", path.display(), e);
+        }
+    }
+    report
+}
@@ -0,0 +1,274 @@
+//! # 空闲检测模块
+//!
+//! 本模块实现"只在机器空闲时挖矿"的策略：周期性采样系统CPU占用，扣除挖矿进程自身的
+//! 占用得到其他程序的负载，负载超过阈值时立即以 [`PauseReason::SystemBusy`] 暂停所有设备，
+//! 持续安静一段时间后再恢复，保证开发机在使用时保持响应。
+//!
+//! ## 🚀 工作流程
+//!
+//! ```text
+//! sysinfo ──▶ 外部负载 = 全局CPU占用 - 挖矿进程占用 ──▶ IdleDetector ──▶ 暂停/恢复
+//! ```
+//!
+//! ## 🎯 状态切换
+//!
+//! - **暂停**: 任意一次采样的外部负载超过 `max_foreign_load`，在一个采样周期内暂停
+//! - **恢复**: 外部负载连续 `quiet_period_ms` 低于阈值后恢复
+//!
+//! [`IdleDetector`] 累计挖矿时间和暂停时间，通过 [`IdleStats`] 上报。因温度、时间表、功率或收益
+//! 等其他原因停止挖矿的时间单独累计，不计入挖矿时间。
+//! 负载来源通过 [`LoadSource`] 注入，测试中使用 [`ScriptedLoadSource`]。
+//!
+//! [`PauseReason::SystemBusy`]: crate::device::PauseReason::SystemBusy
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, System};
+
+/// 空闲检测配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdleConfig {
+    /// 是否只在空闲时挖矿
+    pub enabled: bool,
+    /// 其他程序的CPU占用高于该比例 (0.0-1.0) 时暂停
+    pub max_foreign_load: f64,
+    /// 采样间隔 (毫秒)
+    pub sample_interval_ms: u64,
+    /// 恢复挖矿前需要保持安静的时间 (毫秒)
+    pub quiet_period_ms: u64,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_foreign_load: 0.2,
+            sample_interval_ms: 250,
+            quiet_period_ms: 30_000,
+        }
+    }
+}
+
+/// 系统负载来源
+pub trait LoadSource: Send + Sync {
+    /// 来源描述（用于日志）
+    fn describe(&self) -> String;
+
+    /// 采样其他程序的CPU占用 (0.0-1.0)，暂时无法采样时返回 `None`
+    fn sample(&mut self) -> Option<f64>;
+}
+
+/// 基于sysinfo的负载来源：全局CPU占用减去本进程的占用
+pub struct SysinfoLoadSource {
+    system: System,
+    pid: Option<Pid>,
+}
+
+impl SysinfoLoadSource {
+    /// 创建负载来源（第一次采样只建立基线）
+    pub fn new() -> Self {
+        let mut system = System::new();
+        let pid = sysinfo::get_current_pid().ok();
+        system.refresh_cpu();
+        if let Some(pid) = pid {
+            system.refresh_process(pid);
+        }
+        Self { system, pid }
+    }
+}
+
+impl Default for SysinfoLoadSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoadSource for SysinfoLoadSource {
+    fn describe(&self) -> String {
+        "sysinfo".to_string()
+    }
+
+    fn sample(&mut self) -> Option<f64> {
+        self.system.refresh_cpu();
+        let cpu_count = self.system.cpus().len().max(1) as f64;
+        let total = self.system.global_cpu_info().cpu_usage() as f64 / 100.0;
+
+        // 进程占用以单个CPU为100%，换算为全局比例
+        let own = match self.pid {
+            Some(pid) if self.system.refresh_process(pid) => self.system.process(pid)
+                .map_or(0.0, |process| process.cpu_usage() as f64 / 100.0 / cpu_count),
+            _ => 0.0,
+        };
+
+        Some((total - own).clamp(0.0, 1.0))
+    }
+}
+
+/// 按顺序返回预设负载的来源（测试和回放使用），用完后重复最后一个值
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedLoadSource {
+    samples: VecDeque<f64>,
+    last: Option<f64>,
+}
+
+impl ScriptedLoadSource {
+    /// 创建脚本负载来源
+    pub fn new(samples: impl IntoIterator<Item = f64>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+            last: None,
+        }
+    }
+}
+
+impl LoadSource for ScriptedLoadSource {
+    fn describe(&self) -> String {
+        "脚本负载".to_string()
+    }
+
+    fn sample(&mut self) -> Option<f64> {
+        if let Some(load) = self.samples.pop_front() {
+            self.last = Some(load);
+        }
+        self.last
+    }
+}
+
+/// 空闲检测统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdleStats {
+    /// 当前是否因系统繁忙暂停
+    pub paused: bool,
+    /// 最近一次采样的外部负载
+    pub last_load: Option<f64>,
+    /// 累计挖矿时间（未因任何原因暂停）
+    pub active_time: Duration,
+    /// 累计因系统繁忙暂停的时间
+    pub paused_time: Duration,
+    /// 累计因其他原因停止挖矿的时间（未因系统繁忙暂停时）
+    pub held_time: Duration,
+    /// 暂停次数
+    pub pause_count: u64,
+}
+
+/// 空闲检测器 - 根据外部负载决定暂停或恢复
+#[derive(Debug, Clone)]
+pub struct IdleDetector {
+    config: IdleConfig,
+    paused: bool,
+    /// 是否因其他原因停止挖矿
+    held: bool,
+    /// 暂停期间负载开始低于阈值的时间
+    quiet_since: Option<Instant>,
+    last_update: Instant,
+    last_load: Option<f64>,
+    active_time: Duration,
+    paused_time: Duration,
+    held_time: Duration,
+    pause_count: u64,
+}
+
+impl IdleDetector {
+    /// 创建空闲检测器
+    pub fn new(config: IdleConfig) -> Self {
+        Self::new_at(config, Instant::now())
+    }
+
+    /// 以指定时间为起点创建空闲检测器
+    pub fn new_at(config: IdleConfig, now: Instant) -> Self {
+        Self {
+            config,
+            paused: false,
+            held: false,
+            quiet_since: None,
+            last_update: now,
+            last_load: None,
+            active_time: Duration::ZERO,
+            paused_time: Duration::ZERO,
+            held_time: Duration::ZERO,
+            pause_count: 0,
+        }
+    }
+
+    /// 空闲检测配置
+    pub fn config(&self) -> &IdleConfig {
+        &self.config
+    }
+
+    /// 用一次负载采样更新状态，返回是否应该暂停
+    pub fn update(&mut self, load: Option<f64>) -> bool {
+        self.update_at(load, Instant::now())
+    }
+
+    /// 在指定时间用一次负载采样更新状态，返回是否应该暂停
+    ///
+    /// 没有采样结果时保持当前状态。
+    pub fn update_at(&mut self, load: Option<f64>, now: Instant) -> bool {
+        self.accumulate(now);
+
+        let load = match load {
+            Some(load) => load,
+            None => return self.paused,
+        };
+        self.last_load = Some(load);
+
+        if load > self.config.max_foreign_load {
+            self.quiet_since = None;
+            if !self.paused {
+                self.paused = true;
+                self.pause_count += 1;
+            }
+        } else if self.paused {
+            let quiet_since = *self.quiet_since.get_or_insert(now);
+            if now.saturating_duration_since(quiet_since) >= Duration::from_millis(self.config.quiet_period_ms) {
+                self.paused = false;
+                self.quiet_since = None;
+            }
+        }
+
+        self.paused
+    }
+
+    /// 记录是否因其他原因（温度、时间表、功率、收益）停止挖矿
+    pub fn set_held(&mut self, held: bool) {
+        self.set_held_at(held, Instant::now())
+    }
+
+    /// 在指定时间记录是否因其他原因停止挖矿，此前的时间按原状态累计
+    pub fn set_held_at(&mut self, held: bool, now: Instant) {
+        self.accumulate(now);
+        self.held = held;
+    }
+
+    /// 把上次更新以来的时间累计到当前状态
+    fn accumulate(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_update);
+        if self.paused {
+            self.paused_time += elapsed;
+        } else if self.held {
+            self.held_time += elapsed;
+        } else {
+            self.active_time += elapsed;
+        }
+        self.last_update = now;
+    }
+
+    /// 当前是否应该暂停
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 空闲检测统计
+    pub fn stats(&self) -> IdleStats {
+        IdleStats {
+            paused: self.paused,
+            last_load: self.last_load,
+            active_time: self.active_time,
+            paused_time: self.paused_time,
+            held_time: self.held_time,
+            pause_count: self.pause_count,
+        }
+    }
+}
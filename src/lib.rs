@@ -27,14 +27,32 @@
 //!
 //! ```text
 //! cgminer-cpu-btc-core/
+//! ├── cgroup.rs                  # cgroup v2 CPU限额 (cpu.max/cpuset)
+//! ├── config.rs                  # 强类型核心配置 (TOML/JSON加载和验证)
 //! ├── core.rs                    # 核心挖矿算法实现
 //! ├── device.rs                  # 设备抽象和管理 (无锁优化)
+//! ├── device_handle.rs           # 单设备控制句柄
 //! ├── factory.rs                 # 核心工厂模式
+//! ├── hashing.rs                 # 哈希实现 (sha2/多路可移植实现) 和启动基准测试
 //! ├── cpu_affinity.rs           # CPU亲和性绑定
+//! ├── cpu_info.rs                # CPU信息检测 (厂商/型号/特性/缓存: cpuinfo、sysfs、CPUID)
+//! ├── events.rs                  # 结构化事件流 (份额/工作/设备生命周期)
+//! ├── idle.rs                    # 空闲检测 (只在机器空闲时挖矿)
+//! ├── load_balancer.rs           # 负载均衡 (按外部负载迁移挖矿线程)
+//! ├── numa.rs                    # NUMA节点发现 (设备按节点均匀分布)
+//! ├── result_buffer.rs           # 有界结果缓冲 (溢出策略和丢弃计数)
+//! ├── schedule.rs                # 挖矿时间表 (星期/时刻/时区，可注入时钟)
+//! ├── stats_journal.rs           # 累计统计日志 (跨重启保留哈希数/份额/运行时间/能耗)
 //! ├── concurrent_optimization.rs # 并发优化 (无锁数据结构)
 //! ├── performance.rs             # 性能配置管理 (简化版)
+//! ├── power.rs                   # RAPL功耗测量 (封装功率/设备分摊/J/TH)
+//! ├── profitability.rs           # 收益估算 (每日BTC/电费/盈亏平衡/亏损暂停)
 //! ├── platform_optimization.rs  # 平台特定优化 (简化版)
-//! └── temperature.rs             # 系统温度监控 (简化版)
+//! ├── priority.rs                # 挖矿线程优先级 (SCHED_BATCH/SCHED_IDLE/nice/ioprio)
+//! ├── temperature.rs             # 系统温度监控 (可插拔温度来源: sysfs/命令/脚本)
+//! ├── thermal.rs                 # 闭环温度调节 (PI降频/危险暂停)
+//! ├── topology.rs                # CPU拓扑 (sysfs: 超线程/大小核/缓存共享)
+//! └── tuning.rs                  # 批次大小自动调优 (响应延迟上限/结果缓存)
 //! ```
 //!
 //! ## 🎯 简化设计原则
@@ -88,13 +106,31 @@
 //! ```
 
 // 核心库模块
+pub mod cgroup;
+pub mod config;
 pub mod core;
 pub mod device;
+pub mod device_handle;
 pub mod factory;
+pub mod hashing;
 pub mod cpu_affinity;
+pub mod cpu_info;
+pub mod events;
+pub mod idle;
+pub mod load_balancer;
+pub mod numa;
 pub mod performance;
+pub mod power;
+pub mod profitability;
 pub mod platform_optimization;
+pub mod priority;
+pub mod result_buffer;
+pub mod schedule;
+pub mod stats_journal;
 pub mod temperature;
+pub mod thermal;
+pub mod topology;
+pub mod tuning;
 // 阶段2: 并发和锁优化模块
 pub mod concurrent_optimization;
 
@@ -103,8 +139,10 @@ pub mod concurrent_optimization;
 // 重新导出主要类型
 pub use factory::SoftwareCoreFactory;
 pub use factory::SoftwareCoreFactory as CpuBtcCoreFactory; // 为兼容性添加别名
-pub use core::SoftwareMiningCore;
-pub use device::SoftwareDevice;
+pub use core::{RetiredTotals, ShareRejections, SoftwareMiningCore};
+pub use config::CpuCoreConfig;
+pub use device::{NonceRange, PauseFlags, PauseReason, SoftwareDevice};
+pub use device_handle::DeviceHandle;
 
 use cgminer_core::{CoreType, CoreInfo};
 
@@ -129,9 +167,28 @@ pub fn create_factory() -> Box<dyn cgminer_core::CoreFactory> {
 }
 
 // 温度和性能管理
-pub use temperature::{TemperatureManager, TemperatureConfig};
+pub use temperature::{TemperatureManager, TemperatureConfig, TemperatureSource};
 pub use performance::{PerformanceOptimizer, PerformanceConfig};
 pub use cpu_affinity::CpuAffinityManager;
+pub use cpu_info::CpuInfo;
+
+// 事件流导出
+pub use events::{EventBus, MiningEvent, MiningEventKind};
+
+// 收益估算导出
+pub use profitability::{ProfitabilityConfig, ProfitabilityEstimate, ProfitabilityEstimator};
+
+// 哈希实现导出
+pub use hashing::{BenchmarkReport, HashImplementation, HeaderHasher};
+
+// 批次调优导出
+pub use tuning::{BatchTuner, BatchTuningConfig, TuningResult};
+
+// 统计日志导出
+pub use stats_journal::{LifetimeStats, StatsJournal, StatsJournalConfig};
+
+// 结果缓冲导出
+pub use result_buffer::{ResultBuffer, ResultBufferConfig, ResultBufferStats, OverflowPolicy, SendOutcome};
 
 // 并发优化导出
 pub use concurrent_optimization::{AtomicStatsManager, LockFreeWorkQueue, BatchStatsUpdater};
@@ -0,0 +1,402 @@
+//! # 负载均衡模块
+//!
+//! 本模块实现 `CpuAffinityStrategy::LoadBalanced` 的后台重平衡：周期性采样每个
+//! CPU的利用率，扣除挖矿线程自身占用的部分得到"外部负载"，把挖矿线程从被其他
+//! 程序占用的核心迁移到空闲核心。
+//!
+//! ## 🚀 工作流程
+//!
+//! ```text
+//! /proc/stat ──┐
+//!              ├─▶ 外部负载 = 核心利用率 - 挖矿线程占用 ──▶ LoadBalancer::plan ──▶ 迁移
+//! /proc/self/task/<tid>/stat ─┘
+//! ```
+//!
+//! ## 🎯 防抖动
+//!
+//! - **滞回**: 外部负载超过 `high_watermark` 才迁移，目标核心外部负载必须低于 `low_watermark`
+//! - **最小停留时间**: 设备在一个核心上停留不足 `min_dwell_ms` 时不迁移
+//!
+//! 每次迁移都会记录日志；迁移后挖矿工作线程在下一个批次重新绑定到新核心。
+
+use crate::cpu_affinity::CpuAffinityManager;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// 默认的procfs目录
+pub const DEFAULT_PROC_ROOT: &str = "/proc";
+
+/// 负载均衡配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoadBalancerConfig {
+    /// 采样间隔 (毫秒)
+    pub sample_interval_ms: u64,
+    /// 外部负载高于该比例 (0.0-1.0) 时迁移挖矿线程
+    pub high_watermark: f64,
+    /// 只迁移到外部负载低于该比例 (0.0-1.0) 的核心
+    pub low_watermark: f64,
+    /// 两次迁移之间的最小停留时间 (毫秒)
+    pub min_dwell_ms: u64,
+}
+
+impl Default for LoadBalancerConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_ms: 2000,
+            high_watermark: 0.5,
+            low_watermark: 0.2,
+            min_dwell_ms: 30_000,
+        }
+    }
+}
+
+/// 单个CPU的累计时间 (USER_HZ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuTimes {
+    /// 非空闲时间
+    pub busy: u64,
+    /// 总时间
+    pub total: u64,
+}
+
+/// 挖矿工作线程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerThread {
+    /// 设备ID
+    pub device_id: u32,
+    /// 操作系统线程ID
+    pub tid: i32,
+    /// 当前绑定的CPU核心
+    pub core: usize,
+}
+
+/// 一次迁移
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// 设备ID
+    pub device_id: u32,
+    /// 原核心
+    pub from_core: usize,
+    /// 目标核心
+    pub to_core: usize,
+    /// 原核心的外部负载
+    pub from_load: f64,
+    /// 目标核心的外部负载
+    pub to_load: f64,
+}
+
+/// 解析 `/proc/stat` 中每个CPU的累计时间
+pub fn parse_proc_stat(content: &str) -> BTreeMap<usize, CpuTimes> {
+    let mut cpus = BTreeMap::new();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let cpu = match fields.next()
+            .and_then(|name| name.strip_prefix("cpu"))
+            .and_then(|id| id.parse::<usize>().ok())
+        {
+            Some(cpu) => cpu,
+            None => continue,
+        };
+
+        // user nice system idle iowait irq softirq steal (guest 已计入 user)
+        let values: Vec<u64> = fields.take(8).filter_map(|value| value.parse().ok()).collect();
+        if values.len() < 4 {
+            continue;
+        }
+        let total: u64 = values.iter().sum();
+        let idle = values[3] + values.get(4).copied().unwrap_or(0);
+        cpus.insert(cpu, CpuTimes { busy: total - idle, total });
+    }
+    cpus
+}
+
+/// 解析 `/proc/<pid>/task/<tid>/stat`，返回 utime + stime (USER_HZ)
+pub fn parse_thread_ticks(content: &str) -> Option<u64> {
+    // 线程名可能包含空格和括号，从最后一个 ')' 之后开始解析
+    let rest = &content[content.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // 第3个字段 (state) 是 rest 的第0个，utime/stime 是第14/15个字段
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// 由两次采样计算每个CPU的外部负载 (0.0-1.0)
+pub fn compute_foreign_load(
+    previous: &BTreeMap<usize, CpuTimes>,
+    current: &BTreeMap<usize, CpuTimes>,
+    worker_ticks: &[(WorkerThread, u64)],
+) -> BTreeMap<usize, f64> {
+    let mut loads = BTreeMap::new();
+    for (&cpu, now) in current {
+        let before = match previous.get(&cpu) {
+            Some(before) => before,
+            None => continue,
+        };
+        let total = now.total.saturating_sub(before.total);
+        if total == 0 {
+            continue;
+        }
+        let busy = now.busy.saturating_sub(before.busy);
+        let miner: u64 = worker_ticks.iter()
+            .filter(|(worker, _)| worker.core == cpu)
+            .map(|(_, ticks)| *ticks)
+            .sum();
+
+        let foreign = busy.saturating_sub(miner) as f64 / total as f64;
+        loads.insert(cpu, foreign.clamp(0.0, 1.0));
+    }
+    loads
+}
+
+/// 基于procfs的CPU负载采样器
+#[derive(Debug)]
+pub struct CpuLoadSampler {
+    proc_root: PathBuf,
+    previous: Option<(BTreeMap<usize, CpuTimes>, HashMap<i32, u64>)>,
+}
+
+impl CpuLoadSampler {
+    /// 创建采样器（测试时可指向fixture目录）
+    pub fn new(proc_root: impl Into<PathBuf>) -> Self {
+        Self { proc_root: proc_root.into(), previous: None }
+    }
+
+    /// 采样一次，返回自上次采样以来每个CPU的外部负载；第一次采样返回 `None`
+    pub fn sample(&mut self, workers: &[WorkerThread]) -> io::Result<Option<BTreeMap<usize, f64>>> {
+        let cpus = parse_proc_stat(&fs::read_to_string(self.proc_root.join("stat"))?);
+
+        let mut thread_ticks = HashMap::new();
+        for worker in workers {
+            let path = self.proc_root.join("self/task").join(worker.tid.to_string()).join("stat");
+            if let Some(ticks) = fs::read_to_string(path).ok().and_then(|content| parse_thread_ticks(&content)) {
+                thread_ticks.insert(worker.tid, ticks);
+            }
+        }
+
+        let loads = self.previous.as_ref().map(|(previous_cpus, previous_ticks)| {
+            let worker_ticks: Vec<(WorkerThread, u64)> = workers.iter()
+                .filter_map(|worker| {
+                    let now = thread_ticks.get(&worker.tid)?;
+                    let before = previous_ticks.get(&worker.tid)?;
+                    Some((*worker, now.saturating_sub(*before)))
+                })
+                .collect();
+            compute_foreign_load(previous_cpus, &cpus, &worker_ticks)
+        });
+
+        self.previous = Some((cpus, thread_ticks));
+        Ok(loads)
+    }
+}
+
+/// 迁移决策器
+#[derive(Debug)]
+pub struct LoadBalancer {
+    config: LoadBalancerConfig,
+    started: Instant,
+    last_migration: HashMap<u32, Instant>,
+}
+
+impl LoadBalancer {
+    /// 创建迁移决策器
+    pub fn new(config: LoadBalancerConfig) -> Self {
+        Self::new_at(config, Instant::now())
+    }
+
+    /// 以指定的起始时间创建（便于测试停留时间）
+    pub fn new_at(config: LoadBalancerConfig, started: Instant) -> Self {
+        Self { config, started, last_migration: HashMap::new() }
+    }
+
+    /// 根据外部负载规划迁移
+    ///
+    /// `assignments` 为设备 → 当前核心，`candidates` 为允许使用的核心。
+    /// 不在 `assignments` 中的设备（已移除）的迁移记录会被丢弃。
+    pub fn plan(
+        &mut self,
+        now: Instant,
+        foreign_load: &BTreeMap<usize, f64>,
+        assignments: &BTreeMap<u32, usize>,
+        candidates: &[usize],
+    ) -> Vec<Migration> {
+        let min_dwell = Duration::from_millis(self.config.min_dwell_ms);
+        self.last_migration.retain(|device_id, _| assignments.contains_key(device_id));
+        let mut occupied: BTreeMap<u32, usize> = assignments.clone();
+        let mut migrations = Vec::new();
+
+        for (&device_id, &from_core) in assignments {
+            let from_load = match foreign_load.get(&from_core) {
+                Some(&load) if load > self.config.high_watermark => load,
+                _ => continue,
+            };
+
+            let since = self.last_migration.get(&device_id).copied().unwrap_or(self.started);
+            if now.saturating_duration_since(since) < min_dwell {
+                debug!("设备 {} 所在核心 {} 外部负载 {:.0}%，停留时间不足，暂不迁移",
+                       device_id, from_core, from_load * 100.0);
+                continue;
+            }
+
+            let target = candidates.iter()
+                .filter(|core| !occupied.values().any(|used| used == *core))
+                .filter_map(|&core| {
+                    let load = foreign_load.get(&core).copied()?;
+                    (load < self.config.low_watermark).then_some((core, load))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+            if let Some((to_core, to_load)) = target {
+                occupied.insert(device_id, to_core);
+                self.last_migration.insert(device_id, now);
+                migrations.push(Migration { device_id, from_core, to_core, from_load, to_load });
+            }
+        }
+
+        migrations
+    }
+}
+
+/// 后台重平衡循环，直到任务被取消
+pub async fn run(manager: Arc<RwLock<CpuAffinityManager>>, config: LoadBalancerConfig) {
+    let mut sampler = CpuLoadSampler::new(DEFAULT_PROC_ROOT);
+    let mut balancer = LoadBalancer::new(config.clone());
+    let mut interval = tokio::time::interval(Duration::from_millis(config.sample_interval_ms.max(100)));
+
+    info!("⚖️  负载均衡已启动: 采样间隔 {}ms, 迁移阈值 {:.0}%/{:.0}%, 最小停留 {}ms",
+          config.sample_interval_ms, config.high_watermark * 100.0,
+          config.low_watermark * 100.0, config.min_dwell_ms);
+
+    loop {
+        interval.tick().await;
+
+        let (workers, candidates) = match manager.read() {
+            Ok(manager) => (manager.worker_threads(), manager.available_core_ids()),
+            Err(_) => continue,
+        };
+
+        let foreign_load = match sampler.sample(&workers) {
+            Ok(Some(loads)) => loads,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!("CPU负载采样失败: {}", e);
+                continue;
+            }
+        };
+
+        let assignments: BTreeMap<u32, usize> = workers.iter()
+            .map(|worker| (worker.device_id, worker.core))
+            .collect();
+
+        for migration in balancer.plan(Instant::now(), &foreign_load, &assignments, &candidates) {
+            let result = manager.write()
+                .map_err(|e| e.to_string())
+                .and_then(|mut manager| manager.migrate_device(migration.device_id, migration.to_core));
+
+            match result {
+                Ok(()) => info!(
+                    "🔀 设备 {} 从CPU核心 {} (外部负载 {:.0}%) 迁移到核心 {} (外部负载 {:.0}%)",
+                    migration.device_id, migration.from_core, migration.from_load * 100.0,
+                    migration.to_core, migration.to_load * 100.0
+                ),
+                Err(e) => warn!("设备 {} 迁移失败: {}", migration.device_id, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LoadBalancerConfig {
+        LoadBalancerConfig {
+            sample_interval_ms: 1000,
+            high_watermark: 0.5,
+            low_watermark: 0.2,
+            min_dwell_ms: 10_000,
+        }
+    }
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let cpus = parse_proc_stat("cpu  10 0 10 80 0 0 0 0 0 0\ncpu0 6 0 4 88 2 0 0 0 0 0\ncpu1 1 0 1 98 0 0 0 0 0 0\nintr 1 2\n");
+        assert_eq!(cpus.len(), 2);
+        assert_eq!(cpus[&0], CpuTimes { busy: 10, total: 100 });
+    }
+
+    #[test]
+    fn test_parse_thread_ticks_with_spaces_in_name() {
+        let stat = "4242 (cpu miner (1000)) R 1 1 1 0 -1 4194304 10 0 0 0 150 25 0 0 20 0 1 0 100";
+        assert_eq!(parse_thread_ticks(stat), Some(175));
+    }
+
+    #[test]
+    fn test_foreign_load_excludes_miner_threads() {
+        let previous = [(0, CpuTimes { busy: 0, total: 0 }), (1, CpuTimes { busy: 0, total: 0 })].into();
+        let current = [(0, CpuTimes { busy: 100, total: 100 }), (1, CpuTimes { busy: 100, total: 100 })].into();
+        let worker = |device_id, core| WorkerThread { device_id, tid: device_id as i32, core };
+
+        // 核心0只有挖矿线程；核心1上挖矿线程只拿到40%，其余被其他程序占用
+        let loads = compute_foreign_load(&previous, &current, &[(worker(1000, 0), 100), (worker(1001, 1), 40)]);
+        assert_eq!(loads[&0], 0.0);
+        assert!((loads[&1] - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_plan_respects_hysteresis_and_dwell() {
+        let start = Instant::now();
+        let mut balancer = LoadBalancer::new_at(config(), start);
+        let assignments: BTreeMap<u32, usize> = [(1000, 0), (1001, 1)].into();
+        let candidates = [0, 1, 2, 3];
+
+        // 核心2处于滞回区间 (0.3)，只能迁移到核心3
+        let loads: BTreeMap<usize, f64> = [(0, 0.7), (1, 0.1), (2, 0.3), (3, 0.05)].into();
+
+        // 停留时间不足
+        assert!(balancer.plan(start + Duration::from_secs(5), &loads, &assignments, &candidates).is_empty());
+
+        let migrations = balancer.plan(start + Duration::from_secs(11), &loads, &assignments, &candidates);
+        assert_eq!(migrations.len(), 1);
+        assert_eq!((migrations[0].device_id, migrations[0].to_core), (1000, 3));
+
+        // 刚迁移过的设备在停留时间内不会再次迁移
+        let moved: BTreeMap<u32, usize> = [(1000, 3), (1001, 1)].into();
+        let busy: BTreeMap<usize, f64> = [(0, 0.0), (1, 0.1), (2, 0.0), (3, 0.9)].into();
+        assert!(balancer.plan(start + Duration::from_secs(15), &busy, &moved, &candidates).is_empty());
+    }
+
+    #[test]
+    fn test_plan_drops_removed_devices() {
+        let start = Instant::now();
+        let mut balancer = LoadBalancer::new_at(config(), start);
+        let candidates = [0, 1];
+        let loads: BTreeMap<usize, f64> = [(0, 0.7), (1, 0.0)].into();
+
+        let assignments: BTreeMap<u32, usize> = [(1000, 0)].into();
+        assert_eq!(balancer.plan(start + Duration::from_secs(11), &loads, &assignments, &candidates).len(), 1);
+        assert!(balancer.last_migration.contains_key(&1000));
+
+        // 设备移除后不再保留迁移记录，重新使用的设备ID不会继承停留时间
+        balancer.plan(start + Duration::from_secs(12), &loads, &BTreeMap::new(), &candidates);
+        assert!(balancer.last_migration.is_empty());
+        let migrations = balancer.plan(start + Duration::from_secs(13), &loads, &assignments, &candidates);
+        assert_eq!(migrations.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_below_high_watermark_does_nothing() {
+        let start = Instant::now();
+        let mut balancer = LoadBalancer::new_at(config(), start);
+        let assignments: BTreeMap<u32, usize> = [(1000, 0)].into();
+        let loads: BTreeMap<usize, f64> = [(0, 0.45), (1, 0.0)].into();
+        assert!(balancer.plan(start + Duration::from_secs(60), &loads, &assignments, &[0, 1]).is_empty());
+    }
+}
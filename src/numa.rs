@@ -0,0 +1,141 @@
+//! # NUMA节点模块
+//!
+//! 本模块在Linux上读取 `/sys/devices/system/node` 发现NUMA节点及其CPU，
+//! 供CPU绑定管理器把设备均匀分布到各个节点，并按节点汇总算力。
+//!
+//! ## 🚀 数据来源
+//!
+//! | 路径 (相对 `/sys/devices/system/node/nodeN`) | 内容 |
+//! |------|------|
+//! | `cpulist` | 节点上的逻辑CPU |
+//! | `distance` | 到各节点的访问距离 |
+//!
+//! 没有CPU的节点（例如纯内存节点）会被忽略。
+//!
+//! ## 🎯 内存本地性
+//!
+//! 设备的挖矿循环在绑定到节点CPU的工作线程上构造，循环内部的区块头缓冲在绑定之后才分配，
+//! 按Linux的first-touch策略落在该节点的本地内存上。
+//!
+//! 与其他线程共享的设备状态（`AtomicStats`、`BatchStatsUpdater`、`CgminerHashrateTracker`、
+//! `LockFreeWorkQueue`）在创建设备时先由调用线程分配；设备第一次启动时，工作线程在绑定之后
+//! 重新分配这些状态（保留统计计数和待处理的工作）并交回设备，此后设备和挖矿循环共用节点本地的一份。
+//! 负载均衡把设备迁移到其他节点时状态不再跟随迁移。
+
+use crate::topology::parse_cpu_list;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 默认的sysfs NUMA节点目录
+pub const DEFAULT_SYSFS_NODE_ROOT: &str = "/sys/devices/system/node";
+
+/// NUMA节点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NumaNode {
+    /// 节点ID
+    pub id: u32,
+    /// 节点上的逻辑CPU
+    pub cpus: Vec<usize>,
+    /// 到各节点的访问距离（按节点ID顺序）
+    pub distances: Vec<u32>,
+}
+
+/// NUMA拓扑
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NumaTopology {
+    nodes: Vec<NumaNode>,
+}
+
+impl NumaTopology {
+    /// 检测当前系统的NUMA拓扑（仅Linux）
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            Self::from_sysfs(DEFAULT_SYSFS_NODE_ROOT).ok()
+        } else {
+            None
+        }
+    }
+
+    /// 从指定的sysfs节点目录读取NUMA拓扑（测试时可指向fixture目录）
+    pub fn from_sysfs(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref();
+        let mut nodes = Vec::new();
+
+        for entry in fs::read_dir(root)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let id = match name.to_str()
+                .and_then(|name| name.strip_prefix("node"))
+                .and_then(|id| id.parse::<u32>().ok())
+            {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let dir = entry.path();
+            let cpus = fs::read_to_string(dir.join("cpulist"))
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_default();
+            if cpus.is_empty() {
+                continue;
+            }
+
+            let distances = fs::read_to_string(dir.join("distance"))
+                .map(|line| line.split_whitespace().filter_map(|d| d.parse().ok()).collect())
+                .unwrap_or_default();
+
+            nodes.push(NumaNode { id, cpus, distances });
+        }
+
+        if nodes.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} 下没有包含CPU的NUMA节点", root.display()),
+            ));
+        }
+
+        nodes.sort_by_key(|node| node.id);
+        Ok(Self { nodes })
+    }
+
+    /// 由节点列表构造拓扑
+    pub fn from_nodes(mut nodes: Vec<NumaNode>) -> Self {
+        nodes.sort_by_key(|node| node.id);
+        Self { nodes }
+    }
+
+    /// 所有包含CPU的节点（按ID排序）
+    pub fn nodes(&self) -> &[NumaNode] {
+        &self.nodes
+    }
+
+    /// 节点数量
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 是否有多个NUMA节点
+    pub fn is_numa(&self) -> bool {
+        self.nodes.len() > 1
+    }
+
+    /// 逻辑CPU所在的节点
+    pub fn node_of_cpu(&self, cpu: usize) -> Option<u32> {
+        self.nodes.iter().find(|node| node.cpus.contains(&cpu)).map(|node| node.id)
+    }
+}
+
+/// 单个NUMA节点的算力统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NumaNodeStats {
+    /// 节点ID，设备未绑定或没有NUMA信息时为 `None`
+    pub node_id: Option<u32>,
+    /// 运行在该节点上的设备
+    pub device_ids: Vec<u32>,
+    /// 当前算力 (H/s)
+    pub hashrate: f64,
+    /// 累计哈希数
+    pub total_hashes: u64,
+}
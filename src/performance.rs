@@ -16,6 +16,7 @@
 //! - 🔧 设备配置微调
 //! - 🔧 批次大小智能调整
 //! - 🔧 CPU绑定自动启用
+//! - 🔧 功率上限下的线程数和占空比分配（`power-management`）
 //!
 //! ## 🎯 自动优化规则
 //!
@@ -39,6 +40,7 @@
 //! | `batch_size` | 1000 | 批次大小 | CPU使用效率 |
 //! | `enable_optimizations` | true | 优化开关 | 整体性能 |
 //! | `cpu_affinity` | 默认配置 | CPU绑定 | 缓存命中率 |
+//! | `power_cap` | 无 | 功率上限 | 活跃线程数和占空比 |
 //!
 //! ## 🔄 使用示例
 //!
@@ -64,6 +66,7 @@
 //!     batch_size: 1500,
 //!     enable_optimizations: true,
 //!     cpu_affinity: CpuAffinityConfig::round_robin(),
+//!     power_cap: None,
 //! };
 //! ```
 //!
@@ -76,6 +79,7 @@
 //! 5. **可观测**: 提供清晰的优化日志输出
 
 use crate::cpu_affinity::CpuAffinityConfig;
+use crate::power::{PowerAllocation, PowerCapConfig, PowerCapGovernor};
 
 
 /// 简化的性能配置
@@ -89,6 +93,8 @@ pub struct PerformanceConfig {
     pub batch_size: u32,
     /// 是否启用优化
     pub enable_optimizations: bool,
+    /// 功率上限（None表示不限制）
+    pub power_cap: Option<PowerCapConfig>,
 }
 
 impl Default for PerformanceConfig {
@@ -98,6 +104,7 @@ impl Default for PerformanceConfig {
             base_hashrate: 2_000_000_000.0, // 2 GH/s
             batch_size: 1000,
             enable_optimizations: true,
+            power_cap: None,
         }
     }
 }
@@ -105,12 +112,14 @@ impl Default for PerformanceConfig {
 /// 简化的性能优化器
 pub struct PerformanceOptimizer {
     config: PerformanceConfig,
+    power_governor: Option<PowerCapGovernor>,
 }
 
 impl PerformanceOptimizer {
     /// 创建性能优化器
     pub fn new(config: PerformanceConfig) -> Self {
-        Self { config }
+        let power_governor = config.power_cap.clone().map(PowerCapGovernor::new);
+        Self { config, power_governor }
     }
 
     /// 针对系统进行优化
@@ -153,4 +162,17 @@ impl PerformanceOptimizer {
             device_config.voltage += (device_id % 3) * 10; // 小幅度调整电压
         }
     }
+
+    /// 功率上限调节器
+    pub fn power_governor(&self) -> Option<&PowerCapGovernor> {
+        self.power_governor.as_ref()
+    }
+
+    /// 按实测功率（或功耗模型）计算功率上限下的线程分配，未配置功率上限时返回None
+    pub fn apply_power_cap(&mut self, measured_watts: Option<f64>, threads: usize) -> Option<PowerAllocation> {
+        if !self.config.enable_optimizations {
+            return None;
+        }
+        self.power_governor.as_mut()?.update(measured_watts, threads)
+    }
 }
@@ -60,7 +60,8 @@
 
 /// 获取平台特定的CPU让出频率
 ///
-/// 这个函数在挖矿循环中被使用，用于优化CPU让出策略
+/// 这个函数在挖矿循环中被使用，用于优化CPU让出策略。
+/// 启用批次调优 (`[tuning]`) 并收敛后，设备改用调优得到的批次大小。
 pub fn get_platform_yield_frequency() -> u64 {
     // 根据平台返回合适的让出频率
     #[cfg(target_os = "macos")]
@@ -0,0 +1,576 @@
+//! # 功耗测量模块
+//!
+//! 本模块在Linux上读取RAPL (Running Average Power Limit) 能量计数器，计算每个CPU封装的
+//! 实际功率，按设备在采样周期内的哈希数把功率分摊到设备，并给出每TH能耗 (J/TH)。
+//!
+//! ## 🚀 数据来源
+//!
+//! ```text
+//! /sys/class/powercap/
+//! ├── intel-rapl:0/              name = package-0
+//! │   ├── energy_uj              累计能量 (微焦)
+//! │   ├── max_energy_range_uj    计数器上限，超过后回绕
+//! │   └── intel-rapl:0:0/        core/uncore/dram 子域（已包含在封装中，不重复统计）
+//! ├── intel-rapl:1/              name = package-1
+//! └── intel-rapl:2/              name = psys（平台功耗，不参与统计）
+//! ```
+//!
+//! AMD Zen 处理器的RAPL同样通过 `intel-rapl` powercap 驱动导出。
+//!
+//! ## 📊 功率归属
+//!
+//! - 绑定到某个封装的设备按哈希数分摊该封装的功率
+//! - 没有绑定设备的封装，其功率由未绑定的设备按哈希数分摊
+//! - 采样周期内没有哈希时平均分摊
+//!
+//! ## 🔄 采样
+//!
+//! 核心运行时由一个采样任务每 `interval_ms` 调用一次 [`PowerMeter::update`]，
+//! 功率上限、收益估算、核心统计和统计日志都只读取 [`PowerMeter::last_report`]，
+//! 不会因为多个读者各自采样而把采样周期切碎。
+//!
+//! ## 🎯 功率上限
+//!
+//! 配置 `[power.cap]` 后，[`PowerCapGovernor`] 按测得的封装功率（或配置的 [`PowerModel`]）
+//! 调整活跃线程数和占空比，使总功率保持在预算以内：
+//!
+//! ```text
+//! 计算单元 u = 活跃线程数 × 占空比
+//! 超出预算:   u ← u × 目标功率 / 实测功率        (立即下调)
+//! 低于预算:   u ← u + 增益 × (上式结果 - u)      (逐步上调)
+//! 分配:       活跃线程 = ⌈u⌉，占空比 = u / ⌈u⌉，其余线程暂停
+//! ```
+//!
+//! 计算集中在较少的线程上，空闲核心可以进入深度睡眠状态，同样功率下算力更高。
+//! 目标功率为预算减去 `headroom` 比例的余量。
+//!
+//! ## ⚙️ 启用条件
+//!
+//! 需要 `power-management` feature、Linux平台，并且 `energy_uj` 可读
+//! （较新的内核只允许root读取）。powercap根目录由 [`PowerConfig::powercap_root`]
+//! 配置，测试时可指向fixture目录。没有RAPL时功率上限只能使用功耗模型。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// 默认的powercap根目录
+pub const DEFAULT_POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+/// 默认的功耗采样周期 (毫秒)
+pub const DEFAULT_POWER_SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// 功耗测量配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerConfig {
+    /// 是否读取RAPL功耗
+    pub enabled: bool,
+    /// powercap根目录
+    pub powercap_root: String,
+    /// 采样周期 (毫秒)，所有功耗读数都来自同一个采样任务
+    pub interval_ms: u64,
+    /// 功率上限（None表示不限制）
+    pub cap: Option<PowerCapConfig>,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            powercap_root: DEFAULT_POWERCAP_ROOT.to_string(),
+            interval_ms: DEFAULT_POWER_SAMPLE_INTERVAL_MS,
+            cap: None,
+        }
+    }
+}
+
+/// 线性功耗模型：功率 = 空闲功率 + 每线程功率 × 计算单元
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerModel {
+    /// 空闲功率 (瓦)
+    pub idle_watts: f64,
+    /// 每个全速线程增加的功率 (瓦)
+    pub watts_per_thread: f64,
+}
+
+impl PowerModel {
+    /// 估算指定计算单元的功率 (瓦)
+    pub fn estimate(&self, units: f64) -> f64 {
+        self.idle_watts + self.watts_per_thread * units
+    }
+
+    /// 达到指定功率的计算单元
+    pub fn units_for(&self, watts: f64) -> f64 {
+        if self.watts_per_thread <= 0.0 {
+            return f64::INFINITY;
+        }
+        ((watts - self.idle_watts) / self.watts_per_thread).max(0.0)
+    }
+}
+
+/// 功率上限配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerCapConfig {
+    /// 功率预算 (瓦)
+    pub budget_watts: f64,
+    /// 调节周期 (毫秒)
+    pub interval_ms: u64,
+    /// 低于预算时的上调增益 (0.0-1.0)
+    pub ramp_gain: f64,
+    /// 预留余量（预算的比例）
+    pub headroom: f64,
+    /// 最少保留的计算单元
+    pub min_units: f64,
+    /// 没有RAPL时使用的功耗模型
+    pub model: Option<PowerModel>,
+}
+
+impl Default for PowerCapConfig {
+    fn default() -> Self {
+        Self {
+            budget_watts: 0.0,
+            interval_ms: 2000,
+            ramp_gain: 0.5,
+            headroom: 0.05,
+            min_units: 0.1,
+            model: None,
+        }
+    }
+}
+
+impl PowerCapConfig {
+    /// 调节目标功率 (瓦)
+    pub fn target_watts(&self) -> f64 {
+        self.budget_watts * (1.0 - self.headroom)
+    }
+}
+
+/// RAPL封装能量域
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RaplDomain {
+    /// CPU封装ID
+    pub package_id: u32,
+    /// 域名称，例如 `package-0`
+    pub name: String,
+    /// 域目录
+    pub path: PathBuf,
+    /// 计数器上限 (微焦)
+    pub max_energy_range_uj: u64,
+}
+
+impl RaplDomain {
+    /// 读取累计能量 (微焦)
+    pub fn read_energy_uj(&self) -> io::Result<u64> {
+        let content = fs::read_to_string(self.path.join("energy_uj"))?;
+        content.trim().parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+/// 发现powercap下的RAPL封装域（按封装ID排序）
+pub fn discover_rapl_domains(powercap_root: impl AsRef<Path>) -> Vec<RaplDomain> {
+    let root = powercap_root.as_ref();
+    let mut domains: Vec<RaplDomain> = fs::read_dir(root)
+        .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            // 只取顶层域 intel-rapl:N，子域 intel-rapl:N:M 已包含在封装中
+            let dir_name = path.file_name()?.to_string_lossy().to_string();
+            let index = dir_name.strip_prefix("intel-rapl:")?;
+            if index.contains(':') {
+                return None;
+            }
+
+            let name = fs::read_to_string(path.join("name")).ok()?.trim().to_string();
+            let package_id = name.strip_prefix("package-")?.parse().ok()?;
+            let max_energy_range_uj = fs::read_to_string(path.join("max_energy_range_uj")).ok()?
+                .trim()
+                .parse()
+                .ok()?;
+
+            Some(RaplDomain { package_id, name, path, max_energy_range_uj })
+        })
+        .collect();
+    domains.sort_by_key(|domain| domain.package_id);
+    domains
+}
+
+/// 两次读数之间的能量增量 (微焦)，处理计数器回绕
+pub fn energy_delta_uj(previous: u64, current: u64, max_energy_range_uj: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else {
+        max_energy_range_uj.saturating_sub(previous) + current
+    }
+}
+
+/// 每TH能耗 (J/TH)，算力为0时返回None
+pub fn joules_per_terahash(watts: f64, hashrate: f64) -> Option<f64> {
+    (hashrate > 0.0 && watts.is_finite()).then(|| watts / (hashrate / 1e12))
+}
+
+/// 一个采样周期内各封装的平均功率
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackagePower {
+    /// 封装ID → 功率 (瓦)
+    pub package_watts: BTreeMap<u32, f64>,
+    /// 采样周期
+    pub interval: Duration,
+}
+
+impl PackagePower {
+    /// 所有封装的总功率 (瓦)
+    pub fn total_watts(&self) -> f64 {
+        self.package_watts.values().sum()
+    }
+}
+
+/// RAPL能量计数器采样器
+#[derive(Debug, Clone)]
+pub struct RaplMeter {
+    domains: Vec<RaplDomain>,
+    last: Option<(Instant, Vec<u64>)>,
+}
+
+impl RaplMeter {
+    /// 在指定powercap根目录下发现RAPL域
+    pub fn new(powercap_root: impl AsRef<Path>) -> Self {
+        Self {
+            domains: discover_rapl_domains(powercap_root),
+            last: None,
+        }
+    }
+
+    /// 发现的RAPL封装域
+    pub fn domains(&self) -> &[RaplDomain] {
+        &self.domains
+    }
+
+    /// 采样一次，返回与上次采样之间的平均功率；第一次采样返回None
+    pub fn sample(&mut self) -> io::Result<Option<PackagePower>> {
+        self.sample_at(Instant::now())
+    }
+
+    /// 以指定时刻采样（测试用）
+    pub fn sample_at(&mut self, now: Instant) -> io::Result<Option<PackagePower>> {
+        let energies = self.domains.iter()
+            .map(RaplDomain::read_energy_uj)
+            .collect::<io::Result<Vec<u64>>>()?;
+
+        let (then, previous) = match self.last.replace((now, energies.clone())) {
+            Some(last) => last,
+            None => return Ok(None),
+        };
+        let interval = now.saturating_duration_since(then);
+        if interval.is_zero() {
+            return Ok(None);
+        }
+
+        let mut package_watts = BTreeMap::new();
+        for ((domain, previous), current) in self.domains.iter().zip(previous).zip(energies) {
+            let joules = energy_delta_uj(previous, current, domain.max_energy_range_uj) as f64 / 1e6;
+            *package_watts.entry(domain.package_id).or_insert(0.0) += joules / interval.as_secs_f64();
+        }
+
+        Ok(Some(PackagePower { package_watts, interval }))
+    }
+}
+
+/// 按哈希数把封装功率分摊到设备
+///
+/// `devices` 中每项为 (设备ID, 绑定的封装ID, 采样周期内的哈希数)。
+pub fn attribute_power(power: &PackagePower, devices: &[(u32, Option<u32>, u64)]) -> BTreeMap<u32, f64> {
+    let mut device_watts = BTreeMap::new();
+    let mut share = |watts: f64, group: &[&(u32, Option<u32>, u64)]| {
+        if group.is_empty() {
+            return;
+        }
+        let total_hashes: u64 = group.iter().map(|(_, _, hashes)| hashes).sum();
+        for (device_id, _, hashes) in group {
+            let fraction = if total_hashes > 0 {
+                *hashes as f64 / total_hashes as f64
+            } else {
+                1.0 / group.len() as f64
+            };
+            *device_watts.entry(*device_id).or_insert(0.0) += watts * fraction;
+        }
+    };
+
+    let unpinned: Vec<_> = devices.iter().filter(|(_, package_id, _)| package_id.is_none()).collect();
+    for (&package_id, &watts) in &power.package_watts {
+        let pinned: Vec<_> = devices.iter().filter(|(_, package, _)| *package == Some(package_id)).collect();
+        if pinned.is_empty() {
+            share(watts, &unpinned);
+        } else {
+            share(watts, &pinned);
+        }
+    }
+    device_watts
+}
+
+/// 功耗报告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PowerReport {
+    /// 所有封装的总功率 (瓦)
+    pub total_watts: f64,
+    /// 封装ID → 功率 (瓦)
+    pub package_watts: BTreeMap<u32, f64>,
+    /// 设备ID → 分摊的功率 (瓦)
+    pub device_watts: BTreeMap<u32, f64>,
+    /// 采样周期内的总算力 (H/s)
+    pub hashrate: f64,
+    /// 每TH能耗 (J/TH)
+    pub joules_per_terahash: Option<f64>,
+    /// 采样周期
+    pub interval: Duration,
+}
+
+/// 设备功耗测量：RAPL采样加上设备哈希数跟踪
+#[derive(Debug, Clone)]
+pub struct PowerMeter {
+    rapl: RaplMeter,
+    last_hashes: HashMap<u32, u64>,
+    last_report: Option<PowerReport>,
+    /// 创建以来累计的能量 (焦耳)
+    total_energy_joules: f64,
+}
+
+impl PowerMeter {
+    /// 在指定powercap根目录下创建功耗测量
+    pub fn new(powercap_root: impl AsRef<Path>) -> Self {
+        Self {
+            rapl: RaplMeter::new(powercap_root),
+            last_hashes: HashMap::new(),
+            last_report: None,
+            total_energy_joules: 0.0,
+        }
+    }
+
+    /// 按配置创建，不支持RAPL时返回None
+    pub fn from_config(config: &PowerConfig) -> Option<Self> {
+        if !config.enabled || !cfg!(feature = "power-management") || !cfg!(target_os = "linux") {
+            return None;
+        }
+
+        let mut meter = Self::new(&config.powercap_root);
+        if meter.rapl.domains().is_empty() {
+            debug!("{} 下没有RAPL封装域", config.powercap_root);
+            return None;
+        }
+        // 先读一次确认有读取权限，同时作为第一次采样
+        if let Err(e) = meter.rapl.sample() {
+            debug!("RAPL能量计数器不可读: {}", e);
+            return None;
+        }
+        Some(meter)
+    }
+
+    /// 发现的RAPL封装域
+    pub fn domains(&self) -> &[RaplDomain] {
+        self.rapl.domains()
+    }
+
+    /// 采样一次
+    ///
+    /// `devices` 中每项为 (设备ID, 绑定的封装ID, 累计哈希数)。第一次采样只记录基线，返回None。
+    pub fn update(&mut self, devices: &[(u32, Option<u32>, u64)]) -> io::Result<Option<PowerReport>> {
+        self.update_at(Instant::now(), devices)
+    }
+
+    /// 以指定时刻采样（测试用）
+    pub fn update_at(&mut self, now: Instant, devices: &[(u32, Option<u32>, u64)]) -> io::Result<Option<PowerReport>> {
+        let power = self.rapl.sample_at(now)?;
+
+        let interval_hashes: Vec<(u32, Option<u32>, u64)> = devices.iter()
+            .map(|&(device_id, package_id, total_hashes)| {
+                let previous = self.last_hashes.get(&device_id).copied().unwrap_or(total_hashes);
+                (device_id, package_id, total_hashes.saturating_sub(previous))
+            })
+            .collect();
+        self.last_hashes = devices.iter().map(|&(device_id, _, total_hashes)| (device_id, total_hashes)).collect();
+
+        let power = match power {
+            Some(power) => power,
+            None => return Ok(None),
+        };
+
+        let total_watts = power.total_watts();
+        let hashes: u64 = interval_hashes.iter().map(|(_, _, hashes)| hashes).sum();
+        let hashrate = hashes as f64 / power.interval.as_secs_f64();
+        let report = PowerReport {
+            total_watts,
+            device_watts: attribute_power(&power, &interval_hashes),
+            package_watts: power.package_watts,
+            hashrate,
+            joules_per_terahash: joules_per_terahash(total_watts, hashrate),
+            interval: power.interval,
+        };
+        self.total_energy_joules += total_watts * power.interval.as_secs_f64();
+        self.last_report = Some(report.clone());
+        Ok(Some(report))
+    }
+
+    /// 最近一次的功耗报告
+    pub fn last_report(&self) -> Option<&PowerReport> {
+        self.last_report.as_ref()
+    }
+
+    /// 创建以来所有采样周期累计的能量 (焦耳)
+    pub fn total_energy_joules(&self) -> f64 {
+        self.total_energy_joules
+    }
+}
+
+/// 线程分配：前 `active_threads` 个线程按 `duty_cycle` 运行，其余暂停
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PowerAllocation {
+    /// 活跃线程数
+    pub active_threads: usize,
+    /// 活跃线程的占空比
+    pub duty_cycle: f64,
+}
+
+impl PowerAllocation {
+    /// 全速运行所有线程
+    pub fn full(threads: usize) -> Self {
+        Self { active_threads: threads, duty_cycle: 1.0 }
+    }
+
+    /// 把计算单元集中到尽量少的线程上
+    pub fn from_units(units: f64) -> Self {
+        let active_threads = (units - 1e-9).ceil().max(1.0) as usize;
+        Self {
+            active_threads,
+            duty_cycle: (units / active_threads as f64).clamp(0.0, 1.0),
+        }
+    }
+
+    /// 计算单元（活跃线程数 × 占空比）
+    pub fn units(&self) -> f64 {
+        self.active_threads as f64 * self.duty_cycle
+    }
+
+    /// 第 `index` 个线程的占空比
+    pub fn duty_for(&self, index: usize) -> f64 {
+        if index < self.active_threads { self.duty_cycle } else { 0.0 }
+    }
+}
+
+/// 功率上限调节器
+#[derive(Debug, Clone)]
+pub struct PowerCapGovernor {
+    config: PowerCapConfig,
+    units: Option<f64>,
+}
+
+impl PowerCapGovernor {
+    /// 创建调节器
+    pub fn new(config: PowerCapConfig) -> Self {
+        Self { config, units: None }
+    }
+
+    /// 调节器配置
+    pub fn config(&self) -> &PowerCapConfig {
+        &self.config
+    }
+
+    /// 当前计算单元，尚未调节时为None
+    pub fn units(&self) -> Option<f64> {
+        self.units
+    }
+
+    /// 根据实测功率计算下一次分配；没有实测功率时使用功耗模型，两者都没有时保持当前分配
+    pub fn update(&mut self, measured_watts: Option<f64>, threads: usize) -> Option<PowerAllocation> {
+        if threads == 0 {
+            return None;
+        }
+
+        let max_units = threads as f64;
+        let min_units = self.config.min_units.clamp(0.0, max_units);
+        let current = self.units.unwrap_or(max_units).clamp(min_units, max_units);
+        let target_watts = self.config.target_watts();
+
+        let units = match (measured_watts, self.config.model) {
+            (Some(watts), _) if watts > 0.0 => {
+                let ideal = current * target_watts / watts;
+                if ideal < current {
+                    ideal
+                } else {
+                    current + self.config.ramp_gain * (ideal - current)
+                }
+            }
+            (_, Some(model)) => model.units_for(target_watts),
+            _ => current,
+        };
+
+        let units = units.clamp(min_units, max_units);
+        self.units = Some(units);
+        Some(PowerAllocation::from_units(units))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_delta_handles_wraparound() {
+        assert_eq!(energy_delta_uj(1_000, 5_000, 262_143_328_850), 4_000);
+        assert_eq!(energy_delta_uj(262_143_000_000, 500_000, 262_143_328_850), 828_850);
+    }
+
+    #[test]
+    fn test_joules_per_terahash() {
+        // 65W 下 10 MH/s
+        let efficiency = joules_per_terahash(65.0, 10e6).unwrap();
+        assert!((efficiency - 6_500_000.0).abs() < 1e-3);
+        assert_eq!(joules_per_terahash(65.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_attribute_power_by_package_and_hashes() {
+        let power = PackagePower {
+            package_watts: BTreeMap::from([(0, 60.0), (1, 40.0)]),
+            interval: Duration::from_secs(1),
+        };
+
+        // 设备1000/1001绑定封装0，按哈希数3:1分摊；封装1没有绑定设备，由未绑定的1002承担
+        let devices = [(1000, Some(0), 300), (1001, Some(0), 100), (1002, None, 0)];
+        let watts = attribute_power(&power, &devices);
+        assert_eq!(watts[&1000], 45.0);
+        assert_eq!(watts[&1001], 15.0);
+        assert_eq!(watts[&1002], 40.0);
+
+        // 没有哈希时平均分摊
+        let watts = attribute_power(&power, &[(1, None, 0), (2, None, 0)]);
+        assert_eq!(watts[&1], 50.0);
+        assert_eq!(watts[&2], 50.0);
+    }
+
+    #[test]
+    fn test_allocation_concentrates_units() {
+        let allocation = PowerAllocation::from_units(4.5);
+        assert_eq!(allocation.active_threads, 5);
+        assert_eq!(allocation.duty_cycle, 0.9);
+        assert_eq!(allocation.duty_for(4), 0.9);
+        assert_eq!(allocation.duty_for(5), 0.0);
+
+        assert_eq!(PowerAllocation::from_units(3.0), PowerAllocation { active_threads: 3, duty_cycle: 1.0 });
+        assert_eq!(PowerAllocation::from_units(0.1).active_threads, 1);
+    }
+
+    #[test]
+    fn test_governor_without_measurement_holds_allocation() {
+        let mut governor = PowerCapGovernor::new(PowerCapConfig { budget_watts: 65.0, ..Default::default() });
+        assert_eq!(governor.update(None, 4), Some(PowerAllocation::full(4)));
+        assert_eq!(governor.update(None, 0), None);
+    }
+}
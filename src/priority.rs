@@ -0,0 +1,188 @@
+//! # 线程优先级模块
+//!
+//! 本模块在挖矿工作线程启动时设置Linux调度策略 (`SCHED_BATCH`/`SCHED_IDLE`)、nice值
+//! 和I/O优先级，让挖矿线程让位于交互式程序。每项设置的结果记录在
+//! [`PriorityStatus`] 中，通过 `SoftwareDevice::priority_status` 查询。
+//!
+//! ## 🚀 设置项
+//!
+//! | 设置 | 系统调用 | 说明 |
+//! |------|----------|------|
+//! | `policy` | `sched_setscheduler` | `batch`: 批处理，减少抢占；`idle`: 只使用空闲CPU时间 |
+//! | `nice` | `setpriority` | -20 (最高) 到 19 (最低)，降低nice值需要 `CAP_SYS_NICE` |
+//! | `ioprio` | `ioprio_set` | `best_effort` 0-7 或 `idle` |
+//!
+//! ## ⚙️ 失败处理
+//!
+//! 和CPU绑定一样，设置失败（如缺少权限）只记录警告，不影响设备启动。
+//! 非Linux平台上请求的设置记录为 [`SettingOutcome::Unsupported`]。
+
+#[cfg(target_os = "linux")]
+use crate::cpu_affinity::current_tid;
+use serde::{Deserialize, Serialize};
+
+/// 调度策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerPolicy {
+    /// 不修改（`SCHED_OTHER`）
+    #[default]
+    Normal,
+    /// `SCHED_BATCH`
+    Batch,
+    /// `SCHED_IDLE`
+    Idle,
+}
+
+/// I/O调度类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoPriorityClass {
+    /// 尽力而为，配合 `level` 使用
+    BestEffort,
+    /// 只在磁盘空闲时进行I/O
+    Idle,
+}
+
+/// I/O优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IoPriority {
+    /// 调度类别
+    pub class: IoPriorityClass,
+    /// 类别内的级别 (0最高 - 7最低)，`idle` 类别忽略
+    #[serde(default = "lowest_io_level")]
+    pub level: u8,
+}
+
+fn lowest_io_level() -> u8 {
+    7
+}
+
+/// 挖矿线程优先级配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriorityConfig {
+    /// 调度策略
+    pub policy: SchedulerPolicy,
+    /// nice值 (-20 到 19)
+    pub nice: Option<i32>,
+    /// I/O优先级
+    pub ioprio: Option<IoPriority>,
+}
+
+impl PriorityConfig {
+    /// 是否请求了任何设置
+    pub fn is_requested(&self) -> bool {
+        self.policy != SchedulerPolicy::Normal || self.nice.is_some() || self.ioprio.is_some()
+    }
+}
+
+/// 单项设置的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", content = "error", rename_all = "snake_case")]
+pub enum SettingOutcome {
+    /// 没有请求该设置
+    NotRequested,
+    /// 已生效
+    Applied,
+    /// 设置失败（错误信息）
+    Failed(String),
+    /// 当前平台不支持
+    Unsupported,
+}
+
+impl SettingOutcome {
+    /// 设置是否失败或不受支持
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_) | Self::Unsupported)
+    }
+}
+
+/// 挖矿线程优先级设置结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PriorityStatus {
+    /// 调度策略
+    pub policy: SettingOutcome,
+    /// nice值
+    pub nice: SettingOutcome,
+    /// I/O优先级
+    pub ioprio: SettingOutcome,
+}
+
+/// 把优先级配置应用到当前线程
+///
+/// 必须在挖矿工作线程上调用，失败时不会中断，结果记录在返回值中。
+pub fn apply_to_current_thread(config: &PriorityConfig) -> PriorityStatus {
+    PriorityStatus {
+        policy: match config.policy {
+            SchedulerPolicy::Normal => SettingOutcome::NotRequested,
+            policy => set_policy(policy),
+        },
+        nice: config.nice.map_or(SettingOutcome::NotRequested, set_nice),
+        ioprio: config.ioprio.map_or(SettingOutcome::NotRequested, set_ioprio),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn last_error() -> SettingOutcome {
+    SettingOutcome::Failed(std::io::Error::last_os_error().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn set_policy(policy: SchedulerPolicy) -> SettingOutcome {
+    let policy = match policy {
+        SchedulerPolicy::Normal => libc::SCHED_OTHER,
+        SchedulerPolicy::Batch => libc::SCHED_BATCH,
+        SchedulerPolicy::Idle => libc::SCHED_IDLE,
+    };
+    let param = libc::sched_param { sched_priority: 0 };
+    // SAFETY: pid 0 表示调用线程，param 在调用期间有效
+    if unsafe { libc::sched_setscheduler(0, policy, &param) } == 0 {
+        SettingOutcome::Applied
+    } else {
+        last_error()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_nice(nice: i32) -> SettingOutcome {
+    // Linux上 PRIO_PROCESS 配合线程ID只修改该线程
+    // SAFETY: setpriority 只读取参数
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, current_tid() as libc::id_t, nice) } == 0 {
+        SettingOutcome::Applied
+    } else {
+        last_error()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_ioprio(ioprio: IoPriority) -> SettingOutcome {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+    let value = match ioprio.class {
+        IoPriorityClass::BestEffort => (2 << IOPRIO_CLASS_SHIFT) | ioprio.level.min(7) as libc::c_int,
+        IoPriorityClass::Idle => 3 << IOPRIO_CLASS_SHIFT,
+    };
+    // SAFETY: ioprio_set 只读取参数
+    if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, current_tid(), value) } == 0 {
+        SettingOutcome::Applied
+    } else {
+        last_error()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_policy(_policy: SchedulerPolicy) -> SettingOutcome {
+    SettingOutcome::Unsupported
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_nice(_nice: i32) -> SettingOutcome {
+    SettingOutcome::Unsupported
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_ioprio(_ioprio: IoPriority) -> SettingOutcome {
+    SettingOutcome::Unsupported
+}
@@ -0,0 +1,290 @@
+//! # 收益估算模块
+//!
+//! 本模块根据实测算力、实测（或配置的）功率、电价、全网难度和区块奖励估算挖矿收益，
+//! 给出每日预期BTC、每日电费和盈亏平衡点，并在按当前电价亏损时建议暂停挖矿。
+//!
+//! ## 📊 计算公式
+//!
+//! ```text
+//! BTC/天     = 算力 × 86400 / (难度 × 2^32) × (区块奖励 + 每块手续费)
+//! 收入/天    = BTC/天 × BTC价格
+//! 电费/天    = 功率 × 24 / 1000 × 电价 (每kWh)
+//! 盈亏平衡电价  = 收入/天 ÷ 每天耗电 kWh
+//! 盈亏平衡币价  = 电费/天 ÷ BTC/天
+//! ```
+//!
+//! ## ⏸️ 自动暂停
+//!
+//! 启用 `auto_pause` 后，收入低于电费时以 [`PauseReason::Unprofitable`] 暂停所有设备；
+//! 收入超过电费 `1 + resume_margin` 倍后恢复，避免在盈亏平衡点附近反复切换。
+//! 暂停期间算力为0，估算器沿用最近一次挖矿时的算力和功率，电价或难度变化后可以恢复。
+//!
+//! ## ⚙️ 难度来源
+//!
+//! `network_difficulty` 大于0时使用配置值，否则从提交的工作区块头的 `nBits` 字段推算
+//! （见 [`difficulty_from_header`]）。
+//!
+//! [`PauseReason::Unprofitable`]: crate::device::PauseReason::Unprofitable
+
+use serde::{Deserialize, Serialize};
+
+/// 每天的秒数
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// 难度1对应的期望哈希数 (2^32)
+const HASHES_PER_DIFFICULTY: f64 = 4_294_967_296.0;
+
+/// 收益估算配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfitabilityConfig {
+    /// 是否启用周期性收益估算
+    pub enabled: bool,
+    /// 电价 (每kWh)
+    pub electricity_price: f64,
+    /// BTC价格 (与电价同一币种)
+    pub btc_price: f64,
+    /// 全网难度（0表示从工作区块头推算）
+    pub network_difficulty: f64,
+    /// 区块奖励 (BTC)
+    pub block_reward: f64,
+    /// 每个区块的平均手续费 (BTC)
+    pub fees_per_block: f64,
+    /// 没有RAPL测量时使用的功率 (瓦)
+    pub watts: Option<f64>,
+    /// 亏损时是否自动暂停挖矿
+    pub auto_pause: bool,
+    /// 恢复挖矿需要的收益余量（电费的比例）
+    pub resume_margin: f64,
+    /// 估算周期 (毫秒)
+    pub interval_ms: u64,
+}
+
+impl Default for ProfitabilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            electricity_price: 0.0,
+            btc_price: 0.0,
+            network_difficulty: 0.0,
+            block_reward: 3.125,
+            fees_per_block: 0.0,
+            watts: None,
+            auto_pause: false,
+            resume_margin: 0.05,
+            interval_ms: 60_000,
+        }
+    }
+}
+
+/// 一次收益估算的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfitabilityEstimate {
+    /// 估算使用的算力 (H/s)
+    pub hashrate: f64,
+    /// 估算使用的功率 (瓦)，未知时为None
+    pub watts: Option<f64>,
+    /// 全网难度
+    pub network_difficulty: f64,
+    /// 每日预期BTC
+    pub btc_per_day: f64,
+    /// 每日收入
+    pub revenue_per_day: f64,
+    /// 每日电费
+    pub cost_per_day: f64,
+    /// 每日利润（收入减电费）
+    pub profit_per_day: f64,
+    /// 盈亏平衡电价 (每kWh)，功率未知时为None
+    pub break_even_electricity_price: Option<f64>,
+    /// 盈亏平衡BTC价格，没有产出时为None
+    pub break_even_btc_price: Option<f64>,
+    /// 按当前电价是否盈利
+    pub profitable: bool,
+}
+
+/// 按配置的电价、币价和区块奖励估算收益，难度无效时返回 `None`
+pub fn estimate(
+    config: &ProfitabilityConfig,
+    hashrate: f64,
+    watts: Option<f64>,
+    network_difficulty: f64,
+) -> Option<ProfitabilityEstimate> {
+    if network_difficulty.is_nan() || network_difficulty <= 0.0 {
+        return None;
+    }
+
+    let hashrate = hashrate.max(0.0);
+    let btc_per_day = hashrate * SECONDS_PER_DAY / (network_difficulty * HASHES_PER_DIFFICULTY)
+        * (config.block_reward + config.fees_per_block);
+    let revenue_per_day = btc_per_day * config.btc_price;
+    let kwh_per_day = watts.map(|watts| watts.max(0.0) * 24.0 / 1000.0);
+    let cost_per_day = kwh_per_day.map_or(0.0, |kwh| kwh * config.electricity_price);
+
+    Some(ProfitabilityEstimate {
+        hashrate,
+        watts,
+        network_difficulty,
+        btc_per_day,
+        revenue_per_day,
+        cost_per_day,
+        profit_per_day: revenue_per_day - cost_per_day,
+        break_even_electricity_price: kwh_per_day
+            .filter(|kwh| *kwh > 0.0)
+            .map(|kwh| revenue_per_day / kwh),
+        break_even_btc_price: (btc_per_day > 0.0).then(|| cost_per_day / btc_per_day),
+        profitable: revenue_per_day >= cost_per_day,
+    })
+}
+
+/// 由压缩难度目标 `nBits` 计算难度，无效时返回 `None`
+pub fn difficulty_from_bits(bits: u32) -> Option<f64> {
+    let exponent = (bits >> 24) as i32;
+    let mantissa = bits & 0x00ff_ffff;
+    if mantissa == 0 || mantissa & 0x0080_0000 != 0 {
+        return None;
+    }
+
+    // 难度1的目标为 0x00ffff × 256^(0x1d - 3)
+    Some(0xffff as f64 / mantissa as f64 * 256f64.powi(0x1d - exponent))
+}
+
+/// 从80字节区块头的 `nBits` 字段（偏移72，小端序）计算难度
+pub fn difficulty_from_header(header: &[u8]) -> Option<f64> {
+    let bits = header.get(72..76)?;
+    difficulty_from_bits(u32::from_le_bytes([bits[0], bits[1], bits[2], bits[3]]))
+}
+
+/// 由区块头哈希（小端序，与 `meets_target` 相同）计算份额难度，全零哈希返回无穷大
+pub fn difficulty_from_hash(hash: &[u8; 32]) -> f64 {
+    let value = hash.iter().rev().fold(0.0, |value, &byte| value * 256.0 + byte as f64);
+    // 难度1的目标为 0xffff × 2^208
+    0xffff as f64 * 2f64.powi(208) / value
+}
+
+/// 收益估算器 - 保存最近的估算结果和自动暂停状态
+#[derive(Debug, Clone)]
+pub struct ProfitabilityEstimator {
+    config: ProfitabilityConfig,
+    /// 从工作区块头推算的难度
+    observed_difficulty: Option<f64>,
+    /// 最近一次挖矿时的算力和实测功率
+    active_sample: Option<(f64, Option<f64>)>,
+    last_estimate: Option<ProfitabilityEstimate>,
+    paused: bool,
+}
+
+impl ProfitabilityEstimator {
+    /// 创建收益估算器
+    pub fn new(config: ProfitabilityConfig) -> Self {
+        Self {
+            config,
+            observed_difficulty: None,
+            active_sample: None,
+            last_estimate: None,
+            paused: false,
+        }
+    }
+
+    /// 估算配置
+    pub fn config(&self) -> &ProfitabilityConfig {
+        &self.config
+    }
+
+    /// 修改电价 (每kWh)
+    pub fn set_electricity_price(&mut self, price: f64) {
+        self.config.electricity_price = price.max(0.0);
+    }
+
+    /// 修改BTC价格
+    pub fn set_btc_price(&mut self, price: f64) {
+        self.config.btc_price = price.max(0.0);
+    }
+
+    /// 记录从工作中得到的全网难度（配置了难度时不使用）
+    pub fn observe_difficulty(&mut self, difficulty: f64) {
+        if difficulty > 0.0 {
+            self.observed_difficulty = Some(difficulty);
+        }
+    }
+
+    /// 当前使用的全网难度
+    pub fn network_difficulty(&self) -> Option<f64> {
+        if self.config.network_difficulty > 0.0 {
+            Some(self.config.network_difficulty)
+        } else {
+            self.observed_difficulty
+        }
+    }
+
+    /// 用最新的算力和实测功率更新估算，返回新的估算结果
+    ///
+    /// 算力为0（暂停中）时沿用最近一次挖矿时的算力和功率。
+    pub fn update(&mut self, hashrate: f64, measured_watts: Option<f64>) -> Option<&ProfitabilityEstimate> {
+        if hashrate > 0.0 {
+            self.active_sample = Some((hashrate, measured_watts));
+        }
+        let (hashrate, measured_watts) = self.active_sample?;
+        let watts = measured_watts.or(self.config.watts);
+        let estimate = estimate(&self.config, hashrate, watts, self.network_difficulty()?)?;
+
+        // 功率未知时无法判断是否亏损
+        if self.config.auto_pause && watts.is_some() {
+            self.paused = if self.paused {
+                estimate.revenue_per_day < estimate.cost_per_day * (1.0 + self.config.resume_margin)
+            } else {
+                !estimate.profitable
+            };
+        } else {
+            self.paused = false;
+        }
+
+        self.last_estimate = Some(estimate);
+        self.last_estimate.as_ref()
+    }
+
+    /// 最近一次的估算结果
+    pub fn last_estimate(&self) -> Option<&ProfitabilityEstimate> {
+        self.last_estimate.as_ref()
+    }
+
+    /// 是否应该因亏损暂停挖矿
+    pub fn should_pause(&self) -> bool {
+        self.paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_from_bits() {
+        assert_eq!(difficulty_from_bits(0x1d00ffff), Some(1.0));
+        let difficulty = difficulty_from_bits(0x1b0404cb).unwrap();
+        assert!((difficulty - 16307.42).abs() < 0.01, "{}", difficulty);
+        assert_eq!(difficulty_from_bits(0), None);
+    }
+
+    #[test]
+    fn test_difficulty_from_header() {
+        let mut header = [0u8; 80];
+        header[72..76].copy_from_slice(&0x1d00ffffu32.to_le_bytes());
+        assert_eq!(difficulty_from_header(&header), Some(1.0));
+        assert_eq!(difficulty_from_header(&header[..40]), None);
+    }
+
+    #[test]
+    fn test_difficulty_from_hash() {
+        // 难度1目标：0xffff × 2^208，小端序的第26、27字节
+        let mut hash = [0u8; 32];
+        hash[26] = 0xff;
+        hash[27] = 0xff;
+        assert_eq!(difficulty_from_hash(&hash), 1.0);
+        hash[26] = 0;
+        hash[27] = 0;
+        hash[24] = 0xff;
+        hash[25] = 0xff;
+        assert_eq!(difficulty_from_hash(&hash), 65536.0);
+        assert_eq!(difficulty_from_hash(&[0u8; 32]), f64::INFINITY);
+    }
+}
@@ -0,0 +1,512 @@
+//! # 有界结果缓冲模块
+//!
+//! 本模块替代原先的无界结果通道和无界结果缓存，为挖矿结果提供有界缓冲、
+//! 显式的溢出策略和丢弃计数，防止在简单难度下无人调用 `collect_results`
+//! 时内存无限增长。
+//!
+//! ## 🚀 数据流
+//!
+//! ```text
+//! 设备挖矿循环 ──ResultSender──▶ 有界通道 ──结果收集任务──▶ ResultBuffer ──collect_results──▶ 调用方
+//! ```
+//!
+//! ## 🎯 溢出策略
+//!
+//! | 策略 | 缓冲已满时的行为 |
+//! |------|------------------|
+//! | `DropOldest` | 丢弃最旧的结果，保留最新结果 (默认) |
+//! | `DropNewest` | 丢弃新到达的结果 |
+//! | `PauseMining` | 结果收集任务等待空间，通道填满后设备挖矿循环随之暂停 |
+//!
+//! 设备和收集任务之间的通道同样按溢出策略处理：`DropOldest` 挤出通道中最旧的结果，
+//! `DropNewest` 丢弃新结果，丢弃类策略下设备挖矿循环永远不会因结果上报而阻塞。
+//! `PauseMining` 下设备等待通道空间，设备停止时等待立即结束。
+//!
+//! [`ResultSender::send`] 返回 [`SendOutcome`]，设备只对真正进入通道的结果计数。
+//!
+//! ## 📊 统计计数
+//!
+//! - `total_received`: 进入缓冲的结果总数
+//! - `dropped_oldest`: 因 `DropOldest` 从缓冲或通道中被挤出的结果数
+//! - `dropped_newest`: 因 `DropNewest` 在缓冲或通道中被丢弃的结果数
+//! - `paused_sends`: `PauseMining` 下设备因通道已满而等待的次数
+
+use cgminer_core::MiningResult;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 默认结果缓冲容量
+pub const DEFAULT_RESULT_BUFFER_CAPACITY: usize = 1024;
+
+/// 结果缓冲溢出策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// 丢弃最旧的结果
+    #[default]
+    DropOldest,
+    /// 丢弃最新的结果
+    DropNewest,
+    /// 暂停挖矿直到结果被收集
+    PauseMining,
+}
+
+impl std::str::FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop_oldest" => Ok(OverflowPolicy::DropOldest),
+            "drop_newest" => Ok(OverflowPolicy::DropNewest),
+            "pause_mining" => Ok(OverflowPolicy::PauseMining),
+            other => Err(format!(
+                "未知的溢出策略 '{}'，可选值: drop_oldest, drop_newest, pause_mining", other
+            )),
+        }
+    }
+}
+
+/// 结果缓冲配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultBufferConfig {
+    /// 缓冲容量（结果个数）
+    pub capacity: usize,
+    /// 溢出策略
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for ResultBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_RESULT_BUFFER_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// 结果缓冲统计信息
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResultBufferStats {
+    /// 当前缓冲的结果数
+    pub buffered: usize,
+    /// 缓冲容量
+    pub capacity: usize,
+    /// 溢出策略
+    pub overflow_policy: OverflowPolicy,
+    /// 进入缓冲的结果总数
+    pub total_received: u64,
+    /// 被挤出的最旧结果数
+    pub dropped_oldest: u64,
+    /// 被丢弃的最新结果数
+    pub dropped_newest: u64,
+    /// 设备因背压等待的次数
+    pub paused_sends: u64,
+}
+
+impl ResultBufferStats {
+    /// 丢弃的结果总数
+    pub fn total_dropped(&self) -> u64 {
+        self.dropped_oldest + self.dropped_newest
+    }
+}
+
+/// 溢出计数器 - 由缓冲和所有发送端共享
+#[derive(Debug, Default)]
+struct OverflowCounters {
+    total_received: AtomicU64,
+    dropped_oldest: AtomicU64,
+    dropped_newest: AtomicU64,
+    paused_sends: AtomicU64,
+}
+
+/// 有界结果缓冲
+#[derive(Debug)]
+pub struct ResultBuffer {
+    results: Mutex<VecDeque<MiningResult>>,
+    config: ResultBufferConfig,
+    space_available: Notify,
+    counters: Arc<OverflowCounters>,
+}
+
+impl ResultBuffer {
+    /// 创建结果缓冲
+    pub fn new(config: ResultBufferConfig) -> Self {
+        let config = ResultBufferConfig {
+            capacity: config.capacity.max(1),
+            ..config
+        };
+
+        Self {
+            results: Mutex::new(VecDeque::with_capacity(config.capacity.min(DEFAULT_RESULT_BUFFER_CAPACITY))),
+            config,
+            space_available: Notify::new(),
+            counters: Arc::new(OverflowCounters::default()),
+        }
+    }
+
+    /// 创建连接到本缓冲的设备发送端和有界接收端
+    pub fn channel(&self) -> (ResultSender, ResultReceiver) {
+        let channel = Arc::new(ResultChannel {
+            queue: Mutex::new(VecDeque::with_capacity(self.config.capacity.min(DEFAULT_RESULT_BUFFER_CAPACITY))),
+            capacity: self.config.capacity,
+            item_available: Notify::new(),
+            space_available: Notify::new(),
+            receiver_closed: AtomicBool::new(false),
+            senders: AtomicUsize::new(1),
+        });
+        let sender = ResultSender {
+            channel: channel.clone(),
+            overflow_policy: self.config.overflow_policy,
+            counters: self.counters.clone(),
+        };
+        (sender, ResultReceiver { channel })
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &ResultBufferConfig {
+        &self.config
+    }
+
+    /// 放入结果，按溢出策略处理满载情况
+    ///
+    /// `PauseMining` 策略下会等待 [`ResultBuffer::drain`] 释放空间。
+    pub async fn push(&self, result: MiningResult) {
+        self.counters.total_received.fetch_add(1, Ordering::Relaxed);
+
+        loop {
+            let notified = self.space_available.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+                if results.len() < self.config.capacity {
+                    results.push_back(result);
+                    return;
+                }
+
+                match self.config.overflow_policy {
+                    OverflowPolicy::DropOldest => {
+                        results.pop_front();
+                        results.push_back(result);
+                        self.counters.dropped_oldest.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    OverflowPolicy::DropNewest => {
+                        self.counters.dropped_newest.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    OverflowPolicy::PauseMining => {}
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// 取出所有缓冲的结果
+    pub fn drain(&self) -> Vec<MiningResult> {
+        let drained = {
+            let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+            results.drain(..).collect::<Vec<_>>()
+        };
+
+        if !drained.is_empty() {
+            self.space_available.notify_waiters();
+        }
+        drained
+    }
+
+    /// 取出指定设备最早的一个结果
+    pub fn take_device_result(&self, device_id: u32) -> Option<MiningResult> {
+        let taken = {
+            let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+            let index = results.iter().position(|result| result.device_id == device_id)?;
+            results.remove(index)
+        };
+
+        self.space_available.notify_waiters();
+        taken
+    }
+
+    /// 当前缓冲的结果数
+    pub fn len(&self) -> usize {
+        self.results.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 缓冲是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 获取统计信息
+    pub fn stats(&self) -> ResultBufferStats {
+        ResultBufferStats {
+            buffered: self.len(),
+            capacity: self.config.capacity,
+            overflow_policy: self.config.overflow_policy,
+            total_received: self.counters.total_received.load(Ordering::Relaxed),
+            dropped_oldest: self.counters.dropped_oldest.load(Ordering::Relaxed),
+            dropped_newest: self.counters.dropped_newest.load(Ordering::Relaxed),
+            paused_sends: self.counters.paused_sends.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 结果上报的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOutcome {
+    /// 结果已进入通道（`DropOldest` 下可能挤出了更旧的结果）
+    Sent,
+    /// 结果被丢弃（`DropNewest` 通道已满，或 `PauseMining` 等待期间设备停止）
+    Dropped,
+    /// 接收端已关闭
+    Closed,
+}
+
+/// 设备和结果收集任务之间的有界通道
+#[derive(Debug)]
+struct ResultChannel {
+    queue: Mutex<VecDeque<MiningResult>>,
+    capacity: usize,
+    /// 有新结果或发送端全部关闭
+    item_available: Notify,
+    /// 接收端取走结果或关闭
+    space_available: Notify,
+    receiver_closed: AtomicBool,
+    /// 存活的发送端数量
+    senders: AtomicUsize,
+}
+
+/// 设备端结果发送器
+#[derive(Debug)]
+pub struct ResultSender {
+    channel: Arc<ResultChannel>,
+    overflow_policy: OverflowPolicy,
+    counters: Arc<OverflowCounters>,
+}
+
+impl Clone for ResultSender {
+    fn clone(&self) -> Self {
+        self.channel.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            channel: self.channel.clone(),
+            overflow_policy: self.overflow_policy,
+            counters: self.counters.clone(),
+        }
+    }
+}
+
+impl Drop for ResultSender {
+    fn drop(&mut self) {
+        if self.channel.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.channel.item_available.notify_one();
+        }
+    }
+}
+
+impl ResultSender {
+    /// 发送结果，按溢出策略处理通道已满的情况
+    ///
+    /// `PauseMining` 下等待通道空间，`stopped` 完成（设备停止）时放弃等待并返回 [`SendOutcome::Dropped`]。
+    pub async fn send(&self, result: MiningResult, stopped: impl Future<Output = ()>) -> SendOutcome {
+        tokio::pin!(stopped);
+        let mut paused = false;
+
+        loop {
+            let space_available = self.channel.space_available.notified();
+            tokio::pin!(space_available);
+            space_available.as_mut().enable();
+
+            if self.channel.receiver_closed.load(Ordering::Acquire) {
+                return SendOutcome::Closed;
+            }
+            {
+                let mut queue = self.channel.queue.lock().unwrap_or_else(|e| e.into_inner());
+                if queue.len() < self.channel.capacity {
+                    queue.push_back(result);
+                    drop(queue);
+                    self.channel.item_available.notify_one();
+                    return SendOutcome::Sent;
+                }
+
+                match self.overflow_policy {
+                    OverflowPolicy::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(result);
+                        drop(queue);
+                        self.counters.dropped_oldest.fetch_add(1, Ordering::Relaxed);
+                        self.channel.item_available.notify_one();
+                        return SendOutcome::Sent;
+                    }
+                    OverflowPolicy::DropNewest => {
+                        self.counters.dropped_newest.fetch_add(1, Ordering::Relaxed);
+                        return SendOutcome::Dropped;
+                    }
+                    OverflowPolicy::PauseMining => {
+                        if !paused {
+                            self.counters.paused_sends.fetch_add(1, Ordering::Relaxed);
+                            paused = true;
+                        }
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = &mut space_available => {}
+                _ = &mut stopped => return SendOutcome::Dropped,
+            }
+        }
+    }
+}
+
+/// 结果收集任务端的接收器
+#[derive(Debug)]
+pub struct ResultReceiver {
+    channel: Arc<ResultChannel>,
+}
+
+impl ResultReceiver {
+    /// 接收下一个结果，所有发送端关闭且通道为空时返回 `None`
+    pub async fn recv(&mut self) -> Option<MiningResult> {
+        loop {
+            let result = self.channel.queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+            if let Some(result) = result {
+                self.channel.space_available.notify_waiters();
+                return Some(result);
+            }
+            if self.channel.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+            self.channel.item_available.notified().await;
+        }
+    }
+}
+
+impl Drop for ResultReceiver {
+    fn drop(&mut self) {
+        self.channel.receiver_closed.store(true, Ordering::Release);
+        self.channel.space_available.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn test_result(nonce: u32) -> MiningResult {
+        let work = cgminer_core::Work::new("buffer_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0);
+        MiningResult::new(work.id, 1, nonce, vec![0u8; 32], true)
+    }
+
+    fn buffer(capacity: usize, overflow_policy: OverflowPolicy) -> ResultBuffer {
+        ResultBuffer::new(ResultBufferConfig { capacity, overflow_policy })
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_latest_results() {
+        let buffer = buffer(2, OverflowPolicy::DropOldest);
+        for nonce in 0..5 {
+            buffer.push(test_result(nonce)).await;
+        }
+
+        let stats = buffer.stats();
+        assert_eq!(stats.buffered, 2);
+        assert_eq!(stats.total_received, 5);
+        assert_eq!(stats.dropped_oldest, 3);
+
+        let nonces: Vec<u32> = buffer.drain().iter().map(|r| r.nonce).collect();
+        assert_eq!(nonces, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_keeps_first_results() {
+        let buffer = buffer(2, OverflowPolicy::DropNewest);
+        for nonce in 0..5 {
+            buffer.push(test_result(nonce)).await;
+        }
+
+        assert_eq!(buffer.stats().dropped_newest, 3);
+        let nonces: Vec<u32> = buffer.drain().iter().map(|r| r.nonce).collect();
+        assert_eq!(nonces, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_pause_mining_waits_for_drain() {
+        let buffer = Arc::new(buffer(1, OverflowPolicy::PauseMining));
+        buffer.push(test_result(0)).await;
+
+        let pusher = {
+            let buffer = buffer.clone();
+            tokio::spawn(async move { buffer.push(test_result(1)).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!pusher.is_finished(), "缓冲已满时应该等待");
+
+        assert_eq!(buffer.drain().len(), 1);
+        tokio::time::timeout(Duration::from_secs(1), pusher).await.unwrap().unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.stats().total_dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_channel_drop_oldest_evicts_oldest() {
+        let buffer = buffer(2, OverflowPolicy::DropOldest);
+        let (sender, mut receiver) = buffer.channel();
+        for nonce in 0..4 {
+            assert_eq!(sender.send(test_result(nonce), std::future::pending()).await, SendOutcome::Sent);
+        }
+
+        assert_eq!(buffer.stats().dropped_oldest, 2);
+        assert_eq!(receiver.recv().await.map(|r| r.nonce), Some(2));
+        assert_eq!(receiver.recv().await.map(|r| r.nonce), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_channel_drop_newest_reports_dropped() {
+        let buffer = buffer(1, OverflowPolicy::DropNewest);
+        let (sender, mut receiver) = buffer.channel();
+        assert_eq!(sender.send(test_result(0), std::future::pending()).await, SendOutcome::Sent);
+        assert_eq!(sender.send(test_result(1), std::future::pending()).await, SendOutcome::Dropped);
+        assert_eq!(receiver.recv().await.map(|r| r.nonce), Some(0));
+
+        drop(sender);
+        assert!(receiver.recv().await.is_none(), "发送端全部关闭后接收结束");
+    }
+
+    #[tokio::test]
+    async fn test_channel_pause_mining_stops_waiting() {
+        let buffer = buffer(1, OverflowPolicy::PauseMining);
+        let (sender, receiver) = buffer.channel();
+        assert_eq!(sender.send(test_result(0), std::future::pending()).await, SendOutcome::Sent);
+
+        // 通道已满：停止信号结束等待
+        let stop = Arc::new(Notify::new());
+        let waiting = {
+            let sender = sender.clone();
+            let stop = stop.clone();
+            tokio::spawn(async move { sender.send(test_result(1), async move { stop.notified().await }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished(), "通道已满时应该等待");
+        assert_eq!(buffer.stats().paused_sends, 1);
+        stop.notify_one();
+        let outcome = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(outcome, SendOutcome::Dropped);
+
+        drop(receiver);
+        assert_eq!(sender.send(test_result(2), std::future::pending()).await, SendOutcome::Closed);
+    }
+
+    #[test]
+    fn test_overflow_policy_from_str() {
+        assert_eq!("pause_mining".parse::<OverflowPolicy>(), Ok(OverflowPolicy::PauseMining));
+        assert!("drop_everything".parse::<OverflowPolicy>().is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! # 挖矿时间表模块
+//!
+//! 本模块按时间段（星期 + 时刻，支持跨午夜）决定挖矿的占空比，用于只在低谷电价时段
+//! 全速挖矿、其他时段降速或停止。时间表针对可注入的 [`Clock`] 计算，测试中使用
+//! [`ManualClock`] 指定任意时间。
+//!
+//! ## 🚀 规则
+//!
+//! - 按配置顺序匹配第一个包含当前时间的时间段，使用该时间段的 `duty_cycle`
+//! - 不在任何时间段内时使用 `default_duty_cycle`
+//! - 占空比为0时以 [`PauseReason::OutsideSchedule`] 暂停所有设备，(0, 1) 之间按比例降速
+//! - `end` 早于 `start` 的时间段跨越午夜，属于 `start` 所在的那一天
+//! - `start` 等于 `end` 表示全天
+//!
+//! ## ⚙️ 时区
+//!
+//! `timezone` 可以是 `local`（系统时区）、`utc` 或固定偏移如 `+08:00`。
+//!
+//! ## 🔄 配置示例
+//!
+//! ```toml
+//! [schedule]
+//! enabled = true
+//! timezone = "+08:00"
+//! default_duty_cycle = 0.0         # 时间段之外停止
+//!
+//! [[schedule.windows]]
+//! name = "夜间低谷电价"
+//! days = ["mon", "tue", "wed", "thu", "fri"]
+//! start = "23:00"
+//! end = "07:00"
+//!
+//! [[schedule.windows]]
+//! name = "周末"
+//! days = ["sat", "sun"]
+//! start = "00:00"
+//! end = "00:00"
+//! duty_cycle = 0.5
+//! ```
+//!
+//! [`PauseReason::OutsideSchedule`]: crate::device::PauseReason::OutsideSchedule
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveTime, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// 时间表配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// 是否启用时间表
+    pub enabled: bool,
+    /// 时区: `local`、`utc` 或固定偏移 (如 `+08:00`)
+    pub timezone: String,
+    /// 不在任何时间段内时的占空比 (0.0表示停止)
+    pub default_duty_cycle: f64,
+    /// 时间段（按顺序匹配）
+    pub windows: Vec<ScheduleWindow>,
+    /// 检查周期 (毫秒)
+    pub interval_ms: u64,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timezone: "local".to_string(),
+            default_duty_cycle: 0.0,
+            windows: Vec::new(),
+            interval_ms: 30_000,
+        }
+    }
+}
+
+/// 时间段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleWindow {
+    /// 名称（用于日志和统计）
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 生效的星期 (`mon`..`sun`)，为空表示每天
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// 开始时刻 (`HH:MM`)
+    pub start: String,
+    /// 结束时刻 (`HH:MM`)，早于开始时刻表示跨越午夜
+    pub end: String,
+    /// 时间段内的占空比
+    #[serde(default = "full_duty_cycle")]
+    pub duty_cycle: f64,
+}
+
+fn full_duty_cycle() -> f64 {
+    1.0
+}
+
+/// 时钟 - 返回当前UTC时间
+pub trait Clock: Send + Sync {
+    /// 当前时间
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 手动设置时间的时钟（测试使用），克隆后共享同一个时间
+#[derive(Debug, Clone)]
+pub struct ManualClock(Arc<RwLock<DateTime<Utc>>>);
+
+impl ManualClock {
+    /// 创建指向指定时间的时钟
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Arc::new(RwLock::new(now)))
+    }
+
+    /// 修改当前时间
+    pub fn set(&self, now: DateTime<Utc>) {
+        if let Ok(mut current) = self.0.write() {
+            *current = now;
+        }
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0.read().map(|now| *now).unwrap_or_else(|_| Utc::now())
+    }
+}
+
+/// 时区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTimezone {
+    /// 系统时区
+    Local,
+    /// 固定偏移（`utc` 为零偏移）
+    Fixed(FixedOffset),
+}
+
+impl ScheduleTimezone {
+    /// 解析 `local`、`utc` 或 `+HH:MM`/`-HH:MM`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "local" => return Ok(Self::Local),
+            "utc" | "z" => return Ok(Self::Fixed(FixedOffset::east_opt(0).expect("零偏移有效"))),
+            _ => {}
+        }
+
+        let (sign, rest) = if let Some(rest) = value.strip_prefix('+') {
+            (1, rest)
+        } else if let Some(rest) = value.strip_prefix('-') {
+            (-1, rest)
+        } else {
+            return Err(format!("无效的时区 {:?}，应为 local、utc 或 +HH:MM", value));
+        };
+        let time = parse_time(rest).map_err(|_| format!("无效的时区偏移 {:?}", value))?;
+        let seconds = sign * (time.hour() * 3600 + time.minute() * 60) as i32;
+        FixedOffset::east_opt(seconds)
+            .map(Self::Fixed)
+            .ok_or_else(|| format!("时区偏移超出范围 {:?}", value))
+    }
+
+    /// 把UTC时间换算为该时区的星期和时刻
+    fn local_time(&self, now: DateTime<Utc>) -> (Weekday, NaiveTime) {
+        match self {
+            Self::Local => {
+                let local = now.with_timezone(&Local);
+                (local.weekday(), local.time())
+            }
+            Self::Fixed(offset) => {
+                let local = now.with_timezone(offset);
+                (local.weekday(), local.time())
+            }
+        }
+    }
+}
+
+/// 解析 `HH:MM` 时刻
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("无效的时刻 {:?}，应为 HH:MM", value))
+}
+
+/// 解析星期名称 (`mon`、`monday` 等英文名称，不区分大小写)
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value.trim().parse::<Weekday>().map_err(|_| format!("无效的星期 {:?}", value))
+}
+
+/// 解析后的时间段
+#[derive(Debug, Clone, PartialEq)]
+struct Window {
+    name: String,
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+    duty_cycle: f64,
+}
+
+impl Window {
+    fn on_day(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, day: Weekday, time: NaiveTime) -> bool {
+        if self.start == self.end {
+            self.on_day(day)
+        } else if self.start < self.end {
+            self.on_day(day) && time >= self.start && time < self.end
+        } else {
+            // 跨越午夜：开始当天的 start 之后，或第二天的 end 之前
+            (self.on_day(day) && time >= self.start) || (self.on_day(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// 时间表在某个时刻的状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduleState {
+    /// 当前是否允许挖矿（占空比大于0）
+    pub active: bool,
+    /// 当前占空比
+    pub duty_cycle: f64,
+    /// 当前所在的时间段名称，不在任何时间段内时为None
+    pub window: Option<String>,
+    /// 计算时间
+    pub evaluated_at: DateTime<Utc>,
+}
+
+/// 挖矿时间表
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    timezone: ScheduleTimezone,
+    default_duty_cycle: f64,
+    windows: Vec<Window>,
+}
+
+impl Schedule {
+    /// 从配置解析时间表
+    pub fn from_config(config: &ScheduleConfig) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&config.default_duty_cycle) {
+            return Err("default_duty_cycle 必须在0.0到1.0之间".to_string());
+        }
+
+        let windows = config.windows.iter().enumerate()
+            .map(|(index, window)| {
+                if !(0.0..=1.0).contains(&window.duty_cycle) {
+                    return Err(format!("时间段 {} 的 duty_cycle 必须在0.0到1.0之间", index));
+                }
+                let start = parse_time(&window.start)?;
+                let end = parse_time(&window.end)?;
+                Ok(Window {
+                    name: window.name.clone().unwrap_or_else(|| format!("{}-{}", window.start, window.end)),
+                    days: window.days.iter().map(|day| parse_weekday(day)).collect::<Result<_, _>>()?,
+                    start,
+                    end,
+                    duty_cycle: window.duty_cycle,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            timezone: ScheduleTimezone::parse(&config.timezone)?,
+            default_duty_cycle: config.default_duty_cycle,
+            windows,
+        })
+    }
+
+    /// 计算指定时刻的状态
+    pub fn evaluate(&self, now: DateTime<Utc>) -> ScheduleState {
+        let (day, time) = self.timezone.local_time(now);
+        let window = self.windows.iter().find(|window| window.contains(day, time));
+        let duty_cycle = window.map_or(self.default_duty_cycle, |window| window.duty_cycle);
+
+        ScheduleState {
+            active: duty_cycle > 0.0,
+            duty_cycle,
+            window: window.map(|window| window.name.clone()),
+            evaluated_at: now,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! # 统计日志模块 (跨重启的累计统计)
+//!
+//! 设备的 `AtomicStats` 和核心的 `CoreStats` 在进程重启后都从0开始。本模块把累计哈希数、
+//! 接受/拒绝的份额、硬件错误、最佳份额难度、运行时间和能耗保存到日志文件，
+//! 下一次初始化时读取，长期运行的矿机重启后仍保留累计总数。
+//!
+//! ## 🚀 工作方式
+//!
+//! 1. `initialize` 时读取日志文件作为基线（文件不存在时从0开始）
+//! 2. 运行期间每 `interval_ms` 毫秒写一次：基线 + 本次会话的统计
+//! 3. `stop` / `shutdown` 时再写一次
+//! 4. 写入使用临时文件加重命名的原子替换，写到一半断电也不会损坏已有的日志
+//!
+//! 日志无法解析时改名为 `*.corrupt` 保留，再从0开始，不会被新的日志覆盖。
+//! 因权限等原因无法读取时不改名，本次会话也不写入日志。
+//!
+//! ## 📊 日志内容
+//!
+//! | 字段 | 说明 |
+//! |------|------|
+//! | `total_hashes` | 累计哈希数 |
+//! | `accepted_work` / `rejected_work` | 累计接受/拒绝的份额 |
+//! | `hardware_errors` | 累计硬件错误 |
+//! | `best_share_difficulty` | 最佳份额难度 |
+//! | `uptime_secs` | 累计挖矿时间 (秒) |
+//! | `energy_joules` | 累计能耗 (焦耳，需要RAPL功耗测量) |
+//! | `sessions` | 会话次数 |
+//!
+//! ## 🔄 配置示例
+//!
+//! ```toml
+//! [stats_journal]
+//! enabled = true
+//! path = "/var/lib/cgminer/stats.json"
+//! interval_ms = 60000
+//! ```
+
+use crate::config;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::warn;
+
+/// 统计日志配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsJournalConfig {
+    /// 是否启用统计日志
+    pub enabled: bool,
+    /// 日志文件（启用时必须设置）
+    pub path: Option<PathBuf>,
+    /// 写入周期 (毫秒)
+    pub interval_ms: u64,
+}
+
+impl Default for StatsJournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            interval_ms: 60_000,
+        }
+    }
+}
+
+/// 累计统计（日志文件内容）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    /// 累计哈希数
+    pub total_hashes: u64,
+    /// 累计接受的份额
+    pub accepted_work: u64,
+    /// 累计拒绝的份额
+    pub rejected_work: u64,
+    /// 累计硬件错误
+    pub hardware_errors: u64,
+    /// 最佳份额难度
+    pub best_share_difficulty: f64,
+    /// 累计挖矿时间 (秒)
+    pub uptime_secs: f64,
+    /// 累计能耗 (焦耳)
+    pub energy_joules: f64,
+    /// 会话次数（包括当前会话）
+    pub sessions: u64,
+    /// 最后写入时间 (Unix秒)
+    pub updated_at: u64,
+}
+
+impl LifetimeStats {
+    /// 读取日志文件：文件不存在时返回 `Ok(None)`，无法解析时返回 `InvalidData` 错误
+    pub fn load(path: &Path) -> std::io::Result<Option<Self>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// 写入日志文件（原子替换）
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        config::write_file_atomic(path, &content)
+    }
+}
+
+/// 本次会话的计数（由核心从设备统计和功耗测量汇总）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionCounters {
+    /// 哈希数
+    pub total_hashes: u64,
+    /// 接受的份额
+    pub accepted_work: u64,
+    /// 拒绝的份额
+    pub rejected_work: u64,
+    /// 硬件错误
+    pub hardware_errors: u64,
+    /// 能耗 (焦耳)
+    pub energy_joules: f64,
+}
+
+/// 统计日志：上一次保存的基线加上本次会话的运行时间和最佳份额
+///
+/// 时间通过 `*_at` 方法的参数传入，便于测试。
+#[derive(Debug, Clone)]
+pub struct StatsJournal {
+    config: StatsJournalConfig,
+    baseline: LifetimeStats,
+    best_share_difficulty: f64,
+    /// 已结束的运行时段的总时间
+    run_time: Duration,
+    /// 当前运行时段的开始时间
+    running_since: Option<Instant>,
+}
+
+impl StatsJournal {
+    /// 创建统计日志并读取已有的日志文件作为基线
+    pub fn open(mut config: StatsJournalConfig) -> Self {
+        let baseline = match config.path {
+            Some(ref path) if config.enabled => Self::load_baseline(path),
+            _ => Some(LifetimeStats::default()),
+        };
+        // 无法读取已有的日志时不写入，避免覆盖
+        config.enabled &= baseline.is_some();
+        let baseline = baseline.unwrap_or_default();
+        Self {
+            config,
+            baseline,
+            best_share_difficulty: 0.0,
+            run_time: Duration::ZERO,
+            running_since: None,
+        }
+    }
+
+    /// 读取基线；日志存在但无法读取（非格式错误）时返回 `None`
+    fn load_baseline(path: &Path) -> Option<LifetimeStats> {
+        match LifetimeStats::load(path) {
+            Ok(baseline) => Some(baseline.unwrap_or_default()),
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                // 保留无法解析的日志，避免被新的日志覆盖
+                let backup = path.with_extension("corrupt");
+                warn!("统计日志 {} 无法解析 ({})，改名为 {} 后从0开始",
+                      path.display(), e, backup.display());
+                if let Err(e) = std::fs::rename(path, &backup) {
+                    warn!("保留损坏的统计日志失败 ({})，本次会话不写入统计日志", e);
+                    return None;
+                }
+                Some(LifetimeStats::default())
+            }
+            Err(e) => {
+                warn!("统计日志 {} 无法读取 ({})，本次会话不写入统计日志", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// 统计日志配置
+    pub fn config(&self) -> &StatsJournalConfig {
+        &self.config
+    }
+
+    /// 是否启用并设置了日志文件
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && self.config.path.is_some()
+    }
+
+    /// 读取到的基线（之前会话的累计统计）
+    pub fn baseline(&self) -> &LifetimeStats {
+        &self.baseline
+    }
+
+    /// 开始一个运行时段（核心启动）
+    pub fn mark_started_at(&mut self, now: Instant) {
+        if self.running_since.is_none() {
+            self.running_since = Some(now);
+        }
+    }
+
+    /// 结束当前运行时段（核心停止）
+    pub fn mark_stopped_at(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.run_time += now.saturating_duration_since(since);
+        }
+    }
+
+    /// 本次会话的运行时间
+    pub fn session_uptime_at(&self, now: Instant) -> Duration {
+        self.run_time + self.running_since.map_or(Duration::ZERO, |since| now.saturating_duration_since(since))
+    }
+
+    /// 记录一个通过验证的份额难度
+    pub fn observe_share(&mut self, difficulty: f64) {
+        if difficulty.is_finite() && difficulty > self.best_share_difficulty {
+            self.best_share_difficulty = difficulty;
+        }
+    }
+
+    /// 基线加上本次会话的累计统计
+    pub fn lifetime_at(&self, session: &SessionCounters, now: Instant) -> LifetimeStats {
+        let baseline = &self.baseline;
+        LifetimeStats {
+            total_hashes: baseline.total_hashes + session.total_hashes,
+            accepted_work: baseline.accepted_work + session.accepted_work,
+            rejected_work: baseline.rejected_work + session.rejected_work,
+            hardware_errors: baseline.hardware_errors + session.hardware_errors,
+            best_share_difficulty: baseline.best_share_difficulty.max(self.best_share_difficulty),
+            uptime_secs: baseline.uptime_secs + self.session_uptime_at(now).as_secs_f64(),
+            energy_joules: baseline.energy_joules + session.energy_joules,
+            sessions: baseline.sessions + 1,
+            updated_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+
+    /// 写入日志文件，返回写入的累计统计；未启用时返回 `Ok(None)`
+    pub fn save_at(&self, session: &SessionCounters, now: Instant) -> std::io::Result<Option<LifetimeStats>> {
+        let path = match self.config.path {
+            Some(ref path) if self.config.enabled => path,
+            _ => return Ok(None),
+        };
+        let lifetime = self.lifetime_at(session, now);
+        lifetime.save(path)?;
+        Ok(Some(lifetime))
+    }
+}
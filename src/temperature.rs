@@ -1,18 +1,19 @@
 //! # 系统温度监控模块
 //!
 //! 本模块提供跨平台的系统温度监控功能，专门用于CPU挖矿过程中的温度检测和保护。
-//! 支持Linux和macOS平台的真实温度读取，确保挖矿设备在安全温度范围内运行。
+//! 温度来源可插拔：Linux读取sysfs，其他平台可配置外部命令，测试可注入脚本温度。
 //!
 //! ## 🚀 温度监控特性
 //!
-//! ### 平台支持
-//! - 🌡️ **Linux**: 通过`/sys/class/thermal/`读取CPU温度
-//! - 🌡️ **macOS**: 通过系统API获取CPU温度
-//! - ⚠️ **Windows**: 暂不支持，返回错误
-//! - ⚠️ **其他平台**: 不支持温度监控
+//! ### 温度来源
+//! - 🌡️ [`SysfsTemperatureSource`]: Linux下枚举`/sys/class/hwmon/`和`/sys/class/thermal/`的全部传感器
+//! - 🛠️ [`CommandTemperatureSource`]: 运行用户命令并解析输出（例如macOS上的`osx-cpu-temp`）
+//! - 🧪 [`ScriptedTemperatureSource`]: 按脚本或回放文件返回温度，用于测试温度相关逻辑
+//! - ⚠️ 其他平台未配置命令时不支持温度监控
 //!
 //! ### 核心组件
 //! - [`TemperatureManager`]: 主要的温度管理器
+//! - [`TemperatureSource`]: 可插拔的温度来源
 //! - [`TemperatureConfig`]: 温度监控配置
 //! - [`TemperatureStatus`]: 温度状态枚举
 //! - [`TemperatureError`]: 温度相关错误类型
@@ -21,16 +22,28 @@
 //!
 //! ### Linux平台
 //! ```text
-//! 温度源:
-//! └── /sys/class/thermal/thermal_zone0/temp (主CPU温度)
+//! 温度源 (按优先级):
+//! ├── /sys/class/hwmon/hwmon*/      coretemp "Package id N"、k10temp/zenpower Tdie/Tctl
+//! ├── /sys/class/thermal/thermal_zone*/  type = x86_pkg_temp
+//! ├── 其他CPU传感器                 AMD Tccd*、cpu-thermal 等热区
+//! ├── 单核传感器                    coretemp "Core N"
+//! └── 其他热区                      acpitz 等
 //! ```
 //!
-//! ### macOS平台
+//! 封装温度取优先级最高且可读的传感器；coretemp 的单核温度通过
+//! [`TemperatureManager::read_core_temperatures`] 单独提供。
+//! sysfs根目录由 [`TemperatureConfig::sysfs_root`] 配置，测试时可指向fixture目录。
+//!
+//! ### 外部命令
 //! ```text
-//! 温度源:
-//! └── 模拟温度 (45-60°C范围，用于测试)
+//! command = ["/usr/local/bin/osx-cpu-temp"]   → 输出 "61.5°C"
+//! command = ["cat", "/run/cpu_temp"]          → 输出 "61500" (毫摄氏度)
 //! ```
 //!
+//! 配置了 [`TemperatureConfig::command`] 时在所有平台上代替sysfs。
+//! 命令由 [`TemperatureManager::refresh`] 异步运行（核心每个调节周期运行一次），
+//! 读取温度只返回最近一次的结果，多个设备共享同一个管理器时不会重复运行命令。
+//!
 //! ## 🎯 配置参数
 //!
 //! | 参数 | 默认值 | 说明 | 用途 |
@@ -38,6 +51,10 @@
 //! | `enable_real_monitoring` | true | 启用真实监控 | 功能开关 |
 //! | `warning_threshold` | 75.0°C | 警告阈值 | 性能降级 |
 //! | `critical_threshold` | 85.0°C | 临界阈值 | 紧急停机 |
+//! | `sysfs_root` | `/sys` | sysfs根目录 | 传感器发现 |
+//! | `command` | 无 | 外部温度命令 | 非Linux平台 |
+//! | `command_timeout_ms` | 2000 | 外部命令超时 | 防止命令挂起 |
+//! | `governor` | 启用 | 闭环调节参数 | 见 [`crate::thermal`] |
 //!
 //! ## 🔄 使用示例
 //!
@@ -50,6 +67,7 @@
 //!     enable_real_monitoring: true,
 //!     warning_threshold: 70.0,
 //!     critical_threshold: 80.0,
+//!     ..Default::default()
 //! };
 //!
 //! // 创建温度管理器
@@ -62,6 +80,15 @@
 //! }
 //! ```
 //!
+//! ### 注入脚本温度
+//! ```rust
+//! use cgminer_cpu_btc_core::temperature::{ScriptedTemperatureSource, TemperatureConfig, TemperatureManager};
+//!
+//! let source = ScriptedTemperatureSource::new([60.0, 78.0, 86.0]);
+//! let temp_manager = TemperatureManager::with_source(TemperatureConfig::default(), source);
+//! assert_eq!(temp_manager.read_temperature().unwrap(), 60.0);
+//! ```
+//!
 //! ### 温度状态检查
 //! ```rust
 //! // 检查温度状态
@@ -82,16 +109,34 @@
 //! - 📝 最小化外部依赖
 //!
 //! ### 平台适配
-//! - ⚡ 编译时平台检测
+//! - ⚡ 通过 [`TemperatureSource`] 隔离平台逻辑
 //! - ⚡ 运行时能力查询
 //! - ⚡ 优雅的降级处理
 //! - ⚡ 详细的提供者信息
 
+use crate::thermal::ThermalGovernorConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use thiserror::Error;
+use tracing::{debug, warn};
+
+/// 默认的sysfs根目录
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys";
+
+/// 默认的温度命令超时 (毫秒)
+pub const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 2000;
+
+/// 提供CPU温度的hwmon驱动
+const CPU_HWMON_DRIVERS: &[&str] = &["coretemp", "k10temp", "zenpower"];
 
 /// 温度错误类型
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum TemperatureError {
     #[error("温度读取失败: {0}")]
     ReadFailed(String),
@@ -100,7 +145,8 @@ pub enum TemperatureError {
 }
 
 /// 简化的温度配置
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct TemperatureConfig {
     /// 是否启用真实温度监控
     pub enable_real_monitoring: bool,
@@ -108,6 +154,14 @@ pub struct TemperatureConfig {
     pub warning_threshold: f32,
     /// 温度危险阈值（摄氏度）
     pub critical_threshold: f32,
+    /// sysfs根目录（传感器发现）
+    pub sysfs_root: String,
+    /// 读取温度的外部命令（程序和参数），设置后代替sysfs
+    pub command: Option<Vec<String>>,
+    /// 外部命令超时 (毫秒)
+    pub command_timeout_ms: u64,
+    /// 闭环温度调节
+    pub governor: ThermalGovernorConfig,
 }
 
 impl Default for TemperatureConfig {
@@ -116,105 +170,707 @@ impl Default for TemperatureConfig {
             enable_real_monitoring: true,
             warning_threshold: 75.0,
             critical_threshold: 85.0,
+            sysfs_root: DEFAULT_SYSFS_ROOT.to_string(),
+            command: None,
+            command_timeout_ms: DEFAULT_COMMAND_TIMEOUT_MS,
+            governor: ThermalGovernorConfig::default(),
+        }
+    }
+}
+
+/// 温度传感器来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorSource {
+    /// `/sys/class/hwmon/hwmon*`
+    Hwmon,
+    /// `/sys/class/thermal/thermal_zone*`
+    ThermalZone,
+}
+
+/// 温度传感器类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorKind {
+    /// CPU封装温度
+    Package,
+    /// 单个物理核心的温度（coretemp "Core N"）
+    Core(u32),
+    /// CPU的其他传感器（AMD CCD、cpu-thermal 热区等）
+    Cpu,
+    /// 与CPU无关或无法识别的传感器
+    Other,
+}
+
+/// 温度传感器
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TemperatureSensor {
+    /// 来源
+    pub source: SensorSource,
+    /// hwmon驱动名或热区类型
+    pub name: String,
+    /// hwmon传感器标签
+    pub label: Option<String>,
+    /// 类别
+    pub kind: SensorKind,
+    /// 所在的CPU封装ID（coretemp）
+    pub package_id: Option<u32>,
+    /// 温度文件（毫摄氏度）
+    pub path: PathBuf,
+}
+
+impl TemperatureSensor {
+    /// 读取温度（摄氏度）
+    pub fn read(&self) -> Result<f32, TemperatureError> {
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| TemperatureError::ReadFailed(format!("{}: {}", self.path.display(), e)))?;
+        let millis: i64 = content.trim().parse()
+            .map_err(|e| TemperatureError::ReadFailed(format!("{}: {}", self.path.display(), e)))?;
+        Ok(millis as f32 / 1000.0)
+    }
+
+    /// 传感器描述，例如 `coretemp/Package id 0`
+    pub fn describe(&self) -> String {
+        match self.label {
+            Some(ref label) => format!("{}/{}", self.name, label),
+            None => self.name.clone(),
+        }
+    }
+
+    /// 作为封装温度的优先级，数值越小越优先
+    fn priority(&self) -> u8 {
+        match (self.kind, self.source) {
+            (SensorKind::Package, SensorSource::Hwmon) if self.label.as_deref() == Some("Tctl") => 1,
+            (SensorKind::Package, SensorSource::Hwmon) => 0,
+            (SensorKind::Package, SensorSource::ThermalZone) => 2,
+            (SensorKind::Cpu, _) => 3,
+            (SensorKind::Core(_), _) => 4,
+            (SensorKind::Other, _) => 5,
+        }
+    }
+}
+
+/// 单个物理核心的温度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CoreTemperature {
+    /// CPU封装ID
+    pub package_id: u32,
+    /// 封装内的核心ID（与topology的 `core_id` 对应）
+    pub core_id: u32,
+    /// 温度（摄氏度）
+    pub celsius: f32,
+}
+
+/// 设备温度的来源范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureScope {
+    /// 尚未读取到温度
+    Unavailable,
+    /// CPU封装温度
+    Package,
+    /// 设备绑定的逻辑CPU所在物理核心的温度
+    Core {
+        /// 逻辑CPU编号
+        cpu: usize,
+    },
+}
+
+impl TemperatureScope {
+    /// 打包为u64，便于原子存储
+    pub(crate) fn pack(self) -> u64 {
+        match self {
+            TemperatureScope::Unavailable => 0,
+            TemperatureScope::Package => 1,
+            TemperatureScope::Core { cpu } => 2 + cpu as u64,
+        }
+    }
+
+    /// 从 [`TemperatureScope::pack`] 的结果还原
+    pub(crate) fn unpack(packed: u64) -> Self {
+        match packed {
+            0 => TemperatureScope::Unavailable,
+            1 => TemperatureScope::Package,
+            cpu => TemperatureScope::Core { cpu: (cpu - 2) as usize },
+        }
+    }
+}
+
+impl fmt::Display for TemperatureScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemperatureScope::Unavailable => write!(f, "不可用"),
+            TemperatureScope::Package => write!(f, "封装"),
+            TemperatureScope::Core { cpu } => write!(f, "CPU {} 核心", cpu),
+        }
+    }
+}
+
+/// 发现sysfs下的所有温度传感器，按封装温度优先级排序
+pub fn discover_sensors(sysfs_root: impl AsRef<Path>) -> Vec<TemperatureSensor> {
+    let root = sysfs_root.as_ref();
+    let mut sensors = discover_hwmon(&root.join("class/hwmon"));
+    sensors.extend(discover_thermal_zones(&root.join("class/thermal")));
+    sensors.sort_by(|a, b| a.priority().cmp(&b.priority()).then_with(|| a.path.cmp(&b.path)));
+    sensors
+}
+
+/// 读取目录下名称带指定前缀的子目录（按名称排序）
+fn prefixed_dirs(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|content| content.trim().to_string())
+}
+
+fn discover_hwmon(class_dir: &Path) -> Vec<TemperatureSensor> {
+    let mut sensors = Vec::new();
+
+    for dir in prefixed_dirs(class_dir, "hwmon") {
+        let name = match read_trimmed(&dir.join("name")) {
+            Some(name) if CPU_HWMON_DRIVERS.contains(&name.as_str()) => name,
+            _ => continue,
+        };
+
+        let mut inputs: Vec<(u32, Option<String>, PathBuf)> = fs::read_dir(&dir)
+            .map(|entries| {
+                entries.filter_map(|entry| entry.ok())
+                    .filter_map(|entry| {
+                        let file_name = entry.file_name().to_string_lossy().to_string();
+                        let index = file_name.strip_prefix("temp")?.strip_suffix("_input")?.parse().ok()?;
+                        let label = read_trimmed(&dir.join(format!("temp{}_label", index)));
+                        Some((index, label, entry.path()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        inputs.sort_by_key(|(index, _, _)| *index);
+
+        // coretemp 同一目录下的传感器属于同一个封装
+        let package_id = inputs.iter()
+            .filter_map(|(_, label, _)| label.as_deref()?.strip_prefix("Package id ")?.trim().parse().ok())
+            .next();
+
+        for (_, label, path) in inputs {
+            let kind = classify_hwmon(&name, label.as_deref());
+            sensors.push(TemperatureSensor {
+                source: SensorSource::Hwmon,
+                name: name.clone(),
+                label,
+                kind,
+                package_id,
+                path,
+            });
+        }
+    }
+
+    sensors
+}
+
+fn classify_hwmon(driver: &str, label: Option<&str>) -> SensorKind {
+    match (driver, label) {
+        ("coretemp", Some(label)) if label.starts_with("Package id") => SensorKind::Package,
+        ("coretemp", Some(label)) => label.strip_prefix("Core ")
+            .and_then(|core| core.trim().parse().ok())
+            .map_or(SensorKind::Cpu, SensorKind::Core),
+        // k10temp 在旧内核上没有标签，temp1 即 Tctl
+        (_, None) | (_, Some("Tdie")) | (_, Some("Tctl")) => SensorKind::Package,
+        _ => SensorKind::Cpu,
+    }
+}
+
+fn discover_thermal_zones(class_dir: &Path) -> Vec<TemperatureSensor> {
+    prefixed_dirs(class_dir, "thermal_zone")
+        .into_iter()
+        .filter_map(|dir| {
+            let zone_type = read_trimmed(&dir.join("type"))?;
+            let path = dir.join("temp");
+            if !path.exists() {
+                return None;
+            }
+
+            let lower = zone_type.to_lowercase();
+            let kind = if lower == "x86_pkg_temp" {
+                SensorKind::Package
+            } else if lower.contains("cpu") || lower.contains("soc") || lower.contains("pkg") {
+                SensorKind::Cpu
+            } else {
+                SensorKind::Other
+            };
+
+            Some(TemperatureSensor {
+                source: SensorSource::ThermalZone,
+                name: zone_type,
+                label: None,
+                kind,
+                package_id: None,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// 温度来源
+///
+/// [`TemperatureManager`] 通过该trait读取温度，内置 [`SysfsTemperatureSource`]、
+/// [`CommandTemperatureSource`] 和 [`ScriptedTemperatureSource`] 三种实现。
+pub trait TemperatureSource: Send + Sync {
+    /// 来源描述（日志用）
+    fn describe(&self) -> String;
+
+    /// 读取CPU封装温度
+    fn read_temperature(&self) -> Result<f32, TemperatureError>;
+
+    /// 读取指定封装内物理核心的温度，不支持单核温度时返回None
+    fn read_core_temperature(&self, _package_id: u32, _core_id: u32) -> Option<f32> {
+        None
+    }
+
+    /// 读取全部单核温度（按封装和核心ID排序）
+    fn read_core_temperatures(&self) -> Vec<CoreTemperature> {
+        Vec::new()
+    }
+
+    /// 底层的sysfs传感器
+    fn sensors(&self) -> &[TemperatureSensor] {
+        &[]
+    }
+
+    /// 进入下一个采样周期，由 [`TemperatureManager::refresh`] 每个周期调用一次
+    fn advance(&self) {}
+}
+
+/// 从sysfs的hwmon和热区读取温度
+#[derive(Debug, Clone)]
+pub struct SysfsTemperatureSource {
+    sensors: Vec<TemperatureSensor>,
+}
+
+impl SysfsTemperatureSource {
+    /// 在指定sysfs根目录下发现传感器
+    pub fn new(sysfs_root: impl AsRef<Path>) -> Self {
+        Self::from_sensors(discover_sensors(sysfs_root))
+    }
+
+    /// 使用已发现的传感器（按封装温度优先级排序）
+    pub fn from_sensors(sensors: Vec<TemperatureSensor>) -> Self {
+        Self { sensors }
+    }
+
+    /// 提供封装温度的传感器
+    pub fn package_sensor(&self) -> Option<&TemperatureSensor> {
+        self.sensors.iter().find(|sensor| sensor.read().is_ok())
+    }
+}
+
+impl TemperatureSource for SysfsTemperatureSource {
+    fn describe(&self) -> String {
+        match self.package_sensor() {
+            Some(sensor) => sensor.describe(),
+            None => "Linux hwmon/thermal_zone".to_string(),
+        }
+    }
+
+    fn read_temperature(&self) -> Result<f32, TemperatureError> {
+        let mut last_error = TemperatureError::ReadFailed("无法读取系统温度".to_string());
+        for sensor in &self.sensors {
+            match sensor.read() {
+                Ok(temp) => return Ok(temp),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    fn read_core_temperature(&self, package_id: u32, core_id: u32) -> Option<f32> {
+        self.sensors.iter()
+            .find(|sensor| {
+                sensor.kind == SensorKind::Core(core_id) && sensor.package_id.unwrap_or(0) == package_id
+            })
+            .and_then(|sensor| sensor.read().ok())
+    }
+
+    fn read_core_temperatures(&self) -> Vec<CoreTemperature> {
+        let mut temperatures: Vec<CoreTemperature> = self.sensors.iter()
+            .filter_map(|sensor| match sensor.kind {
+                SensorKind::Core(core_id) => Some(CoreTemperature {
+                    package_id: sensor.package_id.unwrap_or(0),
+                    core_id,
+                    celsius: sensor.read().ok()?,
+                }),
+                _ => None,
+            })
+            .collect();
+        temperatures.sort_by_key(|temp| (temp.package_id, temp.core_id));
+        temperatures
+    }
+
+    fn sensors(&self) -> &[TemperatureSensor] {
+        &self.sensors
+    }
+}
+
+/// 运行外部命令读取温度
+///
+/// 输出格式见 [`parse_temperature_output`]。命令直接执行，不经过shell。
+/// [`CommandTemperatureSource::sample`] 异步运行命令并保存结果，`read_temperature` 只返回最近一次的结果；
+/// 克隆的来源共享同一份结果。
+#[derive(Debug, Clone)]
+pub struct CommandTemperatureSource {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+    /// 最近一次运行命令的结果
+    last_reading: Arc<Mutex<Option<Result<f32, TemperatureError>>>>,
+}
+
+impl CommandTemperatureSource {
+    /// 创建命令温度来源，`command` 第一个元素为程序，其余为参数
+    pub fn new(command: &[String], timeout: Duration) -> Result<Self, TemperatureError> {
+        let (program, args) = command.split_first()
+            .ok_or_else(|| TemperatureError::ReadFailed("温度命令为空".to_string()))?;
+        Ok(Self {
+            program: program.clone(),
+            args: args.to_vec(),
+            timeout,
+            last_reading: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// 运行一次命令并保存结果，超时的命令会被杀掉
+    pub async fn sample(&self) -> Result<f32, TemperatureError> {
+        let reading = self.run().await;
+        if let Ok(mut last_reading) = self.last_reading.lock() {
+            *last_reading = Some(reading.clone());
+        }
+        reading
+    }
+
+    async fn run(&self) -> Result<f32, TemperatureError> {
+        let child = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| TemperatureError::ReadFailed(format!("{}: {}", self.program, e)))?;
+
+        // 等待退出的同时读取标准输出，输出超过管道缓冲区时命令也不会阻塞；
+        // 超时时丢弃子进程句柄，kill_on_drop 杀掉命令
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output()).await
+            .map_err(|_| TemperatureError::ReadFailed(format!("{} 超时", self.program)))?
+            .map_err(|e| TemperatureError::ReadFailed(format!("{}: {}", self.program, e)))?;
+        if !output.status.success() {
+            return Err(TemperatureError::ReadFailed(format!("{} 退出状态 {}", self.program, output.status)));
         }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_temperature_output(&stdout)
+            .ok_or_else(|| TemperatureError::ReadFailed(format!("{} 输出中没有温度: {:?}", self.program, stdout.trim())))
     }
 }
 
-/// 简化的温度管理器
+impl TemperatureSource for CommandTemperatureSource {
+    fn describe(&self) -> String {
+        format!("命令 {}", self.program)
+    }
+
+    fn read_temperature(&self) -> Result<f32, TemperatureError> {
+        let last_reading = self.last_reading.lock()
+            .map_err(|e| TemperatureError::ReadFailed(e.to_string()))?;
+        last_reading.clone()
+            .unwrap_or_else(|| Err(TemperatureError::ReadFailed(format!("{} 尚未运行", self.program))))
+    }
+}
+
+/// 解析命令输出中的温度，例如 `61.5`、`61500`、`CPU: +61.5°C` 或 `Package id 0: +61.0°C`
+///
+/// 取第一个非空行；行中有 `:` 时只解析最后一个 `:` 之后的部分（避免把标签中的编号当作温度），
+/// 取其中第一个数字。不小于1000的数值按毫摄氏度换算。
+pub fn parse_temperature_output(output: &str) -> Option<f32> {
+    let line = output.lines().find(|line| !line.trim().is_empty())?;
+    let value = line.rsplit(':').next().unwrap_or(line);
+    value.split_whitespace()
+        .filter_map(|token| {
+            token.trim_matches(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+                .parse::<f32>()
+                .ok()
+        })
+        .find(|value| value.is_finite())
+        .map(|value| if value.abs() >= 1000.0 { value / 1000.0 } else { value })
+}
+
+/// 按脚本返回温度（测试和回放用）
+///
+/// 每次 [`TemperatureManager::refresh`] 进入脚本的下一个温度，同一周期内的所有读取返回
+/// 同一个温度，与共用管理器的设备数量无关；首次刷新前返回第一个温度，脚本耗尽后重复最后一个温度。
+#[derive(Debug, Default)]
+pub struct ScriptedTemperatureSource {
+    script: Mutex<ScriptState>,
+    cores: HashMap<(u32, u32), f32>,
+}
+
+/// 脚本进度：尚未使用的温度和当前周期的温度
+#[derive(Debug, Default)]
+struct ScriptState {
+    pending: VecDeque<f32>,
+    current: Option<f32>,
+}
+
+impl ScriptedTemperatureSource {
+    /// 创建脚本温度来源
+    pub fn new(temperatures: impl IntoIterator<Item = f32>) -> Self {
+        Self {
+            script: Mutex::new(ScriptState {
+                pending: temperatures.into_iter().collect(),
+                current: None,
+            }),
+            cores: HashMap::new(),
+        }
+    }
+
+    /// 从回放文件读取温度，每行一个温度，忽略空行和 `#` 注释
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TemperatureError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| TemperatureError::ReadFailed(format!("{}: {}", path.display(), e)))?;
+
+        let mut temperatures = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let temperature = line.parse()
+                .map_err(|e| TemperatureError::ReadFailed(format!("{}:{}: {}", path.display(), index + 1, e)))?;
+            temperatures.push(temperature);
+        }
+        Ok(Self::new(temperatures))
+    }
+
+    /// 设置指定物理核心的固定温度
+    pub fn with_core(mut self, package_id: u32, core_id: u32, celsius: f32) -> Self {
+        self.cores.insert((package_id, core_id), celsius);
+        self
+    }
+}
+
+impl TemperatureSource for ScriptedTemperatureSource {
+    fn describe(&self) -> String {
+        "脚本温度".to_string()
+    }
+
+    fn read_temperature(&self) -> Result<f32, TemperatureError> {
+        let script = self.script.lock()
+            .map_err(|e| TemperatureError::ReadFailed(e.to_string()))?;
+        script.current
+            .or_else(|| script.pending.front().copied())
+            .ok_or_else(|| TemperatureError::ReadFailed("温度脚本为空".to_string()))
+    }
+
+    fn advance(&self) {
+        if let Ok(mut script) = self.script.lock() {
+            if let Some(next) = script.pending.pop_front() {
+                script.current = Some(next);
+            }
+        }
+    }
+
+    fn read_core_temperature(&self, package_id: u32, core_id: u32) -> Option<f32> {
+        self.cores.get(&(package_id, core_id)).copied()
+    }
+
+    fn read_core_temperatures(&self) -> Vec<CoreTemperature> {
+        let mut temperatures: Vec<CoreTemperature> = self.cores.iter()
+            .map(|(&(package_id, core_id), &celsius)| CoreTemperature { package_id, core_id, celsius })
+            .collect();
+        temperatures.sort_by_key(|temp| (temp.package_id, temp.core_id));
+        temperatures
+    }
+}
+
+/// 温度管理器
+///
+/// 克隆的管理器共享同一个温度来源，核心为所有设备共用一个管理器。
+#[derive(Clone)]
 pub struct TemperatureManager {
     config: TemperatureConfig,
-    has_real_monitoring: bool,
+    /// 温度来源，None表示不支持温度监控
+    source: Option<Arc<dyn TemperatureSource>>,
+    /// 外部命令来源（与 `source` 共享结果），由 [`TemperatureManager::refresh`] 运行
+    command: Option<CommandTemperatureSource>,
 }
 
 impl TemperatureManager {
-    /// 创建温度管理器
+    /// 按配置创建温度管理器
+    ///
+    /// 配置了 `command` 时使用外部命令，否则在Linux上读取sysfs；其他平台不支持温度监控。
     pub fn new(config: TemperatureConfig) -> Self {
-        let has_real_monitoring = Self::check_temperature_support();
+        match Self::command_source(&config) {
+            Some(command) => Self {
+                config,
+                source: Some(Arc::new(command.clone())),
+                command: Some(command),
+            },
+            None => {
+                let source = Self::default_source(&config);
+                Self { config, source, command: None }
+            }
+        }
+    }
 
+    /// 使用指定的温度来源
+    pub fn with_source(config: TemperatureConfig, source: impl TemperatureSource + 'static) -> Self {
         Self {
             config,
-            has_real_monitoring,
+            source: Some(Arc::new(source)),
+            command: None,
         }
     }
 
-    /// 检查系统是否支持温度监控
-    fn check_temperature_support() -> bool {
-        // 简化的平台检查
-        cfg!(any(target_os = "linux", target_os = "macos"))
+    /// 创建按脚本返回温度的管理器（测试用），见 [`ScriptedTemperatureSource`]
+    pub fn scripted(config: TemperatureConfig, temperatures: impl IntoIterator<Item = f32>) -> Self {
+        Self::with_source(config, ScriptedTemperatureSource::new(temperatures))
     }
 
-    /// 读取温度
-    pub fn read_temperature(&self) -> Result<f32, TemperatureError> {
-        if !self.has_real_monitoring {
-            return Err(TemperatureError::NotSupported);
-        }
-
-        // 尝试读取系统温度
-        #[cfg(target_os = "linux")]
-        {
-            // Linux: 尝试从 /sys/class/thermal 读取
-            if let Ok(temp_str) = std::fs::read_to_string("/sys/class/thermal/thermal_zone0/temp") {
-                if let Ok(temp_millis) = temp_str.trim().parse::<i32>() {
-                    return Ok(temp_millis as f32 / 1000.0);
-                }
+    fn command_source(config: &TemperatureConfig) -> Option<CommandTemperatureSource> {
+        if !config.enable_real_monitoring {
+            return None;
+        }
+        let command = config.command.as_ref()?;
+        let timeout = Duration::from_millis(config.command_timeout_ms);
+        match CommandTemperatureSource::new(command, timeout) {
+            Ok(source) => Some(source),
+            Err(e) => {
+                warn!("温度命令配置无效: {}", e);
+                None
+            }
+        }
+    }
+
+    fn default_source(config: &TemperatureConfig) -> Option<Arc<dyn TemperatureSource>> {
+        if !config.enable_real_monitoring || config.command.is_some() {
+            return None;
+        }
+
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
+        let source = SysfsTemperatureSource::new(&config.sysfs_root);
+        debug!("在 {} 下发现 {} 个温度传感器", config.sysfs_root, source.sensors().len());
+        if source.sensors().is_empty() {
+            None
+        } else {
+            Some(Arc::new(source))
+        }
+    }
+
+    /// 进入下一个采样周期：重新运行外部命令、推进脚本温度，sysfs来源在读取时直接读取
+    pub async fn refresh(&self) {
+        if let Some(ref source) = self.source {
+            source.advance();
+        }
+        if let Some(ref command) = self.command {
+            if let Err(e) = command.sample().await {
+                debug!("温度命令采样失败: {}", e);
             }
         }
+    }
+
+    /// 是否需要周期性调用 [`TemperatureManager::refresh`]
+    pub fn needs_refresh(&self) -> bool {
+        self.command.is_some()
+    }
+
+    /// 获取温度配置
+    pub fn config(&self) -> &TemperatureConfig {
+        &self.config
+    }
 
-        #[cfg(target_os = "macos")]
-        {
-            // macOS: 简化实现，返回模拟温度
-            // 实际实现需要使用系统API或第三方库
-            return Ok(45.0 + fastrand::f32() * 15.0); // 45-60°C 范围
+    /// 读取温度（优先封装温度）
+    pub fn read_temperature(&self) -> Result<f32, TemperatureError> {
+        match self.source {
+            Some(ref source) => source.read_temperature(),
+            None => Err(TemperatureError::NotSupported),
         }
+    }
 
-        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-        {
-            Err(TemperatureError::ReadFailed("无法读取系统温度".to_string()))
+    /// 读取单核温度（按封装和核心ID排序）
+    pub fn read_core_temperatures(&self) -> Vec<CoreTemperature> {
+        self.source.as_ref().map(|source| source.read_core_temperatures()).unwrap_or_default()
+    }
+
+    /// 读取指定封装内物理核心的温度
+    pub fn read_core_temperature(&self, package_id: u32, core_id: u32) -> Option<f32> {
+        self.source.as_ref()?.read_core_temperature(package_id, core_id)
+    }
+
+    /// 读取所有传感器，忽略读取失败的传感器
+    pub fn read_all(&self) -> Vec<(TemperatureSensor, f32)> {
+        self.sensors().iter()
+            .filter_map(|sensor| Some((sensor.clone(), sensor.read().ok()?)))
+            .collect()
+    }
+
+    /// 发现的传感器（按封装温度优先级排序），非sysfs来源为空
+    pub fn sensors(&self) -> &[TemperatureSensor] {
+        match self.source {
+            Some(ref source) => source.sensors(),
+            None => &[],
         }
     }
 
+    /// 提供封装温度的传感器
+    pub fn package_sensor(&self) -> Option<&TemperatureSensor> {
+        self.sensors().iter().find(|sensor| sensor.read().is_ok())
+    }
+
     /// 检查温度状态
     pub fn check_temperature_status(&self) -> Result<TemperatureStatus, TemperatureError> {
         let temp = self.read_temperature()?;
+        Ok(self.classify(temp))
+    }
 
+    /// 按配置阈值对温度分级
+    pub fn classify(&self, temp: f32) -> TemperatureStatus {
         if temp >= self.config.critical_threshold {
-            Ok(TemperatureStatus::Critical)
+            TemperatureStatus::Critical
         } else if temp >= self.config.warning_threshold {
-            Ok(TemperatureStatus::Warning)
+            TemperatureStatus::Warning
         } else {
-            Ok(TemperatureStatus::Normal)
+            TemperatureStatus::Normal
         }
     }
 
     /// 获取提供者信息
-    pub fn provider_info(&self) -> &'static str {
-        if self.has_real_monitoring {
-            #[cfg(target_os = "linux")]
-            return "Linux thermal_zone";
-
-            #[cfg(target_os = "macos")]
-            return "macOS 系统温度";
-
-            #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-            return "未知系统";
-        } else {
-            "不支持温度监控"
+    pub fn provider_info(&self) -> String {
+        match self.source {
+            Some(ref source) => source.describe(),
+            None => "不支持温度监控".to_string(),
         }
     }
 
     /// 检查是否支持真实监控
     pub fn supports_real_monitoring(&self) -> bool {
-        self.has_real_monitoring
+        self.source.is_some()
     }
 
     /// 检查是否有温度监控
     pub fn has_temperature_monitoring(&self) -> bool {
-        self.has_real_monitoring
+        self.source.is_some()
     }
 }
 
 /// 温度状态
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TemperatureStatus {
     Normal,
     Warning,
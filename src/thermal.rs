@@ -0,0 +1,252 @@
+//! # 热管理调节模块
+//!
+//! 本模块实现闭环温度调节：温度超过警告阈值后，PI控制器降低设备的占空比，
+//! 使温度回到目标温度附近；达到危险阈值时暂停设备，降温后带滞回地恢复。
+//!
+//! ## 🚀 状态机
+//!
+//! ```text
+//!            温度 ≥ 警告               温度 ≥ 危险
+//! Normal ─────────────▶ Throttled ─────────────▶ Paused
+//!   ▲                      │  ▲                     │
+//!   └──────────────────────┘  └─────────────────────┘
+//!     温度 ≤ 警告 - 滞回          温度 ≤ 危险 - 滞回
+//! ```
+//!
+//! ## 🎯 PI控制
+//!
+//! `Throttled` 状态下，占空比 = `1 - kp × 误差 - ki × ∫误差dt`，
+//! 误差 = 当前温度 - 目标温度（警告阈值 - `target_offset`），
+//! 结果限制在 `[min_duty_cycle, 1.0]`，饱和时停止积分（抗积分饱和）。
+//!
+//! 危险阈值取 [`crate::temperature::TemperatureConfig::critical_threshold`] 和
+//! `DeviceConfig::temperature_limit` 中较低的一个。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// 热管理调节配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThermalGovernorConfig {
+    /// 是否启用闭环调节
+    pub enabled: bool,
+    /// 调节周期 (毫秒)
+    pub interval_ms: u64,
+    /// 目标温度低于警告阈值的幅度 (摄氏度)
+    pub target_offset: f32,
+    /// 比例系数（每超出1°C降低的占空比）
+    pub kp: f64,
+    /// 积分系数（每 °C·s 降低的占空比）
+    pub ki: f64,
+    /// 降频时的最低占空比
+    pub min_duty_cycle: f64,
+    /// 恢复滞回 (摄氏度)
+    pub resume_hysteresis: f32,
+}
+
+impl Default for ThermalGovernorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 1000,
+            target_offset: 2.0,
+            kp: 0.05,
+            ki: 0.01,
+            min_duty_cycle: 0.1,
+            resume_hysteresis: 5.0,
+        }
+    }
+}
+
+/// 调节状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThermalState {
+    /// 全速运行
+    Normal,
+    /// 降低占空比
+    Throttled,
+    /// 暂停计算
+    Paused,
+}
+
+impl fmt::Display for ThermalState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThermalState::Normal => write!(f, "正常"),
+            ThermalState::Throttled => write!(f, "降频"),
+            ThermalState::Paused => write!(f, "暂停"),
+        }
+    }
+}
+
+/// 一次调节的结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalDecision {
+    /// 调节后的状态
+    pub state: ThermalState,
+    /// 调节后的占空比
+    pub duty_cycle: f64,
+    /// 状态发生变化时为变化前的状态
+    pub previous: Option<ThermalState>,
+}
+
+/// 闭环温度调节器
+#[derive(Debug, Clone)]
+pub struct ThermalGovernor {
+    config: ThermalGovernorConfig,
+    warning: f32,
+    critical: f32,
+    state: ThermalState,
+    integral: f64,
+    duty_cycle: f64,
+}
+
+impl ThermalGovernor {
+    /// 创建调节器
+    pub fn new(config: ThermalGovernorConfig, warning: f32, critical: f32) -> Self {
+        let mut governor = Self {
+            config,
+            warning,
+            critical,
+            state: ThermalState::Normal,
+            integral: 0.0,
+            duty_cycle: 1.0,
+        };
+        governor.set_thresholds(warning, critical);
+        governor
+    }
+
+    /// 更新阈值，警告阈值不会高于危险阈值
+    pub fn set_thresholds(&mut self, warning: f32, critical: f32) {
+        self.critical = critical;
+        self.warning = warning.min(critical);
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> ThermalState {
+        self.state
+    }
+
+    /// 当前占空比
+    pub fn duty_cycle(&self) -> f64 {
+        self.duty_cycle
+    }
+
+    /// 目标温度
+    pub fn target_temperature(&self) -> f32 {
+        self.warning - self.config.target_offset
+    }
+
+    /// 根据最新温度和距上次调节的时间更新状态和占空比
+    pub fn update(&mut self, temperature: f32, elapsed: Duration) -> ThermalDecision {
+        let previous = self.state;
+        let hysteresis = self.config.resume_hysteresis;
+
+        self.state = if temperature >= self.critical {
+            ThermalState::Paused
+        } else {
+            match self.state {
+                ThermalState::Paused if temperature <= self.critical - hysteresis => ThermalState::Throttled,
+                ThermalState::Paused => ThermalState::Paused,
+                ThermalState::Normal if temperature >= self.warning => ThermalState::Throttled,
+                ThermalState::Normal => ThermalState::Normal,
+                ThermalState::Throttled if temperature <= self.warning - hysteresis => ThermalState::Normal,
+                ThermalState::Throttled => ThermalState::Throttled,
+            }
+        };
+
+        self.duty_cycle = match self.state {
+            ThermalState::Normal => {
+                self.integral = 0.0;
+                1.0
+            }
+            ThermalState::Paused => {
+                self.integral = 0.0;
+                0.0
+            }
+            ThermalState::Throttled => self.pi_step(temperature, elapsed),
+        };
+
+        ThermalDecision {
+            state: self.state,
+            duty_cycle: self.duty_cycle,
+            previous: (previous != self.state).then_some(previous),
+        }
+    }
+
+    fn pi_step(&mut self, temperature: f32, elapsed: Duration) -> f64 {
+        let error = (temperature - self.target_temperature()) as f64;
+        let integral = self.integral + error * elapsed.as_secs_f64();
+        let output = 1.0 - self.config.kp * error - self.config.ki * integral;
+        let duty_cycle = output.clamp(self.config.min_duty_cycle, 1.0);
+
+        // 抗积分饱和：输出饱和时不再累积误差
+        if duty_cycle == output {
+            self.integral = integral;
+        }
+        duty_cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_secs(1);
+
+    fn governor() -> ThermalGovernor {
+        ThermalGovernor::new(ThermalGovernorConfig::default(), 75.0, 85.0)
+    }
+
+    #[test]
+    fn test_pi_reduces_duty_cycle_while_hot() {
+        let mut governor = governor();
+        assert_eq!(governor.update(60.0, TICK).duty_cycle, 1.0);
+
+        let first = governor.update(78.0, TICK);
+        assert_eq!(first.state, ThermalState::Throttled);
+        assert_eq!(first.previous, Some(ThermalState::Normal));
+        assert!(first.duty_cycle < 1.0);
+
+        // 温度持续高于目标时积分项继续降低占空比
+        let second = governor.update(78.0, TICK);
+        assert!(second.duty_cycle < first.duty_cycle);
+        assert_eq!(second.previous, None);
+
+        // 温度回落到目标以下时占空比回升
+        let cooler = governor.update(71.0, TICK);
+        assert!(cooler.duty_cycle > second.duty_cycle);
+        assert_eq!(cooler.state, ThermalState::Throttled);
+    }
+
+    #[test]
+    fn test_pause_at_critical_and_resume_with_hysteresis() {
+        let mut governor = governor();
+        let paused = governor.update(85.0, TICK);
+        assert_eq!((paused.state, paused.duty_cycle), (ThermalState::Paused, 0.0));
+
+        // 未降到 危险 - 滞回 之前保持暂停
+        assert_eq!(governor.update(82.0, TICK).state, ThermalState::Paused);
+
+        let resumed = governor.update(79.0, TICK);
+        assert_eq!(resumed.state, ThermalState::Throttled);
+        assert_eq!(resumed.previous, Some(ThermalState::Paused));
+        assert!(resumed.duty_cycle > 0.0 && resumed.duty_cycle < 1.0);
+
+        // 未降到 警告 - 滞回 之前保持降频
+        assert_eq!(governor.update(72.0, TICK).state, ThermalState::Throttled);
+        let normal = governor.update(70.0, TICK);
+        assert_eq!((normal.state, normal.duty_cycle), (ThermalState::Normal, 1.0));
+    }
+
+    #[test]
+    fn test_duty_cycle_never_below_minimum_while_throttled() {
+        let mut governor = governor();
+        for _ in 0..100 {
+            let decision = governor.update(84.0, TICK);
+            assert!(decision.duty_cycle >= ThermalGovernorConfig::default().min_duty_cycle);
+        }
+    }
+}
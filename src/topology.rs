@@ -0,0 +1,374 @@
+//! # CPU拓扑模块
+//!
+//! 本模块在Linux上读取 sysfs 获取真实的CPU拓扑，为CPU绑定策略提供依据，
+//! 取代"偶数索引即物理核心"、"前一半核心即性能核心"之类的假设。
+//!
+//! ## 🚀 数据来源
+//!
+//! | 路径 (相对 `/sys/devices/system/cpu/cpuN`) | 内容 |
+//! |------|------|
+//! | `topology/physical_package_id` | 物理封装 (插槽) |
+//! | `topology/die_id` | 封装内的die |
+//! | `topology/core_id` | 物理核心 |
+//! | `topology/thread_siblings_list` | 同一物理核心上的超线程 |
+//! | `cpu_capacity` | 调度器容量 (大小核系统) |
+//! | `cpufreq/cpuinfo_max_freq` | 最大频率 (kHz) |
+//! | `cache/index*/{level,type,size,shared_cpu_list}` | 缓存层级和共享关系 |
+//!
+//! ## 🎯 大小核识别
+//!
+//! 优先使用 `cpu_capacity`：容量低于最大值的CPU视为能效核心 (E核)。
+//! 没有容量信息时使用最大频率：低于最高频率85%的CPU视为能效核心，
+//! 避免把同一代核心之间的小幅睿频差异误判为大小核。
+//!
+//! ## 🔄 使用示例
+//!
+//! ```rust
+//! use cgminer_cpu_btc_core::topology::CpuTopology;
+//!
+//! if let Some(topology) = CpuTopology::detect() {
+//!     println!("物理核心: {:?}", topology.physical_cores());
+//!     println!("大小核: {}", topology.is_hybrid());
+//! }
+//! ```
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 默认的sysfs CPU目录
+pub const DEFAULT_SYSFS_CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+/// 低于最高频率该比例的CPU视为能效核心
+const EFFICIENCY_FREQ_RATIO: f64 = 0.85;
+
+/// 核心类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreKind {
+    /// 性能核心 (P核)
+    Performance,
+    /// 能效核心 (E核)
+    Efficiency,
+    /// 非大小核系统中的普通核心
+    Uniform,
+}
+
+/// 缓存信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheInfo {
+    /// 缓存级别 (1/2/3)
+    pub level: u8,
+    /// 缓存类型 (Data / Instruction / Unified)
+    pub cache_type: String,
+    /// 缓存大小 (KB)
+    pub size_kb: u32,
+    /// 共享该缓存的逻辑CPU
+    pub shared_cpus: Vec<usize>,
+}
+
+/// 逻辑CPU的拓扑信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogicalCpu {
+    /// 逻辑CPU编号
+    pub id: usize,
+    /// 物理封装ID
+    pub package_id: u32,
+    /// die ID
+    pub die_id: u32,
+    /// 物理核心ID（封装内唯一）
+    pub core_id: u32,
+    /// 同一物理核心上的逻辑CPU（包含自身）
+    pub thread_siblings: Vec<usize>,
+    /// 调度器容量
+    pub capacity: Option<u32>,
+    /// 最大频率 (kHz)
+    pub max_freq_khz: Option<u64>,
+    /// 核心类型
+    pub kind: CoreKind,
+    /// 缓存
+    pub caches: Vec<CacheInfo>,
+}
+
+impl LogicalCpu {
+    /// 是否是所在物理核心的第一个逻辑CPU
+    pub fn is_primary_thread(&self) -> bool {
+        self.thread_siblings.first().map_or(true, |&first| first == self.id)
+    }
+}
+
+/// CPU拓扑
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CpuTopology {
+    cpus: Vec<LogicalCpu>,
+}
+
+impl CpuTopology {
+    /// 检测当前系统的CPU拓扑（仅Linux）
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            Self::from_sysfs(DEFAULT_SYSFS_CPU_ROOT).ok()
+        } else {
+            None
+        }
+    }
+
+    /// 从指定的sysfs CPU目录读取拓扑（测试时可指向fixture目录）
+    pub fn from_sysfs(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref();
+        let mut cpus = Vec::new();
+
+        for entry in fs::read_dir(root)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let id = match name.to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .and_then(|id| id.parse::<usize>().ok())
+            {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let cpu_dir = entry.path();
+            // 跳过离线CPU和没有拓扑信息的目录
+            if read_trimmed(&cpu_dir.join("online")).as_deref() == Some("0") {
+                continue;
+            }
+            let topology_dir = cpu_dir.join("topology");
+            if !topology_dir.is_dir() {
+                continue;
+            }
+
+            let thread_siblings = read_trimmed(&topology_dir.join("thread_siblings_list"))
+                .or_else(|| read_trimmed(&topology_dir.join("core_cpus_list")))
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_else(|| vec![id]);
+
+            cpus.push(LogicalCpu {
+                id,
+                package_id: read_number(&topology_dir.join("physical_package_id")).unwrap_or(0),
+                die_id: read_number(&topology_dir.join("die_id")).unwrap_or(0),
+                core_id: read_number(&topology_dir.join("core_id")).unwrap_or(id as u32),
+                thread_siblings,
+                capacity: read_number(&cpu_dir.join("cpu_capacity")),
+                max_freq_khz: read_number(&cpu_dir.join("cpufreq").join("cpuinfo_max_freq")),
+                kind: CoreKind::Uniform,
+                caches: read_caches(&cpu_dir.join("cache")),
+            });
+        }
+
+        if cpus.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} 下没有可用的CPU拓扑信息", root.display()),
+            ));
+        }
+
+        cpus.sort_by_key(|cpu| cpu.id);
+        classify_core_kinds(&mut cpus);
+        Ok(Self { cpus })
+    }
+
+    /// 所有在线逻辑CPU（按编号排序）
+    pub fn cpus(&self) -> &[LogicalCpu] {
+        &self.cpus
+    }
+
+    /// 查询逻辑CPU
+    pub fn cpu(&self, id: usize) -> Option<&LogicalCpu> {
+        self.cpus.iter().find(|cpu| cpu.id == id)
+    }
+
+    /// 逻辑CPU数量
+    pub fn logical_cpu_count(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// 物理核心数量
+    pub fn physical_core_count(&self) -> usize {
+        self.physical_cores().len()
+    }
+
+    /// 物理封装数量
+    pub fn package_count(&self) -> usize {
+        self.cpus.iter().map(|cpu| cpu.package_id).collect::<BTreeSet<_>>().len()
+    }
+
+    /// 是否是大小核系统
+    pub fn is_hybrid(&self) -> bool {
+        self.cpus.iter().any(|cpu| cpu.kind == CoreKind::Efficiency)
+    }
+
+    /// 每个物理核心选取一个逻辑CPU（同一核心上编号最小的超线程）
+    pub fn physical_cores(&self) -> Vec<usize> {
+        self.cpus.iter()
+            .filter(|cpu| cpu.is_primary_thread())
+            .map(|cpu| cpu.id)
+            .collect()
+    }
+
+    /// 性能核心上的逻辑CPU；非大小核系统返回全部CPU
+    pub fn performance_cpus(&self) -> Vec<usize> {
+        self.cpus.iter()
+            .filter(|cpu| cpu.kind != CoreKind::Efficiency)
+            .map(|cpu| cpu.id)
+            .collect()
+    }
+
+    /// 按挖矿偏好排序的逻辑CPU：
+    /// 性能核心的主线程 → 能效核心的主线程 → 其余超线程
+    pub fn preferred_order(&self) -> Vec<usize> {
+        let rank = |cpu: &LogicalCpu| match (cpu.is_primary_thread(), cpu.kind) {
+            (true, CoreKind::Efficiency) => 1,
+            (true, _) => 0,
+            (false, _) => 2,
+        };
+
+        let mut cpus: Vec<&LogicalCpu> = self.cpus.iter().collect();
+        cpus.sort_by_key(|cpu| (rank(cpu), cpu.id));
+        cpus.into_iter().map(|cpu| cpu.id).collect()
+    }
+
+    /// 指定级别缓存的共享域（每组为共享同一缓存的逻辑CPU）
+    pub fn cache_domains(&self, level: u8) -> Vec<Vec<usize>> {
+        let mut domains = BTreeSet::new();
+        for cpu in &self.cpus {
+            for cache in cpu.caches.iter().filter(|cache| cache.level == level && cache.cache_type != "Instruction") {
+                domains.insert(cache.shared_cpus.clone());
+            }
+        }
+        domains.into_iter().collect()
+    }
+}
+
+/// 根据容量或最大频率标记大小核
+fn classify_core_kinds(cpus: &mut [LogicalCpu]) {
+    let max_capacity = cpus.iter().filter_map(|cpu| cpu.capacity).max();
+    let has_distinct_capacity = max_capacity.is_some()
+        && cpus.iter().filter_map(|cpu| cpu.capacity).any(|capacity| Some(capacity) != max_capacity);
+
+    if has_distinct_capacity {
+        let max_capacity = max_capacity.unwrap_or_default();
+        for cpu in cpus.iter_mut() {
+            cpu.kind = match cpu.capacity {
+                Some(capacity) if capacity < max_capacity => CoreKind::Efficiency,
+                _ => CoreKind::Performance,
+            };
+        }
+        return;
+    }
+
+    let max_freq = cpus.iter().filter_map(|cpu| cpu.max_freq_khz).max().unwrap_or(0);
+    let threshold = (max_freq as f64 * EFFICIENCY_FREQ_RATIO) as u64;
+    let is_hybrid = max_freq > 0
+        && cpus.iter().filter_map(|cpu| cpu.max_freq_khz).any(|freq| freq < threshold);
+
+    if is_hybrid {
+        for cpu in cpus.iter_mut() {
+            cpu.kind = match cpu.max_freq_khz {
+                Some(freq) if freq < threshold => CoreKind::Efficiency,
+                _ => CoreKind::Performance,
+            };
+        }
+    }
+}
+
+/// 读取 `cache/index*` 目录
+pub(crate) fn read_caches(cache_dir: &Path) -> Vec<CacheInfo> {
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut caches = BTreeMap::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let index = match name.to_str()
+            .and_then(|name| name.strip_prefix("index"))
+            .and_then(|index| index.parse::<u32>().ok())
+        {
+            Some(index) => index,
+            None => continue,
+        };
+
+        let dir = entry.path();
+        let level = match read_number::<u8>(&dir.join("level")) {
+            Some(level) => level,
+            None => continue,
+        };
+
+        caches.insert(index, CacheInfo {
+            level,
+            cache_type: read_trimmed(&dir.join("type")).unwrap_or_else(|| "Unified".to_string()),
+            size_kb: read_trimmed(&dir.join("size")).and_then(|size| parse_cache_size_kb(&size)).unwrap_or(0),
+            shared_cpus: read_trimmed(&dir.join("shared_cpu_list"))
+                .map(|list| parse_cpu_list(&list))
+                .unwrap_or_default(),
+        });
+    }
+
+    caches.into_values().collect()
+}
+
+/// 解析CPU列表格式，例如 `0-3,8,10-11`
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = BTreeSet::new();
+    for part in list.trim().split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => {
+                if let Ok(cpu) = part.parse::<usize>() {
+                    cpus.insert(cpu);
+                }
+            }
+        }
+    }
+    cpus.into_iter().collect()
+}
+
+/// 解析缓存大小，例如 `48K`、`2M`
+pub fn parse_cache_size_kb(size: &str) -> Option<u32> {
+    let size = size.trim();
+    if let Some(kb) = size.strip_suffix('K') {
+        kb.parse().ok()
+    } else if let Some(mb) = size.strip_suffix('M') {
+        mb.parse::<u32>().ok().map(|mb| mb * 1024)
+    } else {
+        size.parse::<u32>().ok().map(|bytes| bytes / 1024)
+    }
+}
+
+/// 读取文件并去除首尾空白
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|content| content.trim().to_string())
+}
+
+/// 读取数值文件
+fn read_number<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    read_trimmed(path)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_cache_size() {
+        assert_eq!(parse_cache_size_kb("48K"), Some(48));
+        assert_eq!(parse_cache_size_kb("30M"), Some(30 * 1024));
+        assert_eq!(parse_cache_size_kb("bogus"), None);
+    }
+}
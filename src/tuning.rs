@@ -0,0 +1,343 @@
+//! # 批次大小自动调优模块
+//!
+//! 连续计算循环每完成一个批次才检查一次停止信号和新工作，批次越大让出CPU的开销越小，
+//! 但响应停止和新工作的延迟也越长。本模块的 [`BatchTuner`] 在挖矿过程中测量真实算力
+//! 和响应延迟，找到延迟不超过 `max_latency_ms` 时算力最高的批次大小，
+//! 调优结果按CPU型号、微码版本和挖矿线程数保存到缓存文件，下一次在相同条件下启动时直接使用。
+//!
+//! ## 🚀 调优过程
+//!
+//! 1. 每 `samples_per_step` 个批次为一步，统计算力（只计算哈希时间）和最大延迟
+//! 2. 延迟超过上限时按比例缩小批次，重新开始调优
+//! 3. 延迟满足要求且算力提升超过 `min_gain` 时扩大批次（最多翻倍，不超过延迟上限的预测值）
+//! 4. 算力不再提升或批次无法继续扩大时收敛到最好的批次
+//! 5. 收敛后继续监控，延迟超限（如系统变慢）时重新调优
+//!
+//! ## 📊 延迟的来源
+//!
+//! | 延迟 | 测量方式 |
+//! |------|----------|
+//! | 停止响应 | 单个批次的哈希时间（停止信号只在批次之间生效） |
+//! | 新工作响应 | 从 `submit_work` 到挖矿循环切换到新工作的时间 |
+//!
+//! 上一批次后按占空比或算力上限休眠时，休眠时间与批次大小无关，这时的新工作响应不计入延迟。
+//!
+//! ## 🔄 配置示例
+//!
+//! ```toml
+//! [tuning]
+//! enabled = true
+//! max_latency_ms = 50
+//! cache_path = "/var/lib/cgminer/batch-tuning.json"
+//! ```
+
+use crate::config;
+use crate::hashing::CpuIdentity;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 未启用调优时连续计算循环的批次大小
+pub const DEFAULT_BATCH_SIZE: u32 = 100_000;
+
+/// 缩小批次时在延迟上限之下保留的余量
+const LATENCY_MARGIN: f64 = 0.8;
+
+/// 批次调优配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchTuningConfig {
+    /// 是否启用调优
+    pub enabled: bool,
+    /// 响应停止和新工作的最大延迟 (毫秒)
+    pub max_latency_ms: u64,
+    /// 最小批次大小
+    pub min_batch_size: u32,
+    /// 最大批次大小
+    pub max_batch_size: u32,
+    /// 没有缓存时的初始批次大小
+    pub initial_batch_size: u32,
+    /// 每一步统计的批次数
+    pub samples_per_step: u32,
+    /// 扩大批次所需的最小算力提升 (0.02表示2%)
+    pub min_gain: f64,
+    /// 调优结果缓存文件，None表示不保存
+    pub cache_path: Option<PathBuf>,
+}
+
+impl Default for BatchTuningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_latency_ms: 50,
+            min_batch_size: 1_000,
+            max_batch_size: 10_000_000,
+            initial_batch_size: DEFAULT_BATCH_SIZE,
+            samples_per_step: 8,
+            min_gain: 0.02,
+            cache_path: None,
+        }
+    }
+}
+
+impl BatchTuningConfig {
+    /// 最大延迟
+    pub fn max_latency(&self) -> Duration {
+        Duration::from_millis(self.max_latency_ms)
+    }
+
+    fn clamp(&self, batch_size: u64) -> u32 {
+        batch_size.clamp(self.min_batch_size as u64, self.max_batch_size as u64) as u32
+    }
+}
+
+/// 一个批次大小的测量结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TuningResult {
+    /// 批次大小
+    pub batch_size: u32,
+    /// 测得的算力 (H/s，只计算哈希时间)
+    pub hashrate: f64,
+    /// 测得的最大响应延迟 (毫秒)
+    pub latency_ms: f64,
+    /// 是否已经收敛
+    pub converged: bool,
+}
+
+/// 调优结果缓存（保存到 `cache_path`）
+///
+/// 批次的哈希时间取决于CPU和同时挖矿的线程数，缓存只在两者都相同时使用。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TuningCache {
+    /// 调优时的CPU
+    pub cpu: CpuIdentity,
+    /// 调优时的挖矿线程数（设备数）
+    pub threads: u32,
+    /// 调优时使用的最大延迟 (毫秒)
+    pub max_latency_ms: u64,
+    /// 收敛的批次大小
+    pub batch_size: u32,
+    /// 收敛时的算力 (H/s)
+    pub hashrate: f64,
+    /// 收敛时的最大响应延迟 (毫秒)
+    pub latency_ms: f64,
+}
+
+impl TuningCache {
+    /// 从各设备的调优结果生成缓存：取已收敛设备批次大小的中位数
+    pub fn from_results(
+        config: &BatchTuningConfig,
+        cpu: CpuIdentity,
+        threads: u32,
+        results: &[TuningResult],
+    ) -> Option<Self> {
+        let mut converged: Vec<&TuningResult> = results.iter().filter(|result| result.converged).collect();
+        if converged.is_empty() {
+            return None;
+        }
+        converged.sort_by_key(|result| result.batch_size);
+        let median = converged[converged.len() / 2];
+        Some(Self {
+            cpu,
+            threads,
+            max_latency_ms: config.max_latency_ms,
+            batch_size: median.batch_size,
+            hashrate: median.hashrate,
+            latency_ms: median.latency_ms,
+        })
+    }
+
+    /// 读取缓存文件，文件不存在或无法解析时返回 `None`
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 缓存是否在同一CPU、同样的线程数下调优
+    pub fn matches(&self, cpu: &CpuIdentity, threads: u32) -> bool {
+        self.cpu == *cpu && self.threads == threads
+    }
+
+    /// 写入缓存文件（原子替换）
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        config::write_file_atomic(path, &content)
+    }
+}
+
+/// 调优阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Exploring,
+    Converged,
+}
+
+/// 批次大小调优器
+///
+/// 由挖矿循环在每个批次后调用 [`BatchTuner::record_batch`]，切换到新工作时调用
+/// [`BatchTuner::record_response`]；测量和调整都不依赖真实时间，便于测试。
+#[derive(Debug, Clone)]
+pub struct BatchTuner {
+    config: BatchTuningConfig,
+    batch_size: u32,
+    phase: Phase,
+    /// 延迟满足要求的最好结果
+    best: Option<TuningResult>,
+    /// 最近一步的测量结果
+    last: Option<TuningResult>,
+    step_batches: u32,
+    step_hashes: u64,
+    step_time: Duration,
+    step_latency: Duration,
+}
+
+impl BatchTuner {
+    /// 从初始批次大小开始调优
+    pub fn new(config: BatchTuningConfig) -> Self {
+        let batch_size = config.clamp(config.initial_batch_size as u64);
+        Self {
+            config,
+            batch_size,
+            phase: Phase::Exploring,
+            best: None,
+            last: None,
+            step_batches: 0,
+            step_hashes: 0,
+            step_time: Duration::ZERO,
+            step_latency: Duration::ZERO,
+        }
+    }
+
+    /// 从缓存的结果开始：延迟上限相同时直接收敛，否则以缓存的批次为起点重新调优
+    pub fn from_cache(config: BatchTuningConfig, cache: &TuningCache) -> Self {
+        let mut tuner = Self::new(config);
+        tuner.batch_size = tuner.config.clamp(cache.batch_size as u64);
+        if cache.max_latency_ms == tuner.config.max_latency_ms && tuner.batch_size == cache.batch_size {
+            tuner.phase = Phase::Converged;
+            tuner.best = Some(TuningResult {
+                batch_size: cache.batch_size,
+                hashrate: cache.hashrate,
+                latency_ms: cache.latency_ms,
+                converged: true,
+            });
+        }
+        tuner
+    }
+
+    /// 调优配置
+    pub fn config(&self) -> &BatchTuningConfig {
+        &self.config
+    }
+
+    /// 当前批次大小
+    pub fn batch_size(&self) -> u32 {
+        self.batch_size
+    }
+
+    /// 是否已经收敛
+    pub fn is_converged(&self) -> bool {
+        self.phase == Phase::Converged
+    }
+
+    /// 当前结果：收敛后为收敛的批次，否则为最近一步的测量
+    pub fn result(&self) -> Option<TuningResult> {
+        match self.phase {
+            Phase::Converged => self.best,
+            Phase::Exploring => self.last,
+        }
+    }
+
+    /// 记录新工作的响应延迟，计入当前一步
+    pub fn record_response(&mut self, latency: Duration) {
+        self.step_latency = self.step_latency.max(latency);
+    }
+
+    /// 记录一个批次（哈希数和哈希时间）
+    ///
+    /// 一步结束后批次大小发生变化或刚刚收敛时返回当前结果，否则返回 `None`。
+    pub fn record_batch(&mut self, hashes: u64, elapsed: Duration) -> Option<TuningResult> {
+        self.step_batches += 1;
+        self.step_hashes += hashes;
+        self.step_time += elapsed;
+        self.step_latency = self.step_latency.max(elapsed);
+        if self.step_batches < self.config.samples_per_step.max(1) {
+            return None;
+        }
+
+        let measured = TuningResult {
+            batch_size: self.batch_size,
+            hashrate: self.step_hashes as f64 / self.step_time.as_secs_f64().max(1e-9),
+            latency_ms: self.step_latency.as_secs_f64() * 1000.0,
+            converged: false,
+        };
+        self.step_batches = 0;
+        self.step_hashes = 0;
+        self.step_time = Duration::ZERO;
+        self.step_latency = Duration::ZERO;
+        self.last = Some(measured);
+
+        let (previous_batch_size, previous_phase) = (self.batch_size, self.phase);
+        self.finish_step(measured);
+        let converged_now = self.phase == Phase::Converged && previous_phase != Phase::Converged;
+        if self.batch_size != previous_batch_size || converged_now {
+            self.result()
+        } else {
+            None
+        }
+    }
+
+    /// 延迟上限对应的批次大小（按测得的延迟线性预测，保留余量）
+    fn latency_limit(&self, measured: &TuningResult) -> u32 {
+        if measured.latency_ms <= 0.0 {
+            return self.config.max_batch_size;
+        }
+        let scale = self.config.max_latency_ms as f64 / measured.latency_ms * LATENCY_MARGIN;
+        self.config.clamp((measured.batch_size as f64 * scale) as u64)
+    }
+
+    fn finish_step(&mut self, measured: TuningResult) {
+        if measured.latency_ms > self.config.max_latency_ms as f64 {
+            // 延迟超限：缩小批次并重新调优
+            let next = self.latency_limit(&measured).min(measured.batch_size);
+            if next == measured.batch_size {
+                // 已经是最小批次，无法继续缩小
+                self.converge(measured);
+            } else {
+                self.phase = Phase::Exploring;
+                self.best = None;
+                self.batch_size = next;
+            }
+            return;
+        }
+
+        if self.phase == Phase::Converged {
+            if self.best.map_or(true, |best| best.batch_size == measured.batch_size) {
+                self.best = Some(TuningResult { converged: true, ..measured });
+            }
+            return;
+        }
+
+        match self.best {
+            Some(best) if measured.hashrate <= best.hashrate * (1.0 + self.config.min_gain) => {
+                // 算力没有明显提升：回到较小的批次，延迟更低
+                self.converge(best);
+            }
+            _ => {
+                self.best = Some(measured);
+                let next = measured.batch_size.saturating_mul(2).min(self.latency_limit(&measured));
+                if next <= measured.batch_size {
+                    self.converge(measured);
+                } else {
+                    self.batch_size = next;
+                }
+            }
+        }
+    }
+
+    fn converge(&mut self, result: TuningResult) {
+        self.phase = Phase::Converged;
+        self.batch_size = result.batch_size;
+        self.best = Some(TuningResult { converged: true, ..result });
+    }
+}
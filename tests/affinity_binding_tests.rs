@@ -0,0 +1,82 @@
+//! CPU绑定配置与工作线程绑定测试
+//!
+//! 验证配置中的绑定策略（包括手动映射）传递到绑定管理器，
+//! 绑定发生在设备的挖矿工作线程上，并按设备报告绑定结果
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::config::{AffinityStrategyKind, CpuCoreConfig, ManualCoreBinding};
+use cgminer_cpu_btc_core::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy, DeviceBindingStatus};
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 启动核心并等待设备1000上报绑定结果
+async fn start_and_wait_for_binding(cpu_config: CpuCoreConfig) -> (SoftwareMiningCore, DeviceBindingStatus) {
+    let mut core = SoftwareMiningCore::new("绑定测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("affinity-test")).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    core.submit_work(Arc::new(Work::new("affinity_job".to_string(), [0u8; 32], [0u8; 80], 1.0)))
+        .await
+        .expect("提交工作应该成功");
+
+    for _ in 0..50 {
+        if let Some(status) = core.cpu_affinity_stats()
+            .and_then(|stats| stats.device_bindings.into_iter().find(|status| status.device_id == 1000))
+        {
+            return (core, status);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("设备1000应该上报绑定结果");
+}
+
+#[tokio::test]
+async fn test_binding_happens_on_mining_worker_thread() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.affinity.strategy = AffinityStrategyKind::RoundRobin;
+
+    let (mut core, status) = start_and_wait_for_binding(cpu_config).await;
+
+    assert_eq!(status.thread_name.as_deref(), Some("cpu-miner-1000"), "绑定应该发生在挖矿工作线程上");
+    assert_eq!(status.success, status.error.is_none());
+
+    let stats = core.cpu_affinity_stats().unwrap();
+    assert!(matches!(stats.strategy, CpuAffinityStrategy::RoundRobin));
+    assert_eq!(stats.successful_bindings + stats.failed_bindings, stats.device_bindings.len());
+
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_manual_mapping_from_config() {
+    // 从检测到的可用核心中选择（已应用进程CPU掩码），而不是假设主机上存在某个CPU编号
+    let detected = CpuAffinityManager::new(true, CpuAffinityStrategy::RoundRobin);
+    let last_core = match detected.available_core_ids().last().copied() {
+        Some(cpu) => cpu,
+        None => return, // 无法获取CPU核心信息的环境中跳过
+    };
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.affinity.strategy = AffinityStrategyKind::Manual;
+    cpu_config.affinity.manual_mapping = vec![ManualCoreBinding { device_id: 1000, core: last_core }];
+
+    let (mut core, status) = start_and_wait_for_binding(cpu_config).await;
+
+    assert_eq!(status.core_id, Some(last_core));
+    assert!(matches!(core.cpu_affinity_stats().unwrap().strategy, CpuAffinityStrategy::Manual(_)));
+
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_affinity_disabled_by_config() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.affinity.enabled = false;
+
+    let mut core = SoftwareMiningCore::new("绑定禁用测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("affinity-disabled")).await.unwrap();
+    assert!(core.cpu_affinity_stats().is_none());
+}
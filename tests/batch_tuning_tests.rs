@@ -0,0 +1,223 @@
+//! 批次大小调优测试
+//!
+//! 用算力模型（每批次有固定开销，批次越大算力越接近峰值）驱动 `BatchTuner`，
+//! 验证扩大、收敛、延迟超限时缩小、缓存读写和匹配，以及核心启用调优后的设备结果和缓存文件
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::hashing::CpuIdentity;
+use cgminer_cpu_btc_core::tuning::{BatchTuner, BatchTuningConfig, TuningCache, TuningResult};
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 峰值算力 10 MH/s，每批次固定开销 2000 次哈希的时间
+const PEAK_HASHRATE: f64 = 10_000_000.0;
+const BATCH_OVERHEAD: f64 = 2_000.0;
+
+fn config() -> BatchTuningConfig {
+    BatchTuningConfig {
+        enabled: true,
+        max_latency_ms: 50,
+        min_batch_size: 1_000,
+        max_batch_size: 10_000_000,
+        initial_batch_size: 10_000,
+        samples_per_step: 4,
+        min_gain: 0.02,
+        cache_path: None,
+    }
+}
+
+fn cpu() -> CpuIdentity {
+    CpuIdentity { model: "cpu-a".to_string(), microcode: Some("0x1".to_string()) }
+}
+
+/// 按算力模型计算一个批次的耗时
+fn batch_time(batch_size: u32) -> Duration {
+    Duration::from_secs_f64((batch_size as f64 + BATCH_OVERHEAD) / PEAK_HASHRATE)
+}
+
+/// 运行调优直到收敛，返回经过的批次大小
+fn run_until_converged(tuner: &mut BatchTuner) -> Vec<u32> {
+    let mut sizes = vec![tuner.batch_size()];
+    for _ in 0..1000 {
+        if tuner.is_converged() {
+            break;
+        }
+        let batch_size = tuner.batch_size();
+        if tuner.record_batch(batch_size as u64, batch_time(batch_size)).is_some() && !tuner.is_converged() {
+            sizes.push(tuner.batch_size());
+        }
+    }
+    assert!(tuner.is_converged(), "调优应该收敛: {:?}", sizes);
+    sizes
+}
+
+#[test]
+fn test_grows_until_gain_stops() {
+    let mut tuner = BatchTuner::new(config());
+    let sizes = run_until_converged(&mut tuner);
+
+    // 10k → 20k → 40k → 80k → 160k (160k相对80k只提升约1.2%，回到80k)
+    assert_eq!(sizes, vec![10_000, 20_000, 40_000, 80_000, 160_000]);
+    let result = tuner.result().unwrap();
+    assert!(result.converged);
+    assert_eq!(result.batch_size, 80_000);
+    assert_eq!(tuner.batch_size(), 80_000);
+    assert!(result.latency_ms <= 50.0, "{:?}", result);
+    assert!(result.hashrate > PEAK_HASHRATE * 0.97, "{:?}", result);
+}
+
+#[test]
+fn test_latency_bound_limits_growth() {
+    // 延迟上限5ms：批次不能超过约5万
+    let mut tuner = BatchTuner::new(BatchTuningConfig { max_latency_ms: 5, min_gain: 0.0, ..config() });
+    run_until_converged(&mut tuner);
+    let result = tuner.result().unwrap();
+    assert!(result.latency_ms <= 5.0, "{:?}", result);
+    assert!(result.batch_size >= 20_000 && result.batch_size < 50_000, "{:?}", result);
+}
+
+#[test]
+fn test_shrinks_when_latency_exceeded() {
+    let mut tuner = BatchTuner::new(BatchTuningConfig { initial_batch_size: 2_000_000, ..config() });
+    let first_step = (0..4).filter_map(|_| tuner.record_batch(2_000_000, batch_time(2_000_000))).last();
+
+    // 200ms的批次远超50ms上限，按比例缩小到延迟上限的80%
+    let measured = first_step.expect("一步结束后批次应该变化");
+    assert!(!measured.converged);
+    assert_eq!(measured.batch_size, 2_000_000);
+    assert!(tuner.batch_size() < 500_000, "{}", tuner.batch_size());
+
+    run_until_converged(&mut tuner);
+    assert!(tuner.result().unwrap().latency_ms <= 50.0);
+}
+
+#[test]
+fn test_response_latency_counts_towards_bound() {
+    let mut tuner = BatchTuner::new(config());
+    run_until_converged(&mut tuner);
+    let converged = tuner.batch_size();
+
+    // 新工作的响应延迟超限（如系统变慢）：重新调优并缩小批次
+    tuner.record_response(Duration::from_millis(200));
+    let step = (0..4).filter_map(|_| tuner.record_batch(converged as u64, batch_time(converged))).last();
+    assert!(step.is_some());
+    assert!(!tuner.is_converged());
+    assert!(tuner.batch_size() < converged);
+}
+
+#[test]
+fn test_start_from_cache() {
+    let cache = TuningCache {
+        cpu: cpu(),
+        threads: 4,
+        max_latency_ms: 50,
+        batch_size: 80_000,
+        hashrate: 9.7e6,
+        latency_ms: 8.2,
+    };
+    let tuner = BatchTuner::from_cache(config(), &cache);
+    assert!(tuner.is_converged(), "延迟上限相同时直接使用缓存");
+    assert_eq!(tuner.batch_size(), 80_000);
+    assert_eq!(tuner.result().map(|result| result.hashrate), Some(9.7e6));
+
+    let tuner = BatchTuner::from_cache(BatchTuningConfig { max_latency_ms: 20, ..config() }, &cache);
+    assert!(!tuner.is_converged(), "延迟上限变化后重新调优");
+    assert_eq!(tuner.batch_size(), 80_000);
+    assert_eq!(tuner.result(), None);
+}
+
+#[test]
+fn test_cache_from_results_and_roundtrip() {
+    let result = |batch_size, converged| TuningResult { batch_size, hashrate: 1e7, latency_ms: 10.0, converged };
+    let results = [result(40_000, true), result(160_000, false), result(80_000, true), result(60_000, true)];
+    let cache = TuningCache::from_results(&config(), cpu(), 4, &results).unwrap();
+    assert_eq!(cache.batch_size, 60_000, "取已收敛设备的中位数");
+    assert_eq!(TuningCache::from_results(&config(), cpu(), 4, &[result(40_000, false)]), None);
+
+    // 只在同一CPU、同样的线程数下使用
+    assert!(cache.matches(&cpu(), 4));
+    assert!(!cache.matches(&cpu(), 8), "线程数变化后重新调优");
+    assert!(!cache.matches(&CpuIdentity { microcode: Some("0x2".to_string()), ..cpu() }, 4), "CPU变化后重新调优");
+
+    let path = temp_path("roundtrip");
+    cache.save(&path).unwrap();
+    assert_eq!(TuningCache::load(&path), Some(cache));
+    std::fs::write(&path, "not json").unwrap();
+    assert_eq!(TuningCache::load(&path), None);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_tuning_config_validation() {
+    let config = CpuCoreConfig::from_toml_str("[tuning]\nenabled = true\nmax_latency_ms = 20").unwrap();
+    assert!(config.tuning.enabled);
+    assert_eq!(config.tuning.max_latency_ms, 20);
+    assert_eq!(config.tuning.initial_batch_size, 100_000);
+
+    let err = CpuCoreConfig::from_toml_str("[tuning]\nmax_latency_ms = 0").unwrap_err();
+    assert!(err.to_string().contains("tuning.max_latency_ms"), "{}", err);
+    let err = CpuCoreConfig::from_toml_str("[tuning]\nmin_batch_size = 5000\nmax_batch_size = 1000").unwrap_err();
+    assert!(err.to_string().contains("tuning.min_batch_size"), "{}", err);
+}
+
+fn temp_path(test: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("batch-tuning-{}-{}", test, std::process::id()))
+        .join("tuning.json")
+}
+
+#[tokio::test]
+async fn test_core_tunes_and_persists_batch_size() {
+    let path = temp_path("core");
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 2;
+    cpu_config.power.enabled = false;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.tuning = BatchTuningConfig {
+        enabled: true,
+        max_latency_ms: 20,
+        initial_batch_size: 1_000,
+        samples_per_step: 2,
+        min_gain: 0.5,
+        cache_path: Some(path.clone()),
+        ..Default::default()
+    };
+
+    let mut core = SoftwareMiningCore::new("批次调优测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("tuning-test")).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    let work = Work::new("tuning".to_string(), [0u8; 32], [0u8; 80], 1.0);
+    core.submit_work(Arc::new(work)).await.unwrap();
+
+    let mut converged = false;
+    for _ in 0..200 {
+        let results = core.batch_tuning().await;
+        if results.len() == 2 && results.iter().all(|(_, result)| result.converged) {
+            converged = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(converged, "设备的批次调优应该收敛: {:?}", core.batch_tuning().await);
+    for (_, result) in core.batch_tuning().await {
+        assert!(result.latency_ms <= 20.0, "{:?}", result);
+    }
+
+    let threads = core.device_handles().await.len() as u32;
+    core.stop().await.expect("核心停止应该成功");
+    let cache = TuningCache::load(&path).expect("停止时应该保存调优结果");
+    assert_eq!(cache.max_latency_ms, 20);
+    assert!(cache.matches(&CpuIdentity::detect(), threads), "{:?}", cache);
+
+    // 下一次启动直接使用缓存的批次大小
+    let mut core = SoftwareMiningCore::new("批次调优测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("tuning-test")).await.unwrap();
+    for handle in core.device_handles().await {
+        assert_eq!(handle.continuous_batch_size().await.unwrap(), cache.batch_size);
+        assert_eq!(handle.batch_tuning().await.unwrap().map(|result| result.converged), Some(true));
+    }
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
@@ -0,0 +1,86 @@
+//! cgroup v2 CPU限额测试
+//!
+//! 使用 `tests/fixtures/cgroup` 下的假cgroup文件系统验证配额和cpuset的读取，
+//! 以及核心据此推导的设备数量、占空比和CPU能力
+
+use cgminer_core::MiningCore;
+use cgminer_cpu_btc_core::cgroup::{parse_cpu_max, CgroupLimits, CpuQuota};
+use cgminer_cpu_btc_core::config::{AffinityStrategyKind, CpuCoreConfig};
+use cgminer_cpu_btc_core::cpu_affinity::{usable_cpus, CoreReservation};
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::path::PathBuf;
+
+/// fixture 中的cgroup文件系统
+fn load(name: &str) -> std::io::Result<CgroupLimits> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cgroup").join(name);
+    CgroupLimits::from_fs(root.join("fs"), root.join("proc_cgroup"))
+}
+
+#[test]
+fn test_parse_cpu_max() {
+    assert_eq!(parse_cpu_max("150000 100000\n"), Some(CpuQuota { quota_us: 150000, period_us: 100000 }));
+    assert_eq!(parse_cpu_max("max 100000\n"), None);
+    assert_eq!(parse_cpu_max(""), None);
+}
+
+#[test]
+fn test_fractional_quota() {
+    let limits = load("quota").expect("fixture cgroup应该可以读取");
+    assert_eq!(limits.path, "/docker/abc");
+    assert_eq!(limits.cpu_budget(), Some(1.5));
+    assert_eq!(limits.effective_cpus, Some(vec![0, 1, 2, 3]));
+    assert_eq!(limits.cpu_limit(), Some(2));
+    assert_eq!(limits.duty_cycle(2), 0.75);
+    assert_eq!(limits.duty_cycle(1), 1.0);
+}
+
+#[test]
+fn test_quota_inherited_from_parent_and_cpuset() {
+    let limits = load("nested").expect("fixture cgroup应该可以读取");
+    // 叶子不限制，父级限制为2个CPU
+    assert_eq!(limits.cpu_budget(), Some(2.0));
+    assert_eq!(limits.effective_cpus, Some(vec![4, 5, 6, 7, 12]));
+    assert_eq!(limits.cpu_limit(), Some(2));
+    assert_eq!(limits.restrict(&[0, 4, 5, 12, 13]), vec![4, 5, 12]);
+}
+
+#[test]
+fn test_unlimited_cgroup() {
+    let limits = load("unlimited").expect("fixture cgroup应该可以读取");
+    assert!(!limits.is_limited());
+    assert_eq!(limits.duty_cycle(8), 1.0);
+}
+
+#[test]
+fn test_cgroup_v1_is_not_detected() {
+    assert!(load("v1").is_err());
+}
+
+#[tokio::test]
+async fn test_core_derives_devices_and_duty_cycle_from_quota() {
+    let limits = load("quota").unwrap();
+    let host_cpus = limits.restrict(&usable_cpus(&CoreReservation::default())).len() as u32;
+    if host_cpus < 2 {
+        return; // fixture的cpuset在本机上不足2个CPU时跳过
+    }
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 4;
+    cpu_config.affinity.strategy = AffinityStrategyKind::RoundRobin;
+
+    let mut core = SoftwareMiningCore::new("cgroup测试核心".to_string());
+    core.set_cgroup_limits(Some(limits));
+    core.initialize(cpu_config.to_core_config("cgroup-test")).await.expect("核心初始化应该成功");
+
+    let devices = core.scan_devices().await.unwrap();
+    assert_eq!(devices.len(), 2, "1.5个CPU的配额应该只创建2个设备");
+
+    for device in &devices {
+        let handle = core.device(device.id).await.unwrap();
+        assert_eq!(handle.duty_cycle().await.unwrap(), 0.75);
+    }
+
+    let cpu_capabilities = core.get_capabilities().cpu_capabilities.clone().unwrap();
+    assert_eq!(cpu_capabilities.logical_cores, 2);
+    assert!(cpu_capabilities.physical_cores <= 2);
+}
@@ -0,0 +1,84 @@
+//! CPU核心配置测试
+//!
+//! 验证强类型配置的默认值、TOML/JSON加载、错误信息，以及工厂和核心共用同一验证路径
+
+use cgminer_core::{CoreFactory, MiningCore};
+use cgminer_cpu_btc_core::config::{AffinityStrategyKind, CpuCoreConfig, MAX_DEVICE_COUNT};
+use cgminer_cpu_btc_core::{SoftwareCoreFactory, SoftwareMiningCore};
+
+#[test]
+fn test_default_config_is_valid() {
+    let cpu_config = CpuCoreConfig::default();
+    cpu_config.validate().expect("默认配置应该有效");
+
+    let factory = SoftwareCoreFactory::new();
+    let core = SoftwareMiningCore::new("配置测试核心".to_string());
+    factory.validate_config(&factory.default_config()).expect("工厂默认配置应该有效");
+    core.validate_config(&core.default_config()).expect("核心默认配置应该有效");
+}
+
+#[test]
+fn test_factory_and_core_share_device_limit() {
+    let factory = SoftwareCoreFactory::new();
+    let core = SoftwareMiningCore::new("配置测试核心".to_string());
+
+    for (count, valid) in [(500u32, true), (MAX_DEVICE_COUNT, true), (MAX_DEVICE_COUNT + 1, false)] {
+        let mut cpu_config = CpuCoreConfig::default();
+        cpu_config.device_count = count;
+        let config = cpu_config.to_core_config("limit-test");
+
+        assert_eq!(factory.validate_config(&config).is_ok(), valid, "工厂对 {} 个设备的验证结果不符", count);
+        assert_eq!(core.validate_config(&config).is_ok(), valid, "核心对 {} 个设备的验证结果不符", count);
+    }
+}
+
+#[test]
+fn test_load_toml_file() {
+    let path = std::env::temp_dir().join(format!("cpu-core-config-{}.toml", std::process::id()));
+    std::fs::write(&path, r#"
+        device_count = 2
+        batch_size = 5000
+
+        [affinity]
+        strategy = "round_robin"
+
+        [temperature]
+        warning_threshold = 65.0
+        critical_threshold = 80.0
+
+        [throttling]
+        max_device_hashrate = 1000000.0
+    "#).unwrap();
+
+    let cpu_config = CpuCoreConfig::from_file(&path).expect("TOML配置应该加载成功");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(cpu_config.device_count, 2);
+    assert_eq!(cpu_config.batch_size, 5000);
+    assert_eq!(cpu_config.affinity.strategy, AffinityStrategyKind::RoundRobin);
+    assert_eq!(cpu_config.temperature.warning_threshold, 65.0);
+    assert_eq!(cpu_config.throttling.apply(5_000_000.0), 1_000_000.0);
+    // 未出现的参数使用默认值
+    assert_eq!(cpu_config.error_rate, CpuCoreConfig::default().error_rate);
+}
+
+#[test]
+fn test_invalid_values_are_rejected() {
+    let err = CpuCoreConfig::from_json_str(r#"{ "min_hashrate": 10.0, "max_hashrate": 5.0 }"#).unwrap_err();
+    assert!(err.to_string().contains("最小算力"), "{}", err);
+
+    let err = CpuCoreConfig::from_toml_str("error_rate = 1.5").unwrap_err();
+    assert!(err.to_string().contains("错误率"), "{}", err);
+
+    let err = CpuCoreConfig::from_toml_str("[temperature]\nwarning_threshold = 90.0\ncritical_threshold = 80.0").unwrap_err();
+    assert!(err.to_string().contains("温度"), "{}", err);
+
+    let err = CpuCoreConfig::from_toml_str("[temperature]\ncommand = []").unwrap_err();
+    assert!(err.to_string().contains("temperature.command"), "{}", err);
+
+    let err = CpuCoreConfig::from_toml_str("[affinity]\nstrategy = \"sideways\"").unwrap_err();
+    assert!(err.to_string().contains("TOML"), "{}", err);
+
+    let err = CpuCoreConfig::from_file("/nonexistent/cpu-core.yaml").unwrap_err();
+    assert!(err.to_string().contains("cpu-core.yaml"), "{}", err);
+}
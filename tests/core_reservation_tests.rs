@@ -0,0 +1,143 @@
+//! 预留核心测试
+//!
+//! 验证排除列表和留空数量在核心分配和设备数量限制中都被遵守
+
+use cgminer_core::MiningCore;
+use cgminer_cpu_btc_core::config::{AffinityStrategyKind, CpuCoreConfig};
+use cgminer_cpu_btc_core::cpu_affinity::{
+    parse_cpus_allowed, usable_cpus, CoreReservation, CpuAffinityManager, CpuAffinityStrategy,
+};
+use cgminer_cpu_btc_core::topology::CpuTopology;
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::path::PathBuf;
+
+fn reservation(excluded_cores: Vec<usize>, headroom: usize) -> CoreReservation {
+    CoreReservation { excluded_cores, headroom }
+}
+
+/// fixture 中的 sysfs CPU 拓扑，CPU编号不依赖运行测试的机器
+fn fixture_topology(name: &str) -> CpuTopology {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sysfs")
+        .join(name)
+        .join("devices/system/cpu");
+    CpuTopology::from_sysfs(root).expect("fixture 拓扑应该可以读取")
+}
+
+fn fixture_cpus(topology: &CpuTopology) -> Vec<usize> {
+    topology.cpus().iter().map(|cpu| cpu.id).collect()
+}
+
+#[test]
+fn test_reservation_excludes_and_leaves_headroom() {
+    let cpus: Vec<usize> = (0..8).collect();
+
+    assert_eq!(reservation(vec![0, 1], 0).apply(&cpus), vec![2, 3, 4, 5, 6, 7]);
+    assert_eq!(reservation(vec![], 2).apply(&cpus), vec![2, 3, 4, 5, 6, 7]);
+    // 留空在排除之外额外计算
+    assert_eq!(reservation(vec![0, 1], 2).apply(&cpus), vec![4, 5, 6, 7]);
+    assert!(reservation(vec![], 8).apply(&cpus).is_empty());
+}
+
+#[test]
+fn test_parse_cpus_allowed_list() {
+    let status = "Name:\tminer\nCpus_allowed:\tf0\nCpus_allowed_list:\t4-7\nMems_allowed_list:\t0\n";
+    assert_eq!(parse_cpus_allowed(status), Some(vec![4, 5, 6, 7]));
+    assert_eq!(parse_cpus_allowed("Name:\tminer\n"), None);
+}
+
+#[test]
+fn test_assign_cpu_core_skips_reserved_cores() {
+    let strategies = [
+        CpuAffinityStrategy::RoundRobin,
+        CpuAffinityStrategy::PerformanceFirst,
+        CpuAffinityStrategy::PhysicalCoresOnly,
+        CpuAffinityStrategy::Intelligent,
+        CpuAffinityStrategy::LoadBalanced,
+    ];
+
+    for strategy in strategies {
+        let mut manager = CpuAffinityManager::with_core_ids(true, strategy.clone(), (0..8).collect())
+            .with_reservation(&reservation(vec![0, 1], 1));
+        assert_eq!(manager.available_core_ids(), vec![3, 4, 5, 6, 7]);
+
+        for device_id in 1000..1010 {
+            let core = manager.assign_cpu_core(device_id).expect("应该分配到核心");
+            assert!(core.id >= 3, "{:?} 策略把设备 {} 分配到了预留核心 {}", strategy, device_id, core.id);
+        }
+    }
+}
+
+#[test]
+fn test_manual_mapping_uses_cpu_ids() {
+    let topology = fixture_topology("smt");
+    let cpus = fixture_cpus(&topology);
+    // 预留第一个CPU后，最后一个CPU的编号超出可用核心列表的索引范围
+    let (reserved, target) = (cpus[0], cpus[cpus.len() - 1]);
+    let mapping = [(1000, target), (1001, reserved)].into_iter().collect();
+    let mut manager = CpuAffinityManager::with_topology(true, CpuAffinityStrategy::Manual(mapping), topology)
+        .with_reservation(&reservation(vec![reserved], 0));
+    assert_eq!(manager.available_core_ids(), cpus[1..]);
+
+    // 映射中的值是CPU编号，而不是预留后可用核心列表的索引
+    assert_eq!(manager.assign_cpu_core(1000).map(|core| core.id), Some(target));
+    // 指定了预留的CPU时回退到可用核心
+    let fallback = manager.assign_cpu_core(1001).expect("应该分配到核心");
+    assert_ne!(fallback.id, reserved, "不应该绑定到预留核心");
+}
+
+#[test]
+fn test_set_device_core_uses_cpu_ids() {
+    let topology = fixture_topology("smt");
+    let cpus = fixture_cpus(&topology);
+    let (reserved, target) = (cpus[0], cpus[cpus.len() - 1]);
+    let mut manager = CpuAffinityManager::with_topology(true, CpuAffinityStrategy::RoundRobin, topology)
+        .with_reservation(&reservation(vec![reserved], 0));
+    manager.assign_cpu_core(1000).expect("应该分配到核心");
+
+    // 参数是CPU编号而不是可用核心列表的索引
+    assert_eq!(manager.set_device_core(1000, target).map(|core| core.id), Ok(target));
+    assert_eq!(manager.get_device_core(1000).map(|core| core.id), Some(target));
+    assert!(manager.take_rebind_request(1000));
+    // 预留的CPU不可指定
+    assert!(manager.set_device_core(1000, reserved).is_err());
+    assert_eq!(manager.get_device_core(1000).map(|core| core.id), Some(target));
+}
+
+#[test]
+fn test_reserving_every_core_disables_binding() {
+    let mut manager = CpuAffinityManager::with_core_ids(true, CpuAffinityStrategy::RoundRobin, vec![0, 1])
+        .with_reservation(&reservation(vec![0], 1));
+    assert!(!manager.is_enabled());
+    assert!(manager.assign_cpu_core(1000).is_none());
+}
+
+#[tokio::test]
+async fn test_device_count_clamped_by_headroom() {
+    let available = usable_cpus(&CoreReservation::default()).len();
+    if available < 2 {
+        return; // 单核环境中无法留空
+    }
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = available as u32;
+    cpu_config.affinity.strategy = AffinityStrategyKind::RoundRobin;
+    cpu_config.affinity.headroom = 1;
+
+    let mut core = SoftwareMiningCore::new("预留核心测试".to_string());
+    core.initialize(cpu_config.to_core_config("reservation-test")).await.expect("核心初始化应该成功");
+
+    let devices = core.scan_devices().await.unwrap();
+    assert_eq!(devices.len(), available - 1);
+}
+
+#[tokio::test]
+async fn test_reserving_every_core_fails_initialization() {
+    let available = usable_cpus(&CoreReservation::default()).len();
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.affinity.headroom = available;
+
+    let mut core = SoftwareMiningCore::new("预留核心测试".to_string());
+    assert!(core.initialize(cpu_config.to_core_config("reservation-test")).await.is_err());
+}
@@ -0,0 +1,120 @@
+//! CPU信息检测测试
+//!
+//! 使用 `tests/fixtures/cpuinfo` 下采集的 `/proc/cpuinfo` 和 `tests/fixtures/sysfs` 下的缓存目录
+//! 验证厂商、型号、特性和缓存大小的解析，以及核心能力使用检测结果
+
+use cgminer_core::MiningCore;
+use cgminer_cpu_btc_core::cpu_info::{CacheSizes, CpuInfo};
+use cgminer_cpu_btc_core::hashing::CpuIdentity;
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cpuinfo").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("读取 {} 失败: {}", path.display(), e))
+}
+
+fn sysfs_cpu0(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sysfs")
+        .join(name)
+        .join("devices/system/cpu/cpu0")
+}
+
+#[test]
+fn test_parse_intel_cpuinfo() {
+    let cpu = CpuInfo::from_cpuinfo(&fixture("intel_xeon.txt"));
+    assert_eq!(cpu.vendor.as_deref(), Some("GenuineIntel"));
+    assert_eq!(cpu.model.as_deref(), Some("Intel(R) Xeon(R) Gold 6248 CPU @ 2.50GHz"));
+    assert!(cpu.has_feature("avx512f"));
+    assert!(!cpu.has_feature("sha_ni"));
+    assert_eq!(cpu.simd_support(), vec!["SSE", "SSE2", "SSSE3", "SSE4.1", "SSE4.2", "AVX", "AVX2", "AVX512F"]);
+    assert!(cpu.cache.is_empty(), "cpuinfo不提供分级缓存");
+}
+
+#[test]
+fn test_parse_amd_cpuinfo() {
+    let cpu = CpuInfo::from_cpuinfo(&fixture("amd_epyc.txt"));
+    assert_eq!(cpu.vendor.as_deref(), Some("AuthenticAMD"));
+    assert_eq!(cpu.model.as_deref(), Some("AMD EPYC 7763 64-Core Processor"));
+    assert_eq!(cpu.simd_support(), vec!["SSE", "SSE2", "SSSE3", "SSE4.1", "SSE4.2", "AVX", "AVX2", "SHA"]);
+}
+
+#[test]
+fn test_parse_arm_cpuinfo() {
+    let cpu = CpuInfo::from_cpuinfo(&fixture("arm_neoverse.txt"));
+    assert_eq!(cpu.vendor.as_deref(), Some("ARM"));
+    assert_eq!(cpu.model.as_deref(), Some("part 0xd0c"));
+    assert_eq!(cpu.simd_support(), vec!["NEON", "SHA2"]);
+
+    let identity = CpuIdentity::from_cpuinfo(&fixture("arm_neoverse.txt")).unwrap();
+    assert_eq!(identity.model, "0x41 0xd0c");
+}
+
+#[test]
+fn test_parse_empty_cpuinfo() {
+    let cpu = CpuInfo::from_cpuinfo("");
+    assert_eq!(cpu, CpuInfo::default());
+    assert!(cpu.simd_support().is_empty());
+}
+
+#[test]
+fn test_cache_sizes_from_sysfs() {
+    let cache = CacheSizes::from_sysfs(sysfs_cpu0("hybrid"));
+    assert_eq!(cache, CacheSizes {
+        l1_data_kb: Some(48),
+        l1_instruction_kb: Some(32),
+        l2_kb: Some(1280),
+        l3_kb: Some(30720),
+    });
+    let info = cache.to_cache_info().unwrap();
+    assert_eq!((info.l1_data_kb, info.l1_instruction_kb, info.l2_kb, info.l3_kb), (48, 32, 1280, 30720));
+
+    // 没有L1指令缓存条目的fixture：该级别为0
+    let cache = CacheSizes::from_sysfs(sysfs_cpu0("smt"));
+    assert_eq!(cache.l1_instruction_kb, None);
+    assert_eq!(cache.l3_kb, Some(16 * 1024));
+    assert_eq!(cache.to_cache_info().unwrap().l1_instruction_kb, 0);
+
+    // 没有缓存目录
+    let cache = CacheSizes::from_sysfs(sysfs_cpu0("numa1"));
+    assert!(cache.is_empty());
+    assert!(cache.to_cache_info().is_none());
+}
+
+#[test]
+fn test_fill_missing_keeps_primary_source() {
+    let mut cpu = CpuInfo::from_cpuinfo(&fixture("intel_xeon.txt"));
+    cpu.cache.l2_kb = Some(1024);
+    let fallback = CpuInfo {
+        vendor: Some("other".to_string()),
+        model: None,
+        features: vec!["sse".to_string()],
+        cache: CacheSizes { l1_data_kb: Some(32), l2_kb: Some(256), ..Default::default() },
+    };
+    cpu.fill_missing(&fallback);
+
+    assert_eq!(cpu.vendor.as_deref(), Some("GenuineIntel"));
+    assert!(cpu.has_feature("avx512f"));
+    assert_eq!(cpu.cache.l1_data_kb, Some(32));
+    assert_eq!(cpu.cache.l2_kb, Some(1024));
+}
+
+#[test]
+fn test_detect_current_cpu() {
+    let cpu = CpuInfo::detect();
+    if cfg!(any(target_arch = "x86_64", target_arch = "aarch64")) {
+        assert!(!cpu.simd_support().is_empty(), "64位x86和ARM至少有SSE2/NEON: {:?}", cpu);
+    }
+}
+
+#[test]
+fn test_core_capabilities_use_detected_cpu() {
+    let core = SoftwareMiningCore::new("CPU信息测试核心".to_string());
+    let capabilities = core.get_capabilities().cpu_capabilities.clone().expect("应该有CPU能力");
+    assert_eq!(capabilities.simd_support, core.cpu_info().simd_support());
+
+    let cache = core.cpu_info().cache.to_cache_info();
+    assert_eq!(capabilities.cache_info.as_ref().map(|info| info.l2_kb), cache.as_ref().map(|info| info.l2_kb));
+    assert_eq!(capabilities.cache_info.as_ref().map(|info| info.l3_kb), cache.as_ref().map(|info| info.l3_kb));
+}
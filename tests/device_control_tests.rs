@@ -0,0 +1,141 @@
+//! 单设备控制测试
+//!
+//! 验证 `get_devices` 和 `DeviceHandle` 提供的单设备启用/禁用、重启、参数调整、
+//! 运行中重新绑定核心和按设备取出结果
+
+use cgminer_core::{DeviceStatus, MiningCore, MiningDevice, Work};
+use cgminer_cpu_btc_core::config::{AffinityStrategyKind, CpuCoreConfig};
+use cgminer_cpu_btc_core::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy};
+use cgminer_cpu_btc_core::{MiningEventKind, SoftwareMiningCore};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// 创建并启动指定设备数量的核心
+async fn running_core(device_count: u64) -> SoftwareMiningCore {
+    let mut core = SoftwareMiningCore::new("设备控制测试核心".to_string());
+    let mut config = core.default_config();
+    config.custom_params.insert("device_count".to_string(), serde_json::Value::Number(device_count.into()));
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    core
+}
+
+#[tokio::test]
+async fn test_get_devices_returns_handles() {
+    let mut core = running_core(2).await;
+
+    let devices = core.get_devices().await.expect("get_devices 应该成功");
+    let expected = core.device_count().await.unwrap() as usize;
+    assert_eq!(devices.len(), expected);
+
+    for device in &devices {
+        let info = device.get_info().await.expect("获取设备信息应该成功");
+        assert_eq!(info.id, device.device_id());
+    }
+
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_disable_enable_and_retarget_single_device() {
+    let mut core = running_core(1).await;
+    let handle = core.device(1000).await.expect("设备1000应该存在");
+
+    handle.disable().await.expect("禁用设备应该成功");
+    assert!(!handle.is_enabled().await.unwrap());
+    assert!(matches!(handle.status().await.unwrap(), DeviceStatus::Idle));
+
+    handle.set_target_hashrate(1_000_000.0).await.expect("设置目标算力应该成功");
+    assert!(handle.set_target_hashrate(-1.0).await.is_err(), "负数算力应该被拒绝");
+
+    handle.enable().await.expect("启用设备应该成功");
+    assert!(handle.is_enabled().await.unwrap());
+    assert!(matches!(handle.status().await.unwrap(), DeviceStatus::Running));
+
+    handle.restart().await.expect("重启设备应该成功");
+    let stats = handle.stats().await.expect("获取统计信息应该成功");
+    assert_eq!(stats.device_id, 1000);
+
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_default_config_does_not_cap_hashrate() {
+    // 默认配置的 min_hashrate/max_hashrate 不应该成为连续计算的算力上限
+    let mut core = running_core(1).await;
+    let handle = core.device(1000).await.expect("设备1000应该存在");
+    assert_eq!(handle.hashrate_cap().await.unwrap(), 0.0, "默认不限制算力");
+
+    handle.set_target_hashrate(1_000_000.0).await.unwrap();
+    assert_eq!(handle.hashrate_cap().await.unwrap(), 1_000_000.0, "显式设置的目标算力作为上限");
+    handle.set_target_hashrate(0.0).await.unwrap();
+    assert_eq!(handle.hashrate_cap().await.unwrap(), 0.0);
+    core.stop().await.unwrap();
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.throttling.max_device_hashrate = 20_000_000.0;
+    let mut core = SoftwareMiningCore::new("设备控制测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("throttle-test")).await.unwrap();
+    let handle = core.device(1000).await.expect("设备1000应该存在");
+    assert_eq!(handle.hashrate_cap().await.unwrap(), 20_000_000.0, "配置的单设备上限生效");
+}
+
+#[tokio::test]
+async fn test_unknown_device_has_no_handle() {
+    let mut core = running_core(1).await;
+    assert!(core.device(42).await.is_none());
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_handle_returns_own_results() {
+    let mut core = running_core(1).await;
+    let work = Work::new("handle_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0);
+    core.submit_work(Arc::new(work)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let mut handle = core.device(1000).await.expect("设备1000应该存在");
+    let result = handle.get_result().await.unwrap().expect("最低难度的工作应该产生结果");
+    assert_eq!(result.device_id, 1000);
+    core.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_set_affinity_core_rebinds_running_worker() {
+    // 从检测到的可用核心中选择（已应用进程CPU掩码），与核心的绑定管理器一致
+    let core_ids = CpuAffinityManager::new(true, CpuAffinityStrategy::RoundRobin).available_core_ids();
+    if core_ids.len() < 2 {
+        return; // 单核环境无法验证重新绑定
+    }
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.affinity.strategy = AffinityStrategyKind::RoundRobin;
+    let mut core = SoftwareMiningCore::new("设备控制测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("handle-affinity-test")).await.unwrap();
+    let mut events = core.subscribe_events();
+    core.start().await.unwrap();
+
+    // 运行中调整绑定：不重启设备，工作线程自行重新绑定
+    let handle = core.device(1000).await.expect("设备1000应该存在");
+    // 参数是CPU编号，取一个与当前绑定不同的可用CPU
+    let expected = core_ids[1];
+    handle.set_affinity_core(expected).await.expect("调整绑定核心应该成功");
+    assert!(handle.set_affinity_core(usize::MAX).await.is_err(), "不可用的CPU应该被拒绝");
+    let rebound = timeout(Duration::from_secs(5), async {
+        while let Ok(event) = events.recv().await {
+            if let MiningEventKind::AffinityBinding { core_id: Some(core_id), .. } = event.kind {
+                if core_id == expected {
+                    return true;
+                }
+            }
+        }
+        false
+    }).await.unwrap_or(false);
+    assert!(rebound, "工作线程应该重新绑定到核心 {}", expected);
+    assert!(matches!(handle.status().await.unwrap(), DeviceStatus::Running));
+
+    core.stop().await.unwrap();
+}
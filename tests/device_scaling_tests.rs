@@ -0,0 +1,58 @@
+//! 设备热增减测试
+//!
+//! 验证核心运行期间增加/移除设备，以及移除设备后核心总计保持单调递增
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::{NonceRange, SoftwareMiningCore};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_nonce_partitions_cover_full_space() {
+    for count in [1u32, 2, 3, 7, 16] {
+        let ranges: Vec<NonceRange> = (0..count).map(|i| NonceRange::partition(i, count)).collect();
+
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[count as usize - 1].end, u32::MAX);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end as u64 + 1, pair[1].start as u64, "分区之间不应重叠或留空");
+        }
+
+        let total: u64 = ranges.iter().map(|r| r.size()).sum();
+        assert_eq!(total, u32::MAX as u64 + 1);
+    }
+}
+
+#[tokio::test]
+async fn test_add_and_remove_devices_while_running() {
+    let mut core = SoftwareMiningCore::new("设备热增减测试核心".to_string());
+    // 指定可用CPU数量，使单核机器上也能增加设备
+    core.set_usable_cpu_count(Some(2));
+    let mut config = core.default_config();
+    config.custom_params.insert("device_count".to_string(), serde_json::Value::Number(1.into()));
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+
+    let work = Arc::new(Work::new("scaling_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0));
+    core.submit_work(work).await.expect("提交工作应该成功");
+
+    let added = core.add_devices(1).await.expect("增加设备应该成功");
+    assert_eq!(added, vec![1001]);
+    assert_eq!(core.device_count().await.unwrap(), 2);
+
+    // 可用CPU数量限制设备总数
+    assert!(core.add_devices(1).await.expect("增加设备应该成功").is_empty());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let before = core.get_stats().await.expect("获取统计应该成功");
+
+    let removed = core.remove_devices(1).await.expect("移除设备应该成功");
+    assert_eq!(removed, vec![1001]);
+    assert_eq!(core.device_count().await.unwrap(), 1);
+    assert_eq!(core.retired_totals().devices, removed.len() as u32);
+
+    let after = core.get_stats().await.expect("获取统计应该成功");
+    assert!(after.accepted_work >= before.accepted_work, "移除设备后接受的工作不应减少");
+
+    core.stop().await.expect("核心停止应该成功");
+}
@@ -0,0 +1,75 @@
+//! 挖矿事件流测试
+//!
+//! 验证核心能够广播设备生命周期、工作分发和份额验证事件
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::{MiningEventKind, ShareRejections, SoftwareMiningCore};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// 创建单设备的核心配置
+fn single_device_config(core: &SoftwareMiningCore) -> cgminer_core::CoreConfig {
+    let mut config = core.default_config();
+    config.custom_params.insert("device_count".to_string(), serde_json::Value::Number(1.into()));
+    config
+}
+
+#[tokio::test]
+async fn test_events_for_device_lifecycle_and_shares() {
+    let mut core = SoftwareMiningCore::new("事件测试核心".to_string());
+    let mut events = core.subscribe_events();
+
+    let config = single_device_config(&core);
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+
+    // 目标全部为0xff，任何哈希都满足
+    let work = Arc::new(Work::new("event_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0));
+    core.submit_work(work).await.expect("提交工作应该成功");
+
+    let mut seen_started = false;
+    let mut seen_new_work = false;
+    let mut seen_validated = false;
+
+    let _ = timeout(Duration::from_secs(5), async {
+        while let Ok(event) = events.recv().await {
+            match event.kind {
+                MiningEventKind::DeviceStarted => seen_started = true,
+                MiningEventKind::NewWork { .. } => seen_new_work = true,
+                MiningEventKind::ShareValidated { .. } => seen_validated = true,
+                _ => {}
+            }
+            if seen_started && seen_new_work && seen_validated {
+                break;
+            }
+        }
+    }).await;
+
+    core.stop().await.expect("核心停止应该成功");
+
+    assert!(seen_started, "应该收到设备启动事件");
+    assert!(seen_new_work, "应该收到新工作事件");
+    assert!(seen_validated, "应该收到份额验证事件");
+}
+
+#[tokio::test]
+async fn test_valid_shares_are_not_rejected() {
+    let mut core = SoftwareMiningCore::new("事件测试核心".to_string());
+    let config = single_device_config(&core);
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+
+    let work = Arc::new(Work::new("valid_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0));
+    core.submit_work(work).await.expect("提交工作应该成功");
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    core.stop().await.expect("核心停止应该成功");
+
+    // 份额经过结果通道和核心复算后，拒绝计数保持为0
+    let rejections = core.share_rejections();
+    assert_eq!(rejections, ShareRejections::default(), "当前工作的份额都应该通过复算");
+
+    let stats = core.get_stats().await.expect("获取统计应该成功");
+    assert!(stats.accepted_work > 0, "应该有份额经过结果通道");
+    assert_eq!(stats.rejected_work, 0);
+}
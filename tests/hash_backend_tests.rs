@@ -0,0 +1,186 @@
+//! 哈希实现与启动基准测试测试
+//!
+//! 验证各哈希实现与sha2库的双重SHA256结果一致、CPU标识解析、基准测试选择最快实现，
+//! 以及基准测试结果的缓存和工厂创建核心时的结果记录
+
+use cgminer_core::{CoreFactory, MiningCore, Work};
+use cgminer_cpu_btc_core::config::{CpuCoreConfig, HashBackend};
+use cgminer_cpu_btc_core::hashing::{
+    self, BenchmarkCache, BenchmarkConfig, CpuIdentity, HashImplementation, HeaderHasher,
+};
+use cgminer_cpu_btc_core::SoftwareCoreFactory;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// sha2库计算的参考结果
+fn reference_hash(header: &[u8; 80], nonce: u32) -> [u8; 32] {
+    let mut header = *header;
+    header[76..].copy_from_slice(&nonce.to_le_bytes());
+    Sha256::digest(Sha256::digest(header)).into()
+}
+
+/// 简单的伪随机数（xorshift），保证测试可重复
+fn pseudo_random(seed: &mut u32) -> u32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    *seed
+}
+
+#[test]
+fn test_all_implementations_match_reference() {
+    let mut seed = 0x1234_5678;
+    for _ in 0..8 {
+        let header: [u8; 80] = std::array::from_fn(|_| pseudo_random(&mut seed) as u8);
+        // 5个nonce：4路和8路实现都有不足一组的情况
+        for count in [1usize, 5, 8, 13] {
+            let nonces: Vec<u32> = (0..count).map(|_| pseudo_random(&mut seed)).collect();
+            for implementation in HashImplementation::candidates(HashBackend::Auto) {
+                let mut hasher = HeaderHasher::new(implementation, &header);
+                let mut hashes = vec![[0u8; 32]; count];
+                hasher.hash(&nonces, &mut hashes);
+                for (nonce, hash) in nonces.iter().zip(&hashes) {
+                    assert_eq!(*hash, reference_hash(&header, *nonce), "{} nonce {:#x}", implementation, nonce);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_candidates_follow_backend() {
+    assert_eq!(HashImplementation::candidates(HashBackend::HardwareAccelerated), vec![HashImplementation::Sha2]);
+
+    let names: Vec<String> = HashImplementation::candidates(HashBackend::Auto).iter().map(|i| i.name()).collect();
+    assert_eq!(names, vec!["sha2", "portable-x1", "portable-x2", "portable-x4", "portable-x8"]);
+    assert!(!HashImplementation::Portable { lanes: 4 }.is_hardware_accelerated());
+}
+
+#[test]
+fn test_cpu_identity_from_cpuinfo() {
+    let x86 = "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Xeon(R) Gold 6248 CPU @ 2.50GHz\n\
+               microcode\t: 0x5003604\n\nprocessor\t: 1\nmodel name\t: other\n";
+    let cpu = CpuIdentity::from_cpuinfo(x86).unwrap();
+    assert_eq!(cpu.model, "Intel(R) Xeon(R) Gold 6248 CPU @ 2.50GHz");
+    assert_eq!(cpu.microcode.as_deref(), Some("0x5003604"));
+
+    let arm = "processor\t: 0\nBogoMIPS\t: 50.00\nCPU implementer\t: 0x41\nCPU architecture: 8\nCPU part\t: 0xd0c\n";
+    let cpu = CpuIdentity::from_cpuinfo(arm).unwrap();
+    assert_eq!(cpu.model, "0x41 0xd0c");
+    assert_eq!(cpu.microcode, None);
+
+    assert_eq!(CpuIdentity::from_cpuinfo("processor\t: 0\n"), None);
+}
+
+#[test]
+fn test_benchmark_selects_fastest() {
+    let cpu = CpuIdentity { model: "test".to_string(), microcode: None };
+    let candidates = HashImplementation::candidates(HashBackend::Auto);
+    let report = hashing::run_benchmark(cpu, &candidates, Duration::from_millis(5));
+
+    assert_eq!(report.results.len(), candidates.len());
+    assert!(report.results.iter().all(|result| result.hashrate > 0.0), "{:?}", report);
+    let fastest = report.results.iter().map(|result| result.hashrate).fold(0.0, f64::max);
+    assert_eq!(report.selected_result().unwrap().hashrate, fastest);
+    assert!(!report.cached);
+    assert!(report.summary().ends_with(&format!("→ {}", report.selected)), "{}", report.summary());
+}
+
+fn temp_path(test: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("hash-benchmark-{}-{}", test, std::process::id()))
+        .join("benchmark.json")
+}
+
+#[test]
+fn test_benchmark_cache_roundtrip() {
+    let path = temp_path("cache");
+    let candidates = HashImplementation::candidates(HashBackend::Auto);
+    let cpu = CpuIdentity { model: "cpu-a".to_string(), microcode: Some("0x1".to_string()) };
+    let report = hashing::run_benchmark(cpu.clone(), &candidates, Duration::from_millis(1));
+
+    let mut cache = BenchmarkCache::default();
+    cache.insert(report.clone());
+    cache.insert(report.clone());
+    assert_eq!(cache.reports.len(), 1, "同一CPU只保留一条");
+    cache.save(&path).unwrap();
+
+    let loaded = BenchmarkCache::load(&path).unwrap();
+    let found = loaded.find(&cpu, &candidates).expect("应该找到缓存的结果");
+    assert!(found.cached);
+    assert_eq!(found.selected, report.selected);
+
+    // 微码版本或候选列表不同时不使用缓存
+    let updated = CpuIdentity { microcode: Some("0x2".to_string()), ..cpu.clone() };
+    assert_eq!(loaded.find(&updated, &candidates), None);
+    assert_eq!(loaded.find(&cpu, &[HashImplementation::Sha2]), None);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_unsupported_lanes_rejected() {
+    for lanes in [0, 3, 16] {
+        assert!(HashImplementation::Portable { lanes }.validate().is_err(), "{} 路应该被拒绝", lanes);
+    }
+    for implementation in HashImplementation::candidates(HashBackend::Auto) {
+        assert!(implementation.validate().is_ok(), "{}", implementation);
+    }
+
+    // 缓存中选中了不支持的实现时不使用缓存，而不是退化为单路
+    let candidates = HashImplementation::candidates(HashBackend::Auto);
+    let cpu = CpuIdentity { model: "cpu-a".to_string(), microcode: None };
+    let mut report = hashing::run_benchmark(cpu.clone(), &candidates, Duration::from_millis(1));
+    report.selected = HashImplementation::Portable { lanes: 3 };
+    let mut cache = BenchmarkCache::default();
+    cache.insert(report);
+    assert_eq!(cache.find(&cpu, &candidates), None);
+}
+
+#[test]
+fn test_select_implementation_uses_cache() {
+    let path = temp_path("select");
+    let config = BenchmarkConfig { enabled: true, duration_ms: 2, cache_path: Some(path.clone()) };
+
+    let first = hashing::select_implementation(&config, HashBackend::Auto);
+    assert!(!first.cached);
+    assert!(path.exists(), "基准测试结果应该写入缓存");
+
+    let second = hashing::select_implementation(&config, HashBackend::Auto);
+    assert!(second.cached);
+    assert_eq!(second.selected, first.selected);
+    assert_eq!(second.results, first.results);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_benchmark_config_validation() {
+    let config = CpuCoreConfig::from_toml_str("[benchmark]\nenabled = true").unwrap();
+    assert!(config.benchmark.enabled);
+    assert_eq!(config.benchmark.duration_ms, 50);
+    assert_eq!(config.benchmark.cache_path, None);
+
+    let err = CpuCoreConfig::from_toml_str("[benchmark]\nduration_ms = 0").unwrap_err();
+    assert!(err.to_string().contains("benchmark.duration_ms"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_factory_records_benchmark_result() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.benchmark = BenchmarkConfig { enabled: true, duration_ms: 2, cache_path: None };
+
+    let factory = SoftwareCoreFactory::new();
+    let mut core = factory.create_core(cpu_config.to_core_config("benchmark-test")).await.expect("创建核心应该成功");
+    let info = core.get_info();
+    assert!(info.description.contains("哈希基准"), "{}", info.description);
+
+    // 选中的实现用于挖矿：结果仍然正确
+    core.start().await.unwrap();
+    let work = Work::new("benchmark".to_string(), [0xff; 32], [0u8; 80], 1.0);
+    core.submit_work(std::sync::Arc::new(work)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let results = core.collect_results().await.unwrap();
+    core.stop().await.unwrap();
+    assert!(!results.is_empty(), "最低难度的工作应该产生结果");
+}
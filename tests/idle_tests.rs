@@ -0,0 +1,199 @@
+//! 空闲检测测试
+//!
+//! 用脚本负载驱动 `IdleDetector`，验证负载出现时立即暂停、安静一段时间后恢复、
+//! 挖矿/暂停时间统计，以及核心按空闲检测结果暂停和恢复设备
+
+use cgminer_core::MiningCore;
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::idle::{IdleConfig, IdleDetector, LoadSource, ScriptedLoadSource};
+use cgminer_cpu_btc_core::{MiningEventKind, PauseReason, SoftwareMiningCore};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(250);
+
+fn config() -> IdleConfig {
+    IdleConfig {
+        enabled: true,
+        max_foreign_load: 0.2,
+        sample_interval_ms: 250,
+        quiet_period_ms: 1000,
+    }
+}
+
+#[test]
+fn test_pause_on_load_and_resume_after_quiet_period() {
+    let start = Instant::now();
+    let mut detector = IdleDetector::new_at(config(), start);
+    let loads = [0.05, 0.10, 0.60, 0.15, 0.10, 0.50, 0.05, 0.05, 0.05, 0.05, 0.05, 0.05];
+
+    let states: Vec<bool> = loads.iter().enumerate()
+        .map(|(i, load)| detector.update_at(Some(*load), start + TICK * (i as u32 + 1)))
+        .collect();
+
+    // 第3次采样负载出现立即暂停；第6次负载再次出现，安静计时重新开始，
+    // 从第7次开始连续1秒安静后（第11次）恢复
+    let expected = [false, false, true, true, true, true, true, true, true, true, false, false];
+    assert_eq!(states, expected);
+
+    let stats = detector.stats();
+    assert!(!stats.paused);
+    assert_eq!(stats.pause_count, 1);
+    assert_eq!(stats.last_load, Some(0.05));
+    assert_eq!(stats.active_time + stats.paused_time, TICK * loads.len() as u32);
+    assert_eq!(stats.paused_time, TICK * 8);
+}
+
+#[test]
+fn test_overlapping_thermal_pause_not_counted_as_active() {
+    let start = Instant::now();
+    let mut detector = IdleDetector::new_at(config(), start);
+    let at = |ticks: u32| start + TICK * ticks;
+
+    // 挖矿2个周期后温度调节暂停全部设备
+    detector.update_at(Some(0.05), at(1));
+    detector.update_at(Some(0.05), at(2));
+    detector.set_held_at(true, at(2));
+    detector.update_at(Some(0.05), at(3));
+    // 温度暂停期间系统变忙：繁忙暂停的时间只计入暂停时间
+    assert!(detector.update_at(Some(0.9), at(4)));
+    assert!(detector.update_at(Some(0.05), at(5)));
+    // 温度恢复后仍在等待安静期，直到第9个周期恢复挖矿
+    detector.set_held_at(false, at(6));
+    for tick in 6..=9 {
+        detector.update_at(Some(0.05), at(tick));
+    }
+    assert!(!detector.is_paused());
+    detector.update_at(Some(0.05), at(10));
+
+    let stats = detector.stats();
+    assert_eq!(stats.active_time, TICK * 3, "{:?}", stats);
+    assert_eq!(stats.held_time, TICK * 2, "{:?}", stats);
+    assert_eq!(stats.paused_time, TICK * 5, "{:?}", stats);
+    assert_eq!(stats.active_time + stats.paused_time + stats.held_time, TICK * 10);
+}
+
+#[test]
+fn test_missing_sample_keeps_state() {
+    let start = Instant::now();
+    let mut detector = IdleDetector::new_at(config(), start);
+    assert!(detector.update_at(Some(0.9), start + TICK));
+    assert!(detector.update_at(None, start + TICK * 10), "没有采样时保持暂停");
+    assert_eq!(detector.stats().paused_time, TICK * 9);
+}
+
+#[test]
+fn test_scripted_load_source_repeats_last_value() {
+    let mut source = ScriptedLoadSource::new([0.1, 0.7]);
+    let samples: Vec<Option<f64>> = (0..3).map(|_| source.sample()).collect();
+    assert_eq!(samples, vec![Some(0.1), Some(0.7), Some(0.7)]);
+    assert_eq!(ScriptedLoadSource::new([]).sample(), None);
+}
+
+#[test]
+fn test_idle_config_validation() {
+    let config = CpuCoreConfig::from_toml_str("[idle]\nenabled = true\nmax_foreign_load = 0.3").unwrap();
+    assert!(config.idle.enabled);
+    assert_eq!(config.idle.quiet_period_ms, 30_000);
+
+    let err = CpuCoreConfig::from_toml_str("[idle]\nmax_foreign_load = 1.5").unwrap_err();
+    assert!(err.to_string().contains("idle.max_foreign_load"), "{}", err);
+}
+
+/// 等待条件成立，最多等待5秒
+async fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    for _ in 0..100 {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_core_pauses_while_system_busy() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 2;
+    cpu_config.power.enabled = false;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.idle = IdleConfig {
+        enabled: true,
+        max_foreign_load: 0.2,
+        sample_interval_ms: 50,
+        quiet_period_ms: 200,
+    };
+
+    let mut core = SoftwareMiningCore::new("空闲检测测试核心".to_string());
+    let mut events = core.subscribe_events();
+    core.initialize(cpu_config.to_core_config("idle-test")).await.expect("核心初始化应该成功");
+    assert_eq!(core.idle_stats().map(|stats| stats.paused), Some(false));
+
+    // 空闲 → 繁忙 → 安静
+    let mut loads = vec![0.05; 4];
+    loads.extend([0.8; 6]);
+    loads.push(0.05);
+    core.set_load_source(ScriptedLoadSource::new(loads));
+    core.start().await.expect("核心启动应该成功");
+
+    assert!(wait_for(|| core.pause_reasons() == vec![PauseReason::SystemBusy]).await, "系统繁忙时应该暂停");
+    for handle in core.device_handles().await {
+        assert_eq!(handle.effective_duty_cycle().await.unwrap(), 0.0);
+    }
+
+    assert!(wait_for(|| core.pause_reasons().is_empty()).await, "安静一段时间后应该恢复");
+    let stats = core.idle_stats().unwrap();
+    assert_eq!(stats.pause_count, 1);
+    assert!(stats.paused_time >= Duration::from_millis(200), "{:?}", stats);
+    assert!(stats.active_time > Duration::ZERO);
+    core.stop().await.expect("核心停止应该成功");
+
+    let mut transitions = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        match event.kind {
+            MiningEventKind::MiningPaused { reason } => transitions.push((true, reason)),
+            MiningEventKind::MiningResumed { reason } => transitions.push((false, reason)),
+            _ => {}
+        }
+    }
+    assert_eq!(transitions, vec![(true, PauseReason::SystemBusy), (false, PauseReason::SystemBusy)]);
+}
+
+/// 记录采样次数的负载来源
+struct CountingLoadSource(Arc<AtomicUsize>);
+
+impl LoadSource for CountingLoadSource {
+    fn describe(&self) -> String {
+        "计数负载".to_string()
+    }
+
+    fn sample(&mut self) -> Option<f64> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Some(0.0)
+    }
+}
+
+#[tokio::test]
+async fn test_injected_load_source_survives_restart() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.power.enabled = false;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.idle = IdleConfig { sample_interval_ms: 50, ..config() };
+
+    let mut core = SoftwareMiningCore::new("空闲检测测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("idle-test")).await.expect("核心初始化应该成功");
+    let samples = Arc::new(AtomicUsize::new(0));
+    core.set_load_source(CountingLoadSource(samples.clone()));
+
+    core.start().await.expect("核心启动应该成功");
+    assert!(wait_for(|| samples.load(Ordering::SeqCst) > 0).await);
+    core.stop().await.expect("核心停止应该成功");
+
+    // 重新启动后仍然使用注入的来源，而不是回退到sysinfo
+    let before_restart = samples.load(Ordering::SeqCst);
+    core.start().await.expect("核心重新启动应该成功");
+    assert!(wait_for(|| samples.load(Ordering::SeqCst) > before_restart).await, "重新启动后应该继续使用注入的负载来源");
+    core.stop().await.expect("核心停止应该成功");
+}
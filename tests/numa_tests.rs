@@ -0,0 +1,167 @@
+//! NUMA节点测试
+//!
+//! 使用 `tests/fixtures/sysfs` 下 1/2/4 节点的 sysfs fixture 验证节点发现，
+//! 以及设备在各节点之间的均匀分布、共享状态在绑定后的工作线程上分配
+
+use cgminer_cpu_btc_core::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy};
+use cgminer_cpu_btc_core::numa::NumaTopology;
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// fixture 中的 sysfs NUMA 节点目录
+fn load(name: &str) -> NumaTopology {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sysfs")
+        .join(name)
+        .join("devices/system/node");
+    NumaTopology::from_sysfs(root).expect("fixture NUMA拓扑应该可以读取")
+}
+
+/// 为 `devices` 个设备分配核心，返回每个节点上的设备数量
+fn devices_per_node(numa: NumaTopology, strategy: CpuAffinityStrategy, devices: u32) -> BTreeMap<u32, usize> {
+    let cpus: Vec<usize> = numa.nodes().iter().flat_map(|node| node.cpus.clone()).collect();
+    let mut manager = CpuAffinityManager::with_core_ids(true, strategy, cpus).with_numa(Some(numa));
+
+    let mut per_node = BTreeMap::new();
+    for device_id in 1000..1000 + devices {
+        manager.assign_cpu_core(device_id).expect("应该分配到核心");
+        let node = manager.device_node(device_id).expect("设备应该位于某个节点");
+        *per_node.entry(node).or_insert(0) += 1;
+    }
+    per_node
+}
+
+#[test]
+fn test_single_node() {
+    let numa = load("numa1");
+    assert_eq!(numa.node_count(), 1);
+    assert!(!numa.is_numa());
+    assert_eq!(numa.nodes()[0].cpus, (0..8).collect::<Vec<_>>());
+
+    let per_node = devices_per_node(numa, CpuAffinityStrategy::RoundRobin, 4);
+    assert_eq!(per_node.get(&0), Some(&4));
+}
+
+#[test]
+fn test_two_nodes_skip_memory_only_node() {
+    let numa = load("numa2");
+    // node2 没有CPU，不参与设备分布
+    assert_eq!(numa.node_count(), 2);
+    assert_eq!(numa.node_of_cpu(9), Some(0));
+    assert_eq!(numa.node_of_cpu(12), Some(1));
+    assert_eq!(numa.nodes()[1].distances, vec![21, 10, 21]);
+
+    let per_node = devices_per_node(numa, CpuAffinityStrategy::Intelligent, 6);
+    assert_eq!(per_node.values().copied().collect::<Vec<_>>(), vec![3, 3]);
+}
+
+#[test]
+fn test_four_nodes_spread_evenly() {
+    let numa = load("numa4");
+    assert_eq!(numa.node_count(), 4);
+    assert_eq!(numa.node_of_cpu(21), Some(2));
+
+    let per_node = devices_per_node(numa.clone(), CpuAffinityStrategy::RoundRobin, 8);
+    assert_eq!(per_node.values().copied().collect::<Vec<_>>(), vec![2, 2, 2, 2]);
+
+    // 设备数不能整除节点数时，节点之间最多相差一个设备
+    let per_node = devices_per_node(numa, CpuAffinityStrategy::PhysicalCoresOnly, 6);
+    let counts: Vec<usize> = per_node.values().copied().collect();
+    assert_eq!(counts.iter().sum::<usize>(), 6);
+    assert!(counts.iter().max().unwrap() - counts.iter().min().unwrap() <= 1);
+}
+
+#[test]
+fn test_manual_mapping_is_not_rebalanced() {
+    let numa = load("numa2");
+    let cpus: Vec<usize> = numa.nodes().iter().flat_map(|node| node.cpus.clone()).collect();
+    // 两个设备都手动映射到第一个节点的CPU
+    let node0 = &numa.nodes()[0].cpus;
+    let mapping = [(1000, node0[0]), (1001, node0[1])].into_iter().collect();
+    let mut manager = CpuAffinityManager::with_core_ids(true, CpuAffinityStrategy::Manual(mapping), cpus)
+        .with_numa(Some(numa));
+
+    manager.assign_cpu_core(1000);
+    manager.assign_cpu_core(1001);
+    assert_eq!(manager.device_node(1000), Some(0));
+    assert_eq!(manager.device_node(1001), Some(0));
+}
+
+#[tokio::test]
+async fn test_core_reports_per_node_hashrate() {
+    use cgminer_core::MiningCore;
+
+    let mut core = SoftwareMiningCore::new("NUMA统计测试核心".to_string());
+    let mut config = core.default_config();
+    config.custom_params.insert("device_count".to_string(), serde_json::Value::Number(2.into()));
+    core.initialize(config).await.expect("核心初始化应该成功");
+
+    let node_stats = core.numa_node_stats().await;
+    let device_count: usize = node_stats.iter().map(|stats| stats.device_ids.len()).sum();
+    assert_eq!(device_count as u32, core.device_count().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_unbound_devices_have_no_node() {
+    use cgminer_core::MiningCore;
+    use cgminer_cpu_btc_core::config::CpuCoreConfig;
+
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.affinity.enabled = false;
+
+    let mut core = SoftwareMiningCore::new("NUMA统计测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("numa-test")).await.expect("核心初始化应该成功");
+
+    let node_stats = core.numa_node_stats().await;
+    assert_eq!(node_stats.len(), 1);
+    assert_eq!(node_stats[0].node_id, None, "未绑定的设备不应该归入节点0");
+    assert_eq!(node_stats[0].device_ids.len(), 1);
+}
+
+#[tokio::test]
+async fn test_device_state_is_allocated_on_bound_worker_thread() {
+    use cgminer_core::{DeviceInfo, MiningDevice, Work};
+    use cgminer_cpu_btc_core::config::default_device_config;
+    use cgminer_cpu_btc_core::cpu_affinity::{self, CoreReservation};
+    use cgminer_cpu_btc_core::SoftwareDevice;
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    let cpu = cpu_affinity::usable_cpus(&CoreReservation::default())[0];
+    let mut manager = CpuAffinityManager::with_core_ids(true, CpuAffinityStrategy::RoundRobin, vec![cpu]);
+    manager.assign_cpu_core(1000).expect("应该分配到核心");
+
+    let info = DeviceInfo::new(1000, "Software Device 0".to_string(), "software".to_string(), 0);
+    let config = default_device_config(0);
+    let mut device = SoftwareDevice::new_with_cpu_affinity(info, config.clone(), 0.0, 0.0, 1000,
+                                                           Arc::new(RwLock::new(manager)))
+        .await
+        .unwrap();
+    device.initialize(config).await.unwrap();
+    assert_eq!(device.state_thread(), None);
+
+    // 启动前提交的工作随工作队列一起迁移
+    let work = Work::new("numa".to_string(), [0xff; 32], [0u8; 80], 1.0);
+    device.submit_work(Arc::new(work)).await.unwrap();
+    device.start_continuous_mining().await.unwrap();
+    assert_eq!(device.state_thread(), Some("cpu-miner-1000"), "共享状态应该在绑定后的工作线程上分配");
+
+    // 设备读取的统计就是工作线程更新的那一份
+    let mut total_hashes = 0;
+    for _ in 0..50 {
+        total_hashes = device.get_stats().await.unwrap().total_hashes;
+        if total_hashes > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    device.stop().await.unwrap();
+    assert!(total_hashes > 0);
+
+    // 重新启动时沿用已经迁移的状态
+    device.start_continuous_mining().await.unwrap();
+    assert!(device.get_stats().await.unwrap().total_hashes >= total_hashes);
+    device.stop().await.unwrap();
+}
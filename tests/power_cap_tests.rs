@@ -0,0 +1,181 @@
+//! 功率上限调节测试
+//!
+//! 用线性功耗模型模拟CPU封装功率，验证调节器把功率收敛到预算以内、
+//! 在预算内尽量提高计算单元，以及核心按分配结果设置设备占空比
+
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::performance::{PerformanceConfig, PerformanceOptimizer};
+use cgminer_cpu_btc_core::power::{PowerCapConfig, PowerCapGovernor, PowerModel};
+
+/// 模拟的CPU：20 W 空闲，每个全速线程 10 W，8个线程全速时 100 W
+const PLANT: PowerModel = PowerModel { idle_watts: 20.0, watts_per_thread: 10.0 };
+const THREADS: usize = 8;
+
+fn cap(budget_watts: f64) -> PowerCapConfig {
+    PowerCapConfig { budget_watts, ..Default::default() }
+}
+
+/// 以模拟功率驱动调节器，返回每一步的实测功率
+fn simulate(governor: &mut PowerCapGovernor, plant: PowerModel, steps: usize) -> Vec<f64> {
+    let mut units = THREADS as f64;
+    (0..steps)
+        .map(|_| {
+            let watts = plant.estimate(units);
+            units = governor.update(Some(watts), THREADS).unwrap().units();
+            watts
+        })
+        .collect()
+}
+
+#[test]
+fn test_converges_under_budget_from_full_load() {
+    let config = cap(65.0);
+    let mut governor = PowerCapGovernor::new(config.clone());
+    let trace = simulate(&mut governor, PLANT, 12);
+
+    // 全速 100 W 超出预算，第一步立即下调
+    assert_eq!(trace[0], 100.0);
+    assert!(trace[1] < 75.0, "{:?}", trace);
+    for watts in &trace[6..] {
+        assert!(*watts <= 65.0, "稳定后应该低于预算: {:?}", trace);
+    }
+
+    // 预算内尽量提高计算单元：收敛到目标功率对应的单元数
+    let optimal = PLANT.units_for(config.target_watts());
+    let units = governor.units().unwrap();
+    assert!((units - optimal).abs() / optimal < 0.02, "units={} optimal={}", units, optimal);
+    assert_eq!(PowerAllocation::from_units(units).active_threads, optimal.ceil() as usize);
+}
+
+#[test]
+fn test_ramps_up_without_overshoot_when_load_drops() {
+    let mut governor = PowerCapGovernor::new(cap(65.0));
+
+    // 其他负载占用 30 W 时收敛到较少的单元
+    let busy = PowerModel { idle_watts: 50.0, ..PLANT };
+    simulate(&mut governor, busy, 12);
+    let throttled = governor.units().unwrap();
+    assert!(throttled < 2.0, "{}", throttled);
+
+    // 其他负载结束后逐步上调，不超过预算
+    let mut units = throttled;
+    for _ in 0..20 {
+        let watts = PLANT.estimate(units);
+        assert!(watts <= 65.0, "上调过程中不应超出预算: {} W", watts);
+        let next = governor.update(Some(watts), THREADS).unwrap().units();
+        assert!(next >= units - 1e-9, "功率低于预算时只上调");
+        units = next;
+    }
+    assert!(units > 4.0, "{}", units);
+}
+
+#[test]
+fn test_budget_above_full_load_keeps_all_threads() {
+    let mut governor = PowerCapGovernor::new(cap(200.0));
+    simulate(&mut governor, PLANT, 5);
+    assert_eq!(governor.units(), Some(THREADS as f64));
+}
+
+#[test]
+fn test_model_only_solves_directly() {
+    let mut governor = PowerCapGovernor::new(PowerCapConfig { model: Some(PLANT), ..cap(65.0) });
+    let allocation = governor.update(None, THREADS).unwrap();
+    let expected = PLANT.units_for(65.0 * 0.95);
+    assert!((allocation.units() - expected).abs() < 1e-9);
+    assert!(PLANT.estimate(allocation.units()) <= 65.0);
+}
+
+#[test]
+fn test_performance_optimizer_applies_power_cap() {
+    let mut optimizer = PerformanceOptimizer::new(PerformanceConfig::default());
+    assert_eq!(optimizer.apply_power_cap(Some(100.0), THREADS), None);
+
+    let mut optimizer = PerformanceOptimizer::new(PerformanceConfig {
+        power_cap: Some(cap(65.0)),
+        ..Default::default()
+    });
+    let allocation = optimizer.apply_power_cap(Some(100.0), THREADS).unwrap();
+    assert!(allocation.units() < THREADS as f64);
+    assert_eq!(optimizer.power_governor().unwrap().units(), Some(allocation.units()));
+}
+
+#[test]
+fn test_invalid_cap_rejected() {
+    let err = CpuCoreConfig::from_toml_str("[power.cap]\nbudget_watts = 0.0").unwrap_err();
+    assert!(err.to_string().contains("power.cap.budget_watts"), "{}", err);
+
+    let config = CpuCoreConfig::from_toml_str(
+        "[power.cap]\nbudget_watts = 65.0\nmodel = { idle_watts = 15.0, watts_per_thread = 9.0 }",
+    )
+    .unwrap();
+    assert_eq!(config.power.cap.unwrap().model, Some(PowerModel { idle_watts: 15.0, watts_per_thread: 9.0 }));
+}
+
+/// 核心只在启用 `power-management` 特性时运行功率上限任务
+#[cfg(feature = "power-management")]
+mod core_allocation {
+    use super::cap;
+    use cgminer_core::MiningCore;
+    use cgminer_cpu_btc_core::config::CpuCoreConfig;
+    use cgminer_cpu_btc_core::power::{PowerAllocation, PowerCapConfig, PowerModel};
+    use cgminer_cpu_btc_core::SoftwareMiningCore;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_core_applies_allocation_to_devices() {
+        let model = PowerModel { idle_watts: 10.0, watts_per_thread: 10.0 };
+        let mut cpu_config = CpuCoreConfig::default();
+        cpu_config.device_count = 4;
+        cpu_config.power.enabled = false; // 强制使用功耗模型
+        cpu_config.power.cap = Some(PowerCapConfig { interval_ms: 100, model: Some(model), ..cap(40.0) });
+        cpu_config.temperature.governor.enabled = false;
+
+        let mut core = SoftwareMiningCore::new("功率上限测试核心".to_string());
+        core.initialize(cpu_config.to_core_config("power-cap-test")).await.expect("核心初始化应该成功");
+        core.start().await.expect("核心启动应该成功");
+        tokio::time::sleep(Duration::from_millis(350)).await;
+
+        let handles = core.device_handles().await;
+        let expected = PowerAllocation::from_units(model.units_for(38.0).min(handles.len() as f64));
+        assert_eq!(core.power_allocation(), Some(expected));
+
+        // 按设备ID顺序：前面的设备按占空比运行，其余暂停
+        for (index, handle) in handles.iter().enumerate() {
+            let configured = handle.duty_cycle().await.unwrap();
+            let effective = handle.effective_duty_cycle().await.unwrap();
+            assert!((effective - configured * expected.duty_for(index)).abs() < 1e-9,
+                    "设备 {} 占空比 {} 不符合分配", index, effective);
+        }
+
+        core.stop().await.expect("核心停止应该成功");
+    }
+
+    #[tokio::test]
+    async fn test_added_device_starts_with_current_allocation() {
+        let model = PowerModel { idle_watts: 10.0, watts_per_thread: 10.0 };
+        let mut cpu_config = CpuCoreConfig::default();
+        cpu_config.device_count = 2;
+        cpu_config.power.enabled = false; // 强制使用功耗模型
+        cpu_config.power.cap = Some(PowerCapConfig { interval_ms: 60_000, model: Some(model), ..cap(15.0) });
+        cpu_config.temperature.governor.enabled = false;
+
+        let mut core = SoftwareMiningCore::new("功率上限增加设备测试核心".to_string());
+        core.initialize(cpu_config.to_core_config("power-cap-add-test")).await.expect("核心初始化应该成功");
+        core.start().await.expect("核心启动应该成功");
+        // 调节周期很长，只有启动时的第一次调节
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let allocation = core.power_allocation().expect("应该已经完成第一次调节");
+        assert_eq!(allocation.active_threads, 1, "{:?}", allocation);
+
+        let added = core.add_devices(1).await.expect("增加设备应该成功");
+        if added.is_empty() {
+            core.stop().await.unwrap();
+            return; // CPU核心数不足以增加设备
+        }
+        // 新设备在下一个调节周期之前就按当前分配运行，不会超出功率上限
+        let handle = core.device(added[0]).await.expect("新设备应该存在");
+        assert_eq!(handle.effective_duty_cycle().await.unwrap(), 0.0);
+
+        core.stop().await.expect("核心停止应该成功");
+    }
+}
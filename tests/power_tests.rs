@@ -0,0 +1,149 @@
+//! RAPL功耗测量测试
+//!
+//! 把 `tests/fixtures/powercap` 下的假powercap目录复制到临时目录，修改 `energy_uj`
+//! 模拟计数器增长和回绕，验证封装功率、设备分摊、J/TH和设备统计中的功耗
+
+use cgminer_core::{DeviceInfo, MiningCore, MiningDevice};
+use cgminer_cpu_btc_core::config::{default_device_config, CpuCoreConfig};
+use cgminer_cpu_btc_core::power::{discover_rapl_domains, PowerConfig, PowerMeter};
+use cgminer_cpu_btc_core::{SoftwareDevice, SoftwareMiningCore};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/powercap").join(name)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// 复制fixture到临时目录，便于修改计数器
+fn writable_fixture(test: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("powercap-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    copy_dir(&fixture("dual"), &root);
+    root
+}
+
+fn set_energy(root: &Path, domain: &str, energy_uj: u64) {
+    fs::write(root.join(domain).join("energy_uj"), format!("{}\n", energy_uj)).unwrap();
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+}
+
+#[test]
+fn test_discover_package_domains_only() {
+    let domains = discover_rapl_domains(fixture("dual"));
+
+    // 子域 intel-rapl:0:N、psys 和 mmio 接口都不参与统计
+    let names: Vec<&str> = domains.iter().map(|domain| domain.name.as_str()).collect();
+    assert_eq!(names, vec!["package-0", "package-1"]);
+    assert_eq!(domains[0].package_id, 0);
+    assert_eq!(domains[0].max_energy_range_uj, 262_143_328_850);
+    assert!(discover_rapl_domains(fixture("missing")).is_empty());
+}
+
+#[test]
+fn test_package_power_with_wraparound_and_attribution() {
+    let root = writable_fixture("meter");
+    let mut meter = PowerMeter::new(&root);
+    let start = Instant::now();
+
+    let baseline = meter.update_at(start, &[(1000, Some(0), 0), (1001, Some(0), 0), (1002, None, 0)]).unwrap();
+    assert!(baseline.is_none(), "第一次采样只记录基线");
+
+    // 封装0计数器回绕: 262143000000 → 31171150，增量31.5J；封装1增量20J
+    set_energy(&root, "intel-rapl:0", 31_171_150);
+    set_energy(&root, "intel-rapl:1", 70_000_000);
+    let devices = [(1000, Some(0), 3_000_000), (1001, Some(0), 1_000_000), (1002, None, 1_000_000)];
+    let report = meter.update_at(start + Duration::from_secs(1), &devices).unwrap().unwrap();
+
+    assert_close(report.package_watts[&0], 31.5);
+    assert_close(report.package_watts[&1], 20.0);
+    assert_close(report.total_watts, 51.5);
+
+    // 封装0按哈希数3:1分摊给绑定的设备，封装1由未绑定的设备承担
+    assert_close(report.device_watts[&1000], 23.625);
+    assert_close(report.device_watts[&1001], 7.875);
+    assert_close(report.device_watts[&1002], 20.0);
+
+    assert_close(report.hashrate, 5_000_000.0);
+    let efficiency = report.joules_per_terahash.unwrap();
+    assert!((efficiency - 51.5 / 5e-6).abs() < 1.0, "{}", efficiency);
+    assert_eq!(meter.last_report(), Some(&report));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_disabled_power_config() {
+    let config = PowerConfig {
+        enabled: false,
+        powercap_root: fixture("dual").to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    assert!(PowerMeter::from_config(&config).is_none());
+}
+
+#[tokio::test]
+async fn test_device_reports_power_consumption() {
+    let info = DeviceInfo::new(1000, "Software Device 0".to_string(), "software".to_string(), 0);
+    let config = default_device_config(0);
+    let device = SoftwareDevice::new(info, config, 0.0, 0.0, 1000).await.unwrap();
+
+    assert_eq!(device.get_stats().await.unwrap().power_consumption, None);
+    device.set_power_consumption(23.625);
+    assert_eq!(device.get_stats().await.unwrap().power_consumption, Some(23.625));
+}
+
+#[tokio::test]
+async fn test_core_attributes_rapl_power_to_devices() {
+    if !cfg!(target_os = "linux") {
+        return; // 只有Linux读取RAPL
+    }
+
+    let root = writable_fixture("core");
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 2;
+    cpu_config.power.powercap_root = root.to_string_lossy().to_string();
+    // 只在启动时采样一次，之后计数器不再变化
+    cpu_config.power.interval_ms = 60_000;
+
+    let mut core = SoftwareMiningCore::new("功耗测试核心".to_string());
+    core.initialize(cpu_config.to_core_config("power-test")).await.expect("核心初始化应该成功");
+    assert!(core.power_report().is_none(), "核心启动之前不采样");
+
+    set_energy(&root, "intel-rapl:0", 31_171_150);
+    set_energy(&root, "intel-rapl:1", 70_000_000);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    core.start().await.expect("核心启动应该成功");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // 统计只读取采样任务的结果，不会再次采样
+    core.get_stats().await.unwrap();
+    let report = core.power_report().expect("应该有功耗报告");
+    assert!(report.total_watts > 0.0);
+    let attributed: f64 = report.device_watts.values().sum();
+    assert!(attributed > 0.0 && attributed <= report.total_watts + 1e-6);
+
+    for handle in core.device_handles().await {
+        let stats = handle.get_stats().await.unwrap();
+        assert!(stats.power_consumption.unwrap() > 0.0, "设备 {} 应该分摊到功耗", handle.device_id());
+    }
+    core.stop().await.expect("核心停止应该成功");
+
+    let _ = fs::remove_dir_all(&root);
+}
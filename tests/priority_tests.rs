@@ -0,0 +1,121 @@
+//! 挖矿线程优先级测试
+//!
+//! 在独立线程上应用调度策略、nice值和I/O优先级（提高nice值和 `SCHED_BATCH`/`SCHED_IDLE`
+//! 不需要特权），验证设置结果和设备上报的优先级状态
+
+use cgminer_core::{DeviceInfo, MiningDevice};
+use cgminer_cpu_btc_core::config::{default_device_config, CpuCoreConfig};
+use cgminer_cpu_btc_core::priority::{
+    apply_to_current_thread, IoPriority, IoPriorityClass, PriorityConfig, SchedulerPolicy, SettingOutcome,
+};
+use cgminer_cpu_btc_core::SoftwareDevice;
+use std::time::Duration;
+
+/// 在新线程上应用配置，避免影响测试线程
+fn apply_on_new_thread<T: Send + 'static>(
+    config: PriorityConfig,
+    inspect: impl FnOnce() -> T + Send + 'static,
+) -> (cgminer_cpu_btc_core::priority::PriorityStatus, T) {
+    std::thread::spawn(move || {
+        let status = apply_to_current_thread(&config);
+        (status, inspect())
+    })
+    .join()
+    .unwrap()
+}
+
+#[test]
+fn test_default_config_changes_nothing() {
+    let config = PriorityConfig::default();
+    assert!(!config.is_requested());
+
+    let (status, _) = apply_on_new_thread(config, || ());
+    assert_eq!(status.policy, SettingOutcome::NotRequested);
+    assert_eq!(status.nice, SettingOutcome::NotRequested);
+    assert_eq!(status.ioprio, SettingOutcome::NotRequested);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_batch_policy_nice_and_ioprio_applied() {
+    let config = PriorityConfig {
+        policy: SchedulerPolicy::Batch,
+        nice: Some(10),
+        ioprio: Some(IoPriority { class: IoPriorityClass::BestEffort, level: 7 }),
+    };
+    let (status, (policy, nice)) = apply_on_new_thread(config, || unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        (libc::sched_getscheduler(0), libc::getpriority(libc::PRIO_PROCESS as _, tid))
+    });
+
+    assert_eq!(status.policy, SettingOutcome::Applied);
+    assert_eq!(status.nice, SettingOutcome::Applied);
+    assert_eq!(status.ioprio, SettingOutcome::Applied);
+    assert_eq!(policy, libc::SCHED_BATCH);
+    assert_eq!(nice, 10);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_idle_policy_applied() {
+    let config = PriorityConfig { policy: SchedulerPolicy::Idle, ..Default::default() };
+    let (status, policy) = apply_on_new_thread(config, || unsafe { libc::sched_getscheduler(0) });
+    assert_eq!(status.policy, SettingOutcome::Applied);
+    assert_eq!(policy, libc::SCHED_IDLE);
+}
+
+#[cfg(not(target_os = "linux"))]
+#[test]
+fn test_unsupported_outside_linux() {
+    let config = PriorityConfig { policy: SchedulerPolicy::Idle, nice: Some(19), ioprio: None };
+    let (status, _) = apply_on_new_thread(config, || ());
+    assert_eq!(status.policy, SettingOutcome::Unsupported);
+    assert_eq!(status.nice, SettingOutcome::Unsupported);
+    assert_eq!(status.ioprio, SettingOutcome::NotRequested);
+}
+
+#[test]
+fn test_priority_config_parsing_and_validation() {
+    let config = CpuCoreConfig::from_toml_str(
+        "[priority]\npolicy = \"idle\"\nnice = 19\nioprio = { class = \"best_effort\" }",
+    )
+    .unwrap();
+    assert_eq!(config.priority.policy, SchedulerPolicy::Idle);
+    assert_eq!(config.priority.nice, Some(19));
+    assert_eq!(config.priority.ioprio, Some(IoPriority { class: IoPriorityClass::BestEffort, level: 7 }));
+
+    let err = CpuCoreConfig::from_toml_str("[priority]\nnice = 25").unwrap_err();
+    assert!(err.to_string().contains("priority.nice"), "{}", err);
+    let err = CpuCoreConfig::from_toml_str("[priority]\nioprio = { class = \"best_effort\", level = 9 }").unwrap_err();
+    assert!(err.to_string().contains("priority.ioprio.level"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_device_reports_priority_status() {
+    let info = DeviceInfo::new(1000, "Software Device 0".to_string(), "software".to_string(), 0);
+    let config = default_device_config(0);
+    let mut device = SoftwareDevice::new(info, config.clone(), 0.0, 0.0, 1000).await.unwrap();
+    device.initialize(config).await.unwrap();
+    assert_eq!(device.priority_status(), None);
+
+    device.set_priority_config(PriorityConfig { nice: Some(5), ..Default::default() });
+    device.start_continuous_mining().await.unwrap();
+
+    let mut status = None;
+    for _ in 0..50 {
+        status = device.priority_status();
+        if status.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    device.stop().await.unwrap();
+
+    let status = status.expect("工作线程启动后应该上报优先级状态");
+    assert_eq!(status.policy, SettingOutcome::NotRequested);
+    if cfg!(target_os = "linux") {
+        assert_eq!(status.nice, SettingOutcome::Applied);
+    } else {
+        assert_eq!(status.nice, SettingOutcome::Unsupported);
+    }
+}
@@ -0,0 +1,198 @@
+//! 收益估算测试
+//!
+//! 验证每日BTC、电费和盈亏平衡点的计算，自动暂停的滞回，
+//! 以及核心按估算结果暂停/恢复设备并发布事件
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::profitability::{self, difficulty_from_header};
+use cgminer_cpu_btc_core::{
+    MiningEventKind, PauseReason, ProfitabilityConfig, ProfitabilityEstimator, SoftwareMiningCore,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 1 TH/s 在难度 1e12 下每天的期望BTC
+const BTC_PER_DAY: f64 = 86_400.0 / 4_294_967_296.0 * 3.125;
+
+fn config() -> ProfitabilityConfig {
+    ProfitabilityConfig {
+        enabled: true,
+        electricity_price: 0.10,
+        btc_price: 60_000.0,
+        network_difficulty: 1e12,
+        ..Default::default()
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() <= expected.abs() * 1e-9, "{} != {}", actual, expected);
+}
+
+#[test]
+fn test_estimate_revenue_cost_and_break_even() {
+    let estimate = profitability::estimate(&config(), 1e12, Some(100.0), 1e12).unwrap();
+
+    assert_close(estimate.btc_per_day, BTC_PER_DAY);
+    assert_close(estimate.revenue_per_day, BTC_PER_DAY * 60_000.0);
+    // 100 W × 24 h = 2.4 kWh
+    assert_close(estimate.cost_per_day, 0.24);
+    assert_close(estimate.profit_per_day, estimate.revenue_per_day - 0.24);
+    assert_close(estimate.break_even_electricity_price.unwrap(), estimate.revenue_per_day / 2.4);
+    assert_close(estimate.break_even_btc_price.unwrap(), 0.24 / BTC_PER_DAY);
+    assert!(estimate.profitable);
+
+    // 手续费计入区块收入
+    let with_fees = ProfitabilityConfig { fees_per_block: 3.125, ..config() };
+    let doubled = profitability::estimate(&with_fees, 1e12, Some(100.0), 1e12).unwrap();
+    assert_close(doubled.btc_per_day, BTC_PER_DAY * 2.0);
+}
+
+#[test]
+fn test_estimate_without_power_or_difficulty() {
+    let estimate = profitability::estimate(&config(), 1e6, None, 1e12).unwrap();
+    assert_eq!(estimate.cost_per_day, 0.0);
+    assert_eq!(estimate.break_even_electricity_price, None);
+
+    assert!(profitability::estimate(&config(), 1e6, Some(100.0), 0.0).is_none());
+    let estimate = profitability::estimate(&config(), 0.0, Some(100.0), 1e12).unwrap();
+    assert_eq!(estimate.break_even_btc_price, None);
+    assert!(!estimate.profitable);
+}
+
+#[test]
+fn test_difficulty_from_work_header() {
+    let mut header = [0u8; 80];
+    header[72..76].copy_from_slice(&0x1b0404cbu32.to_le_bytes());
+    let difficulty = difficulty_from_header(&header).unwrap();
+    assert!((difficulty - 16307.42).abs() < 0.01, "{}", difficulty);
+
+    // 配置了难度时优先使用配置值
+    let mut estimator = ProfitabilityEstimator::new(ProfitabilityConfig { network_difficulty: 0.0, ..config() });
+    assert_eq!(estimator.network_difficulty(), None);
+    estimator.observe_difficulty(difficulty);
+    assert_eq!(estimator.network_difficulty(), Some(difficulty));
+
+    let mut estimator = ProfitabilityEstimator::new(config());
+    estimator.observe_difficulty(difficulty);
+    assert_eq!(estimator.network_difficulty(), Some(1e12));
+}
+
+#[test]
+fn test_auto_pause_with_resume_margin() {
+    let mut estimator = ProfitabilityEstimator::new(ProfitabilityConfig {
+        auto_pause: true,
+        watts: Some(100.0),
+        ..config()
+    });
+    let revenue = BTC_PER_DAY * 60_000.0;
+    let break_even = revenue / 2.4;
+
+    // 电价高于盈亏平衡点时暂停
+    estimator.set_electricity_price(break_even * 1.5);
+    assert!(!estimator.update(1e12, None).unwrap().profitable);
+    assert!(estimator.should_pause());
+
+    // 暂停期间算力为0，沿用挖矿时的算力；略低于平衡点但在余量内时保持暂停
+    estimator.set_electricity_price(break_even * 0.98);
+    let estimate = estimator.update(0.0, None).unwrap();
+    assert_eq!(estimate.hashrate, 1e12);
+    assert!(estimate.profitable);
+    assert!(estimator.should_pause(), "收益未超过电费的1.05倍时不应恢复");
+
+    estimator.set_electricity_price(break_even * 0.9);
+    estimator.update(0.0, None).unwrap();
+    assert!(!estimator.should_pause());
+}
+
+#[test]
+fn test_no_auto_pause_without_power() {
+    let mut estimator = ProfitabilityEstimator::new(ProfitabilityConfig {
+        auto_pause: true,
+        electricity_price: 1e9,
+        ..config()
+    });
+    assert!(estimator.update(1e12, None).unwrap().profitable);
+    assert!(!estimator.should_pause(), "功率未知时不能判断亏损");
+    assert!(estimator.last_estimate().is_some());
+}
+
+#[test]
+fn test_profitability_config() {
+    let config = CpuCoreConfig::from_toml_str(
+        "[profitability]\nenabled = true\nelectricity_price = 0.12\nbtc_price = 60000.0\nwatts = 65.0\nauto_pause = true",
+    )
+    .unwrap();
+    assert_eq!(config.profitability.watts, Some(65.0));
+    assert_eq!(config.profitability.block_reward, 3.125);
+
+    let err = CpuCoreConfig::from_toml_str("[profitability]\nelectricity_price = -1.0").unwrap_err();
+    assert!(err.to_string().contains("profitability"), "{}", err);
+    let err = CpuCoreConfig::from_toml_str("[profitability]\nwatts = 0.0").unwrap_err();
+    assert!(err.to_string().contains("profitability.watts"), "{}", err);
+}
+
+/// 等待条件成立，最多等待5秒
+async fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    for _ in 0..50 {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_core_pauses_when_unprofitable() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 2;
+    cpu_config.power.enabled = false;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.profitability = ProfitabilityConfig {
+        enabled: true,
+        electricity_price: 1e12,
+        btc_price: 60_000.0,
+        network_difficulty: 0.0,
+        watts: Some(100.0),
+        auto_pause: true,
+        interval_ms: 100,
+        ..Default::default()
+    };
+
+    let mut core = SoftwareMiningCore::new("收益测试核心".to_string());
+    let mut events = core.subscribe_events();
+    core.initialize(cpu_config.to_core_config("profitability-test")).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+
+    // 难度来自区块头 nBits = 0x1d00ffff（难度1）
+    let mut header = [0u8; 80];
+    header[72..76].copy_from_slice(&0x1d00ffffu32.to_le_bytes());
+    core.submit_work(Arc::new(Work::new("profit_job".to_string(), [0u8; 32], header, 1.0)))
+        .await
+        .expect("提交工作应该成功");
+
+    assert!(wait_for(|| core.pause_reasons() == vec![PauseReason::Unprofitable]).await, "亏损时应该暂停");
+    let estimate = core.profitability().expect("应该有收益估算");
+    assert_eq!(estimate.network_difficulty, 1.0);
+    assert!(!estimate.profitable);
+    for handle in core.device_handles().await {
+        assert_eq!(handle.effective_duty_cycle().await.unwrap(), 0.0);
+    }
+
+    // 电价降到0后恢复
+    core.set_electricity_price(0.0);
+    assert!(wait_for(|| core.pause_reasons().is_empty()).await, "盈利后应该恢复");
+    core.stop().await.expect("核心停止应该成功");
+
+    let mut kinds = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        match event.kind {
+            MiningEventKind::MiningPaused { reason } => kinds.push(format!("paused:{:?}", reason)),
+            MiningEventKind::MiningResumed { reason } => kinds.push(format!("resumed:{:?}", reason)),
+            MiningEventKind::ProfitabilityChanged { profitable, .. } => kinds.push(format!("profitable:{}", profitable)),
+            _ => {}
+        }
+    }
+    assert_eq!(kinds, vec!["paused:Unprofitable", "profitable:false", "resumed:Unprofitable", "profitable:true"]);
+}
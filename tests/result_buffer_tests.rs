@@ -0,0 +1,84 @@
+//! 有界结果缓冲压力测试
+//!
+//! 在极低难度下持续挖矿但不调用 `collect_results`，验证结果缓冲保持有界并正确计数
+
+use cgminer_core::{CoreConfig, MiningCore, Work};
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// 创建指定结果缓冲配置的核心配置
+fn buffered_config(core: &SoftwareMiningCore, capacity: u64, policy: &str) -> CoreConfig {
+    let mut config = core.default_config();
+    config.custom_params.insert("device_count".to_string(), serde_json::Value::Number(2.into()));
+    config.custom_params.insert("result_buffer_capacity".to_string(), serde_json::Value::Number(capacity.into()));
+    config.custom_params.insert("result_overflow_policy".to_string(), serde_json::Value::String(policy.to_string()));
+    config
+}
+
+/// 极低难度的工作 - 每个哈希都是有效份额
+fn trivial_work() -> Arc<Work> {
+    Arc::new(Work::new("trivial_job".to_string(), [0xffu8; 32], [0u8; 80], 1.0))
+}
+
+#[tokio::test]
+async fn test_trivial_difficulty_without_collecting_stays_bounded() {
+    let mut core = SoftwareMiningCore::new("压力测试核心".to_string());
+    let config = buffered_config(&core, 64, "drop_oldest");
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    core.submit_work(trivial_work()).await.expect("提交工作应该成功");
+
+    // 持续挖矿但不收集结果
+    sleep(Duration::from_millis(500)).await;
+
+    let stats = core.result_buffer_stats();
+    core.stop().await.expect("核心停止应该成功");
+
+    assert!(stats.buffered <= 64, "缓冲结果数 {} 不应超过容量", stats.buffered);
+    assert!(stats.total_received > 64, "低难度下应该产生大量结果");
+    assert!(stats.total_dropped() > 0, "超出容量的结果应该被计入丢弃数");
+
+    let results = core.collect_results().await.expect("收集结果应该成功");
+    assert!(results.len() <= 64);
+}
+
+#[tokio::test]
+async fn test_pause_mining_applies_backpressure() {
+    let mut core = SoftwareMiningCore::new("背压测试核心".to_string());
+    let config = buffered_config(&core, 16, "pause_mining");
+    core.initialize(config).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    core.submit_work(trivial_work()).await.expect("提交工作应该成功");
+
+    sleep(Duration::from_millis(300)).await;
+    let paused = core.result_buffer_stats();
+    assert_eq!(paused.buffered, 16, "缓冲应该被填满");
+    assert_eq!(paused.total_dropped(), 0, "暂停策略不应丢弃结果");
+    assert!(paused.paused_sends > 0, "设备应该因背压而等待");
+
+    // 收集结果后挖矿继续
+    let results = core.collect_results().await.expect("收集结果应该成功");
+    assert_eq!(results.len(), 16);
+    sleep(Duration::from_millis(100)).await;
+    assert!(core.result_buffer_stats().total_received > paused.total_received);
+
+    core.stop().await.expect("核心停止应该成功");
+}
+
+#[tokio::test]
+async fn test_results_delivered_after_reinitialize() {
+    let mut core = SoftwareMiningCore::new("重新初始化测试核心".to_string());
+    for round in 0..2 {
+        let config = buffered_config(&core, 64, "drop_oldest");
+        core.initialize(config).await.expect("核心初始化应该成功");
+        core.start().await.expect("核心启动应该成功");
+        core.submit_work(trivial_work()).await.expect("提交工作应该成功");
+        sleep(Duration::from_millis(200)).await;
+        core.stop().await.expect("核心停止应该成功");
+
+        let results = core.collect_results().await.expect("收集结果应该成功");
+        assert!(!results.is_empty(), "第 {} 次初始化后应该收到结果", round + 1);
+    }
+}
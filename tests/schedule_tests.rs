@@ -0,0 +1,171 @@
+//! 挖矿时间表测试
+//!
+//! 用手动时钟验证跨午夜时间段、星期过滤、时区偏移、降速时间段，
+//! 以及核心按时间表暂停、恢复和降速
+
+use cgminer_core::MiningCore;
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::schedule::{Clock, ManualClock, Schedule, ScheduleConfig, ScheduleWindow};
+use cgminer_cpu_btc_core::{MiningEventKind, PauseReason, SoftwareMiningCore};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// 2024-01-01 是星期一
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+}
+
+fn window(name: &str, days: &[&str], start: &str, end: &str, duty_cycle: f64) -> ScheduleWindow {
+    ScheduleWindow {
+        name: Some(name.to_string()),
+        days: days.iter().map(|day| day.to_string()).collect(),
+        start: start.to_string(),
+        end: end.to_string(),
+        duty_cycle,
+    }
+}
+
+fn night_config() -> ScheduleConfig {
+    ScheduleConfig {
+        enabled: true,
+        timezone: "utc".to_string(),
+        default_duty_cycle: 0.0,
+        windows: vec![
+            window("夜间", &["mon", "tue", "wed", "thu", "fri"], "23:00", "07:00", 1.0),
+            window("周末午间", &["sat", "sun"], "11:00", "14:00", 0.5),
+        ],
+        interval_ms: 60_000,
+    }
+}
+
+#[test]
+fn test_overnight_window_and_weekdays() {
+    let schedule = Schedule::from_config(&night_config()).unwrap();
+    let window = |time| schedule.evaluate(time).window;
+
+    assert_eq!(window(at(1, 22, 59)), None);
+    assert_eq!(window(at(1, 23, 30)).as_deref(), Some("夜间"));
+    assert_eq!(window(at(2, 6, 59)).as_deref(), Some("夜间"), "周一晚上的时间段延续到周二早上");
+    assert_eq!(window(at(2, 7, 0)), None);
+    assert_eq!(window(at(6, 1, 0)).as_deref(), Some("夜间"), "周五晚上的时间段延续到周六早上");
+    assert_eq!(window(at(7, 1, 0)), None, "周六晚上不在时间段内");
+    assert_eq!(window(at(1, 1, 0)), None, "周日晚上不在时间段内");
+
+    let outside = schedule.evaluate(at(1, 12, 0));
+    assert!(!outside.active);
+    assert_eq!(outside.duty_cycle, 0.0);
+    assert_eq!(outside.evaluated_at, at(1, 12, 0));
+
+    let throttled = schedule.evaluate(at(6, 12, 0));
+    assert!(throttled.active);
+    assert_eq!(throttled.duty_cycle, 0.5);
+}
+
+#[test]
+fn test_first_matching_window_and_full_day() {
+    let config = ScheduleConfig {
+        windows: vec![
+            window("维护", &[], "03:00", "04:00", 0.0),
+            window("全天", &[], "00:00", "00:00", 0.8),
+        ],
+        default_duty_cycle: 1.0,
+        ..night_config()
+    };
+    let schedule = Schedule::from_config(&config).unwrap();
+
+    assert_eq!(schedule.evaluate(at(3, 3, 30)).window.as_deref(), Some("维护"));
+    assert!(!schedule.evaluate(at(3, 3, 30)).active);
+    assert_eq!(schedule.evaluate(at(3, 4, 0)).duty_cycle, 0.8);
+}
+
+#[test]
+fn test_timezone_offset() {
+    let config = ScheduleConfig { timezone: "+08:00".to_string(), ..night_config() };
+    let schedule = Schedule::from_config(&config).unwrap();
+
+    // UTC 周一 15:30 = 东八区周一 23:30
+    assert_eq!(schedule.evaluate(at(1, 15, 30)).window.as_deref(), Some("夜间"));
+    assert_eq!(schedule.evaluate(at(1, 23, 30)).window, None);
+
+    let config = ScheduleConfig { timezone: "-05:30".to_string(), ..night_config() };
+    let schedule = Schedule::from_config(&config).unwrap();
+    // UTC 周二 04:30 = 周一 23:00
+    assert_eq!(schedule.evaluate(at(2, 4, 30)).window.as_deref(), Some("夜间"));
+}
+
+#[test]
+fn test_invalid_schedule_rejected() {
+    let invalid = [
+        ScheduleConfig { timezone: "Asia/Shanghai".to_string(), ..night_config() },
+        ScheduleConfig { windows: vec![window("x", &[], "25:00", "07:00", 1.0)], ..night_config() },
+        ScheduleConfig { windows: vec![window("x", &["funday"], "23:00", "07:00", 1.0)], ..night_config() },
+        ScheduleConfig { windows: vec![window("x", &[], "23:00", "07:00", 1.5)], ..night_config() },
+    ];
+    for config in &invalid {
+        assert!(Schedule::from_config(config).is_err(), "{:?}", config);
+    }
+
+    let err = CpuCoreConfig::from_toml_str("[schedule]\nwindows = [{ start = \"23:00\", end = \"7am\" }]").unwrap_err();
+    assert!(err.to_string().contains("schedule"), "{}", err);
+
+    let config = CpuCoreConfig::from_toml_str(
+        "[schedule]\nenabled = true\ntimezone = \"+08:00\"\nwindows = [{ name = \"夜间\", start = \"23:00\", end = \"07:00\" }]",
+    )
+    .unwrap();
+    assert_eq!(config.schedule.windows[0].duty_cycle, 1.0);
+}
+
+#[test]
+fn test_manual_clock_is_shared() {
+    let clock = ManualClock::new(at(1, 0, 0));
+    let shared = clock.clone();
+    clock.set(at(2, 12, 0));
+    assert_eq!(shared.now(), at(2, 12, 0));
+}
+
+#[tokio::test]
+async fn test_core_follows_schedule() {
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 2;
+    cpu_config.power.enabled = false;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.schedule = night_config();
+
+    let clock = ManualClock::new(at(1, 12, 0));
+    let mut core = SoftwareMiningCore::new("时间表测试核心".to_string());
+    let mut events = core.subscribe_events();
+    core.set_clock(clock.clone());
+    core.initialize(cpu_config.to_core_config("schedule-test")).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+
+    // 周一中午不在时间段内：暂停
+    let state = core.evaluate_schedule().await.unwrap();
+    assert!(!state.active);
+    assert_eq!(core.pause_reasons(), vec![PauseReason::OutsideSchedule]);
+    assert_eq!(core.schedule_state(), Some(state));
+
+    // 进入夜间时间段：全速
+    clock.set(at(1, 23, 30));
+    core.evaluate_schedule().await.unwrap();
+    assert!(core.pause_reasons().is_empty());
+    for handle in core.device_handles().await {
+        assert_eq!(handle.effective_duty_cycle().await.unwrap(), handle.duty_cycle().await.unwrap());
+    }
+
+    // 周末午间：降速到一半
+    clock.set(at(6, 12, 0));
+    core.evaluate_schedule().await.unwrap();
+    for handle in core.device_handles().await {
+        let expected = handle.duty_cycle().await.unwrap() * 0.5;
+        assert!((handle.effective_duty_cycle().await.unwrap() - expected).abs() < 1e-9);
+    }
+    assert_eq!(core.schedule_state().unwrap().window.as_deref(), Some("周末午间"));
+    core.stop().await.expect("核心停止应该成功");
+
+    let mut windows = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let MiningEventKind::ScheduleChanged { window, .. } = event.kind {
+            windows.push(window);
+        }
+    }
+    assert_eq!(windows, vec![None, Some("夜间".to_string()), Some("周末午间".to_string())]);
+}
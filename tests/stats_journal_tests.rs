@@ -0,0 +1,198 @@
+//! 累计统计日志测试
+//!
+//! 验证基线与本次会话的累加、运行时间和最佳份额的记录、日志文件的读写和损坏处理，
+//! 以及核心停止时写入、重新初始化后继续累加
+
+use cgminer_core::{MiningCore, Work};
+use cgminer_cpu_btc_core::config::CpuCoreConfig;
+use cgminer_cpu_btc_core::stats_journal::{LifetimeStats, SessionCounters, StatsJournal, StatsJournalConfig};
+use cgminer_cpu_btc_core::SoftwareMiningCore;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn temp_path(test: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("stats-journal-{}-{}", test, std::process::id()))
+        .join("stats.json")
+}
+
+fn journal_config(path: &PathBuf) -> StatsJournalConfig {
+    StatsJournalConfig { enabled: true, path: Some(path.clone()), ..Default::default() }
+}
+
+fn session(total_hashes: u64, accepted_work: u64) -> SessionCounters {
+    SessionCounters { total_hashes, accepted_work, rejected_work: 1, hardware_errors: 0, energy_joules: 50.0 }
+}
+
+#[test]
+fn test_lifetime_adds_session_to_baseline() {
+    let path = temp_path("lifetime");
+    let baseline = LifetimeStats {
+        total_hashes: 1_000,
+        accepted_work: 10,
+        rejected_work: 2,
+        best_share_difficulty: 8.0,
+        uptime_secs: 100.0,
+        energy_joules: 500.0,
+        sessions: 3,
+        ..Default::default()
+    };
+    baseline.save(&path).unwrap();
+
+    let mut journal = StatsJournal::open(journal_config(&path));
+    assert_eq!(journal.baseline(), &baseline);
+
+    // 两个运行时段：10秒和5秒（停止期间不计时）
+    let t0 = Instant::now();
+    journal.mark_started_at(t0);
+    journal.mark_stopped_at(t0 + Duration::from_secs(10));
+    journal.mark_started_at(t0 + Duration::from_secs(60));
+    journal.observe_share(4.0);
+    journal.observe_share(f64::INFINITY);
+
+    let lifetime = journal.lifetime_at(&session(500, 5), t0 + Duration::from_secs(65));
+    assert_eq!(lifetime.total_hashes, 1_500);
+    assert_eq!(lifetime.accepted_work, 15);
+    assert_eq!(lifetime.rejected_work, 3);
+    assert_eq!(lifetime.uptime_secs, 115.0);
+    assert_eq!(lifetime.energy_joules, 550.0);
+    assert_eq!(lifetime.best_share_difficulty, 8.0, "基线中的最佳份额更高");
+    assert_eq!(lifetime.sessions, 4);
+
+    journal.observe_share(12.5);
+    let lifetime = journal.lifetime_at(&session(500, 5), t0 + Duration::from_secs(65));
+    assert_eq!(lifetime.best_share_difficulty, 12.5);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_save_and_reload() {
+    let path = temp_path("reload");
+    let journal = StatsJournal::open(journal_config(&path));
+    assert_eq!(journal.baseline(), &LifetimeStats::default(), "没有日志文件时从0开始");
+
+    let now = Instant::now();
+    let saved = journal.save_at(&session(2_000, 7), now).unwrap().expect("启用时应该写入");
+    assert_eq!(LifetimeStats::load(&path).unwrap(), Some(saved.clone()));
+
+    // 下一次启动以保存的结果为基线
+    let journal = StatsJournal::open(journal_config(&path));
+    let lifetime = journal.lifetime_at(&session(1_000, 3), now);
+    assert_eq!(lifetime.total_hashes, 3_000);
+    assert_eq!(lifetime.accepted_work, 10);
+    assert_eq!(lifetime.sessions, 2);
+
+    let disabled = StatsJournal::open(StatsJournalConfig { enabled: false, ..journal_config(&path) });
+    assert_eq!(disabled.save_at(&session(1, 1), now).unwrap(), None);
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_corrupt_journal_is_kept() {
+    let path = temp_path("corrupt");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "{ not json").unwrap();
+    assert!(LifetimeStats::load(&path).is_err());
+
+    let journal = StatsJournal::open(journal_config(&path));
+    assert_eq!(journal.baseline(), &LifetimeStats::default());
+    let backup = path.with_extension("corrupt");
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ not json", "损坏的日志应该保留");
+    assert!(!path.exists());
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_unreadable_journal_is_not_renamed_or_overwritten() {
+    // 日志路径是目录：读取失败但不是格式错误
+    let path = temp_path("unreadable");
+    std::fs::create_dir_all(&path).unwrap();
+    assert!(LifetimeStats::load(&path).is_err());
+
+    let journal = StatsJournal::open(journal_config(&path));
+    assert_eq!(journal.baseline(), &LifetimeStats::default());
+    assert!(!path.with_extension("corrupt").exists(), "只有无法解析的日志才改名");
+    assert!(!journal.is_enabled(), "无法读取的日志不应该被覆盖");
+    assert_eq!(journal.save_at(&session(1, 1), Instant::now()).unwrap(), None);
+    assert!(path.is_dir());
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[test]
+fn test_journal_config_validation() {
+    let config = CpuCoreConfig::from_toml_str("[stats_journal]\nenabled = true\npath = \"/tmp/stats.json\"").unwrap();
+    assert!(config.stats_journal.enabled);
+    assert_eq!(config.stats_journal.interval_ms, 60_000);
+
+    let err = CpuCoreConfig::from_toml_str("[stats_journal]\nenabled = true").unwrap_err();
+    assert!(err.to_string().contains("stats_journal.path"), "{}", err);
+    let err = CpuCoreConfig::from_toml_str("[stats_journal]\ninterval_ms = 0").unwrap_err();
+    assert!(err.to_string().contains("stats_journal.interval_ms"), "{}", err);
+}
+
+/// 运行核心一段时间（最低难度的工作，每个nonce都是份额）
+async fn run_core(cpu_config: &CpuCoreConfig) -> (SoftwareMiningCore, LifetimeStats) {
+    let core = SoftwareMiningCore::new("统计日志测试核心".to_string());
+    run_existing_core(core, cpu_config).await
+}
+
+/// 初始化并运行一段时间已有的核心（可以是已经运行并停止过的核心）
+async fn run_existing_core(mut core: SoftwareMiningCore, cpu_config: &CpuCoreConfig) -> (SoftwareMiningCore, LifetimeStats) {
+    core.initialize(cpu_config.to_core_config("journal-test")).await.expect("核心初始化应该成功");
+    core.start().await.expect("核心启动应该成功");
+    let work = Work::new("journal".to_string(), [0xff; 32], [0u8; 80], 1.0);
+    core.submit_work(Arc::new(work)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    core.stop().await.expect("核心停止应该成功");
+    let lifetime = core.lifetime_stats().await.expect("启用统计日志时应该有累计统计");
+    (core, lifetime)
+}
+
+#[tokio::test]
+async fn test_core_persists_lifetime_totals() {
+    let path = temp_path("core");
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    cpu_config.temperature.governor.enabled = false;
+    cpu_config.stats_journal = StatsJournalConfig { interval_ms: 50, ..journal_config(&path) };
+
+    let (_, first) = run_core(&cpu_config).await;
+    let saved = LifetimeStats::load(&path).unwrap().expect("停止时应该写入日志");
+    assert_eq!(saved.total_hashes, first.total_hashes);
+    assert!(saved.total_hashes > 0);
+    assert!(saved.accepted_work > 0);
+    assert!(saved.best_share_difficulty > 0.0);
+    assert!(saved.uptime_secs > 0.2 && saved.uptime_secs < 5.0, "{}", saved.uptime_secs);
+    assert_eq!(saved.sessions, 1);
+
+    // 重启后从保存的总计继续累加
+    let (core, second) = run_core(&cpu_config).await;
+    assert_eq!(second.sessions, 2);
+    assert!(second.total_hashes > saved.total_hashes);
+    assert!(second.accepted_work > saved.accepted_work);
+    assert!(second.uptime_secs > saved.uptime_secs);
+    assert!(second.best_share_difficulty >= saved.best_share_difficulty);
+
+    core.save_stats_journal().await.unwrap();
+    assert_eq!(LifetimeStats::load(&path).unwrap().map(|stats| stats.total_hashes), Some(second.total_hashes));
+
+    // 同一个核心停止后重新初始化：只累加新会话的计数，不重复计算上一次会话
+    let (core, third) = run_existing_core(core, &cpu_config).await;
+    let session = core.get_stats().await.expect("获取统计应该成功");
+    assert_eq!(third.sessions, 3);
+    assert_eq!(third.accepted_work, second.accepted_work + session.accepted_work);
+    assert_eq!(third.rejected_work, second.rejected_work + session.rejected_work);
+    assert_eq!(core.device_count().await.unwrap(), 1, "重新初始化应该替换旧设备");
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+}
+
+#[tokio::test]
+async fn test_lifetime_stats_disabled_by_default() {
+    let mut core = SoftwareMiningCore::new("统计日志测试核心".to_string());
+    let mut cpu_config = CpuCoreConfig::default();
+    cpu_config.device_count = 1;
+    core.initialize(cpu_config.to_core_config("journal-test")).await.unwrap();
+    assert_eq!(core.lifetime_stats().await, None);
+    assert_eq!(core.save_stats_journal().await.unwrap(), None);
+}
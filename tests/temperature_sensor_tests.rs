@@ -0,0 +1,167 @@
+//! 温度传感器发现测试
+//!
+//! 使用 `tests/fixtures/sysfs` 下 Intel coretemp、AMD k10temp、zenpower 和仅热区的
+//! sysfs fixture 验证传感器枚举、封装温度优先级和单核温度
+
+use cgminer_cpu_btc_core::temperature::{
+    discover_sensors, CoreTemperature, SensorKind, SensorSource, TemperatureConfig, TemperatureManager,
+};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sysfs").join(name)
+}
+
+fn manager(name: &str) -> TemperatureManager {
+    TemperatureManager::new(TemperatureConfig {
+        sysfs_root: fixture(name).to_string_lossy().to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_intel_prefers_coretemp_package() {
+    let sensors = discover_sensors(fixture("intel"));
+
+    // acpitz hwmon 不是CPU驱动，不参与枚举；两个热区都保留
+    assert_eq!(sensors.iter().filter(|s| s.source == SensorSource::Hwmon).count(), 5);
+    assert_eq!(sensors.iter().filter(|s| s.source == SensorSource::ThermalZone).count(), 2);
+
+    let package = &sensors[0];
+    assert_eq!(package.describe(), "coretemp/Package id 0");
+    assert_eq!(package.kind, SensorKind::Package);
+    assert_eq!(package.package_id, Some(0));
+    assert_eq!(sensors.last().unwrap().name, "acpitz");
+}
+
+#[test]
+fn test_intel_per_core_temperatures() {
+    let manager = manager("intel");
+    if !manager.has_temperature_monitoring() {
+        return; // 非Linux平台不读取sysfs
+    }
+
+    assert_eq!(manager.read_temperature().unwrap(), 61.0);
+    assert_eq!(
+        manager.read_core_temperatures(),
+        vec![
+            CoreTemperature { package_id: 0, core_id: 0, celsius: 58.0 },
+            CoreTemperature { package_id: 0, core_id: 1, celsius: 63.0 },
+            CoreTemperature { package_id: 0, core_id: 2, celsius: 55.0 },
+            CoreTemperature { package_id: 0, core_id: 3, celsius: 60.0 },
+        ]
+    );
+}
+
+#[test]
+fn test_amd_k10temp_tctl_over_ccd_and_acpi() {
+    let sensors = discover_sensors(fixture("amd"));
+    assert_eq!(sensors[0].describe(), "k10temp/Tctl");
+    assert_eq!(sensors[1].describe(), "k10temp/Tccd1");
+    assert_eq!(sensors[1].kind, SensorKind::Cpu);
+    assert_eq!(sensors[2].name, "acpitz");
+
+    let manager = manager("amd");
+    if manager.has_temperature_monitoring() {
+        assert_eq!(manager.read_temperature().unwrap(), 72.5);
+        assert!(manager.read_core_temperatures().is_empty());
+    }
+}
+
+#[test]
+fn test_zenpower_prefers_tdie() {
+    let sensors = discover_sensors(fixture("zenpower"));
+    assert_eq!(sensors[0].describe(), "zenpower/Tdie");
+    assert_eq!(sensors[1].describe(), "zenpower/Tctl");
+}
+
+#[test]
+fn test_cpu_thermal_zone_preferred_over_zone0() {
+    let sensors = discover_sensors(fixture("thermal_only"));
+    assert_eq!(sensors[0].name, "cpu-thermal");
+    assert_eq!(sensors[0].kind, SensorKind::Cpu);
+
+    let manager = manager("thermal_only");
+    if manager.has_temperature_monitoring() {
+        assert_eq!(manager.read_temperature().unwrap(), 52.0);
+    }
+}
+
+#[test]
+fn test_missing_sysfs_has_no_sensors() {
+    let manager = manager("does-not-exist");
+    assert!(manager.sensors().is_empty());
+    assert!(manager.read_temperature().is_err() || cfg!(target_os = "macos"));
+}
+
+mod per_core {
+    use super::fixture;
+    use cgminer_core::{DeviceInfo, MiningDevice};
+    use cgminer_cpu_btc_core::config::default_device_config;
+    use cgminer_cpu_btc_core::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy};
+    use cgminer_cpu_btc_core::temperature::{TemperatureConfig, TemperatureManager, TemperatureScope};
+    use cgminer_cpu_btc_core::topology::CpuTopology;
+    use cgminer_cpu_btc_core::SoftwareDevice;
+    use std::sync::{Arc, RwLock};
+
+    fn intel_config() -> TemperatureConfig {
+        TemperatureConfig {
+            sysfs_root: fixture("intel").to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 创建设备并读取一次温度，返回 (温度, 来源)
+    async fn device_temperature(
+        device_id: u32,
+        cpu_affinity: Option<Arc<RwLock<CpuAffinityManager>>>,
+    ) -> (Option<f32>, TemperatureScope) {
+        let info = DeviceInfo::new(device_id, format!("Software Device {}", device_id), "software".to_string(), 0);
+        let config = default_device_config(device_id - 1000);
+        let mut device = match cpu_affinity {
+            Some(manager) => SoftwareDevice::new_with_cpu_affinity(info, config.clone(), 0.0, 0.0, 1000, manager).await,
+            None => SoftwareDevice::new(info, config.clone(), 0.0, 0.0, 1000).await,
+        }
+        .unwrap();
+        device.set_temperature_config(intel_config());
+        device.initialize(config).await.unwrap();
+
+        let stats = device.get_stats().await.unwrap();
+        (stats.temperature.map(|t| t.celsius), device.temperature_scope())
+    }
+
+    #[tokio::test]
+    async fn test_device_reports_temperature_of_pinned_core() {
+        if !TemperatureManager::new(intel_config()).has_temperature_monitoring() {
+            return; // 非Linux平台不读取sysfs
+        }
+
+        // smt fixture: CPU 0/2 属于核心0，CPU 1/3 属于核心1
+        let topology = CpuTopology::from_sysfs(fixture("smt").join("devices/system/cpu")).unwrap();
+        let mut manager = CpuAffinityManager::with_topology(true, CpuAffinityStrategy::RoundRobin, topology);
+        manager.assign_cpu_core(1000);
+        manager.assign_cpu_core(1001);
+        let manager = Arc::new(RwLock::new(manager));
+
+        let cpu_of = |device_id| manager.read().unwrap().get_device_core(device_id).unwrap().id;
+        let expected = |cpu: usize| if cpu % 2 == 0 { 58.0 } else { 63.0 };
+
+        for device_id in [1000, 1001] {
+            let cpu = cpu_of(device_id);
+            let (temperature, scope) = device_temperature(device_id, Some(manager.clone())).await;
+            assert_eq!(temperature, Some(expected(cpu)));
+            assert_eq!(scope, TemperatureScope::Core { cpu });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unpinned_device_falls_back_to_package() {
+        if !TemperatureManager::new(intel_config()).has_temperature_monitoring() {
+            return;
+        }
+
+        let (temperature, scope) = device_temperature(1000, None).await;
+        assert_eq!(temperature, Some(61.0));
+        assert_eq!(scope, TemperatureScope::Package);
+    }
+}
@@ -1,89 +1,211 @@
 //! 温度管理功能测试
+//!
+//! 通过 `TemperatureSource` 注入温度，验证配置默认值、脚本和回放来源、
+//! 外部命令来源以及设备的温度上报和健康检查
 
-#[cfg(test)]
-mod tests {
-    use super::super::temperature::{
-        TemperatureConfig, TemperatureManager, get_platform_temperature_capabilities
-    };
-    use cgminer_core::MiningDevice;
-
-    #[test]
-    fn test_temperature_config_default() {
-        let config = TemperatureConfig::default();
-        assert_eq!(config.enable_real_monitoring, true);
-        assert_eq!(config.update_interval, 5);
-        assert_eq!(config.warning_threshold, 80.0);
-        assert_eq!(config.critical_threshold, 90.0);
-        assert_eq!(config.simulated_base_temp, 35.0);
+use cgminer_core::{DeviceInfo, MiningDevice};
+use cgminer_cpu_btc_core::config::default_device_config;
+use cgminer_cpu_btc_core::temperature::{
+    parse_temperature_output, CommandTemperatureSource, ScriptedTemperatureSource, TemperatureConfig,
+    TemperatureError, TemperatureManager, TemperatureScope, TemperatureSource, TemperatureStatus,
+};
+use cgminer_cpu_btc_core::SoftwareDevice;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/temperature").join(name)
+}
+
+#[test]
+fn test_temperature_config_default() {
+    let config = TemperatureConfig::default();
+    assert!(config.enable_real_monitoring);
+    assert_eq!(config.warning_threshold, 75.0);
+    assert_eq!(config.critical_threshold, 85.0);
+    assert_eq!(config.sysfs_root, "/sys");
+    assert_eq!(config.command, None);
+}
+
+#[test]
+fn test_disabled_monitoring_has_no_source() {
+    let manager = TemperatureManager::new(TemperatureConfig {
+        enable_real_monitoring: false,
+        ..Default::default()
+    });
+    assert!(!manager.has_temperature_monitoring());
+    assert!(matches!(manager.read_temperature(), Err(TemperatureError::NotSupported)));
+    assert_eq!(manager.provider_info(), "不支持温度监控");
+}
+
+#[tokio::test]
+async fn test_scripted_source_repeats_last_value() {
+    let manager = TemperatureManager::scripted(TemperatureConfig::default(), vec![60.0, 78.0, 86.0]);
+    let mut readings = Vec::new();
+    for _ in 0..5 {
+        manager.refresh().await;
+        readings.push(manager.read_temperature().unwrap());
     }
+    assert_eq!(readings, vec![60.0, 78.0, 86.0, 86.0, 86.0]);
 
-    #[test]
-    fn test_temperature_manager_creation() {
-        let config = TemperatureConfig::default();
-        let manager = TemperatureManager::new(config);
+    let manager = TemperatureManager::scripted(TemperatureConfig::default(), vec![60.0, 78.0, 86.0]);
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        manager.refresh().await;
+        statuses.push(manager.check_temperature_status().unwrap());
+    }
+    assert_eq!(statuses, vec![TemperatureStatus::Normal, TemperatureStatus::Warning, TemperatureStatus::Critical]);
+}
 
-        // 检查是否有温度监控功能
-        println!("温度监控功能: {}", manager.has_temperature_monitoring());
-        println!("提供者信息: {}", manager.provider_info());
+#[tokio::test]
+async fn test_scripted_source_advances_once_per_refresh() {
+    let manager = TemperatureManager::scripted(TemperatureConfig::default(), vec![60.0, 78.0, 86.0]);
+    // 首次刷新前返回第一个温度，且读取不推进脚本
+    assert_eq!(manager.read_temperature().unwrap(), 60.0);
+    assert_eq!(manager.read_temperature().unwrap(), 60.0);
 
-        // 尝试读取温度
-        match manager.read_temperature() {
-            Ok(temp) => println!("读取到温度: {:.1}°C", temp),
-            Err(e) => println!("温度读取失败: {}", e),
-        }
+    // 共用管理器的多个设备在同一周期读取到同一个温度
+    for expected in [60.0, 78.0, 86.0] {
+        manager.refresh().await;
+        let readings: Vec<f32> = (0..4).map(|_| manager.clone().read_temperature().unwrap()).collect();
+        assert_eq!(readings, vec![expected; 4]);
+    }
+}
+
+#[test]
+fn test_empty_script_fails() {
+    let source = ScriptedTemperatureSource::new(Vec::new());
+    assert!(matches!(source.read_temperature(), Err(TemperatureError::ReadFailed(_))));
+}
+
+#[test]
+fn test_replay_file() {
+    let source = ScriptedTemperatureSource::from_file(fixture("replay.txt")).unwrap();
+    let readings: Vec<f32> = (0..6)
+        .map(|_| {
+            source.advance();
+            source.read_temperature().unwrap()
+        })
+        .collect();
+    assert_eq!(readings, vec![58.0, 66.5, 74.0, 81.5, 72.0, 72.0]);
+
+    assert!(ScriptedTemperatureSource::from_file(fixture("missing.txt")).is_err());
+}
+
+#[test]
+fn test_scripted_core_temperatures() {
+    let source = ScriptedTemperatureSource::new([61.0])
+        .with_core(0, 1, 63.0)
+        .with_core(0, 0, 58.0);
+    let manager = TemperatureManager::with_source(TemperatureConfig::default(), source);
+
+    assert_eq!(manager.read_core_temperature(0, 1), Some(63.0));
+    assert_eq!(manager.read_core_temperature(1, 0), None);
+    let cores: Vec<(u32, f32)> = manager.read_core_temperatures().iter().map(|t| (t.core_id, t.celsius)).collect();
+    assert_eq!(cores, vec![(0, 58.0), (1, 63.0)]);
+    assert!(manager.sensors().is_empty());
+}
+
+#[test]
+fn test_parse_temperature_output() {
+    assert_eq!(parse_temperature_output("61.5\n"), Some(61.5));
+    assert_eq!(parse_temperature_output("61500"), Some(61.5));
+    assert_eq!(parse_temperature_output("CPU: +61.5°C"), Some(61.5));
+    // 标签中的编号不是温度
+    assert_eq!(parse_temperature_output("Core 0: 45°C"), Some(45.0));
+    assert_eq!(parse_temperature_output("Package id 0: +61.0°C\nCore 1: +58.0°C\n"), Some(61.0));
+    assert_eq!(parse_temperature_output("no reading"), None);
+    assert_eq!(parse_temperature_output(""), None);
+}
+
+#[cfg(unix)]
+mod command {
+    use super::*;
+
+    fn command(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
     }
 
-    #[test]
-    fn test_platform_capabilities() {
-        let capabilities = get_platform_temperature_capabilities();
-        println!("平台温度监控能力: {}", capabilities);
+    #[tokio::test]
+    async fn test_command_source_reads_stdout() {
+        let source = CommandTemperatureSource::new(&command(&["echo", "61500"]), Duration::from_secs(2)).unwrap();
+        assert!(source.read_temperature().is_err(), "运行命令之前没有温度");
+        assert_eq!(source.sample().await.unwrap(), 61.5);
+        assert_eq!(source.read_temperature().unwrap(), 61.5);
+        assert_eq!(source.describe(), "命令 echo");
+    }
 
-        assert!(!capabilities.platform_name.is_empty());
-        assert!(!capabilities.arch.is_empty());
-        assert!(!capabilities.provider_type.is_empty());
+    #[tokio::test]
+    async fn test_command_from_config() {
+        let manager = TemperatureManager::new(TemperatureConfig {
+            command: Some(command(&["echo", "CPU:", "+58.0°C"])),
+            ..Default::default()
+        });
+        assert!(manager.has_temperature_monitoring());
+        assert!(manager.needs_refresh());
+        manager.refresh().await;
+        assert_eq!(manager.read_temperature().unwrap(), 58.0);
     }
 
     #[tokio::test]
-    async fn test_device_temperature_integration() {
-        use crate::device::SoftwareDevice;
-        use cgminer_core::{DeviceInfo, DeviceConfig};
-
-        let device_info = DeviceInfo::new(
-            1,
-            "Test Device".to_string(),
-            "CPU".to_string(),
-            1, // chain_id as u8
-        );
-
-        let device_config = DeviceConfig {
-            frequency: 600,
-            voltage: 900,
-            fan_speed: Some(50),
+    async fn test_command_runs_once_per_refresh() {
+        let dir = std::env::temp_dir().join(format!("temperature-command-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("runs.log");
+        let script = format!("echo run >> {}; echo 61000", log.display());
+        let manager = TemperatureManager::new(TemperatureConfig {
+            command: Some(command(&["sh", "-c", &script])),
             ..Default::default()
-        };
-
-        let mut device = SoftwareDevice::new(
-            device_info,
-            device_config.clone(),
-            1000000.0, // 1MH/s
-            0.01,      // 1% error rate
-            1000,      // batch size
-        ).await.expect("Failed to create device");
-
-        // 初始化设备
-        device.initialize(device_config).await.expect("Failed to initialize device");
-
-        // 获取统计信息
-        let stats = device.get_stats().await.expect("Failed to get stats");
-
-        if let Some(temp) = stats.temperature {
-            println!("设备温度: {:.1}°C", temp.celsius);
-        } else {
-            println!("设备不支持温度监控");
+        });
+
+        // 两个设备共享同一个管理器，多次读取只返回最近一次的结果
+        let devices = [manager.clone(), manager.clone()];
+        manager.refresh().await;
+        for device in &devices {
+            for _ in 0..3 {
+                assert_eq!(device.read_temperature().unwrap(), 61.0);
+            }
         }
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_command_failures() {
+        assert!(CommandTemperatureSource::new(&[], Duration::from_secs(2)).is_err());
+
+        let failing = CommandTemperatureSource::new(&command(&["false"]), Duration::from_secs(2)).unwrap();
+        assert!(failing.sample().await.is_err());
+        assert!(failing.read_temperature().is_err(), "失败的结果也应该被保存");
+
+        let missing = CommandTemperatureSource::new(&command(&["/nonexistent/cpu-temp"]), Duration::from_secs(2)).unwrap();
+        assert!(missing.sample().await.is_err());
 
-        // 检查健康状态
-        let health = device.health_check().await.expect("Failed to check health");
-        println!("设备健康状态: {}", if health { "正常" } else { "异常" });
+        let slow = CommandTemperatureSource::new(&command(&["sleep", "5"]), Duration::from_millis(100)).unwrap();
+        let started = std::time::Instant::now();
+        let error = slow.sample().await.unwrap_err();
+        assert!(error.to_string().contains("超时"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
     }
 }
+
+#[tokio::test]
+async fn test_device_temperature_integration() {
+    let info = DeviceInfo::new(1000, "Software Device 0".to_string(), "software".to_string(), 0);
+    let config = default_device_config(0);
+    let mut device = SoftwareDevice::new(info, config.clone(), 1_000_000.0, 0.01, 1000).await.unwrap();
+    device.set_temperature_source(ScriptedTemperatureSource::new([62.0, 90.0]));
+    device.initialize(config).await.unwrap();
+
+    // 初始化时读取一次
+    let stats = device.get_stats().await.unwrap();
+    assert_eq!(stats.temperature.map(|t| t.celsius), Some(62.0));
+    assert_eq!(device.temperature_scope(), TemperatureScope::Package);
+    assert!(device.health_check().await.unwrap());
+
+    // 下一次读取 90°C，超过设备温度限制
+    device.thermal_tick(Duration::from_secs(1)).unwrap();
+    let stats = device.get_stats().await.unwrap();
+    assert_eq!(stats.temperature.map(|t| t.celsius), Some(90.0));
+    assert!(!device.health_check().await.unwrap());
+}
@@ -0,0 +1,94 @@
+//! 闭环温度调节测试
+//!
+//! 向 `TemperatureManager` 注入脚本温度，验证设备的降频、暂停、滞回恢复，
+//! 以及健康检查使用 `DeviceConfig::temperature_limit` 和温度配置的阈值
+
+use cgminer_core::{DeviceInfo, MiningDevice};
+use cgminer_cpu_btc_core::config::default_device_config;
+use cgminer_cpu_btc_core::events::MiningEventKind;
+use cgminer_cpu_btc_core::temperature::{TemperatureConfig, TemperatureManager};
+use cgminer_cpu_btc_core::thermal::ThermalState;
+use cgminer_cpu_btc_core::{EventBus, SoftwareDevice};
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_secs(1);
+
+async fn device_with_script(temperature_limit: f32, temperatures: Vec<f32>) -> (SoftwareDevice, TemperatureManager) {
+    let info = DeviceInfo::new(1000, "Software Device 0".to_string(), "software".to_string(), 0);
+    let mut config = default_device_config(0);
+    config.temperature_limit = temperature_limit;
+
+    let mut device = SoftwareDevice::new(info, config.clone(), 0.0, 0.0, 1000).await.unwrap();
+    device.initialize(config).await.unwrap();
+    // 脚本每个刷新周期推进一次，与核心的温度调节任务相同
+    let manager = TemperatureManager::scripted(TemperatureConfig::default(), temperatures);
+    device.set_temperature_manager(manager.clone());
+    (device, manager)
+}
+
+#[tokio::test]
+async fn test_throttle_pause_and_resume() {
+    // 默认阈值: 警告75°C，危险85°C，滞回5°C
+    let (mut device, manager) = device_with_script(100.0, vec![60.0, 78.0, 79.0, 86.0, 82.0, 79.0, 72.0, 69.0]).await;
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+    device.set_event_bus(bus);
+
+    let mut states = Vec::new();
+    let mut duty_cycles = Vec::new();
+    for _ in 0..8 {
+        manager.refresh().await;
+        states.push(device.thermal_tick(TICK).unwrap().unwrap());
+        duty_cycles.push(device.effective_duty_cycle());
+    }
+
+    use ThermalState::*;
+    assert_eq!(states, vec![Normal, Throttled, Throttled, Paused, Paused, Throttled, Throttled, Normal]);
+    assert_eq!(duty_cycles[0], 1.0);
+    assert!(duty_cycles[1] < 1.0);
+    assert!(duty_cycles[2] < duty_cycles[1], "温度持续高于目标时占空比应该继续降低");
+    assert_eq!(duty_cycles[3], 0.0);
+    assert_eq!(duty_cycles[4], 0.0);
+    assert!(duty_cycles[5] > 0.0 && duty_cycles[5] < 1.0);
+    assert_eq!(duty_cycles[7], 1.0);
+
+    // 每次状态变化发布一个事件
+    let mut transitions = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let MiningEventKind::ThermalThrottle { previous, current, .. } = event.kind {
+            transitions.push((previous, current));
+        }
+    }
+    assert_eq!(transitions, vec![(Normal, Throttled), (Throttled, Paused), (Paused, Throttled), (Throttled, Normal)]);
+}
+
+#[tokio::test]
+async fn test_device_temperature_limit_lowers_critical_threshold() {
+    // 设备温度限制80°C低于温度配置的危险阈值85°C
+    let (device, _) = device_with_script(80.0, vec![81.0]).await;
+    assert_eq!(device.thermal_tick(TICK).unwrap(), Some(ThermalState::Paused));
+    assert!(!device.health_check().await.unwrap(), "超过设备温度限制时健康检查应该失败");
+}
+
+#[tokio::test]
+async fn test_health_check_uses_configured_thresholds() {
+    // 旧实现固定在90°C；现在88°C低于设备限制但高于危险阈值85°C
+    let (device, _) = device_with_script(100.0, vec![88.0]).await;
+    device.thermal_tick(TICK).unwrap();
+    assert!(!device.health_check().await.unwrap());
+
+    let (device, _) = device_with_script(100.0, vec![70.0]).await;
+    device.thermal_tick(TICK).unwrap();
+    assert!(device.health_check().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_governor_can_be_disabled() {
+    let mut config = TemperatureConfig::default();
+    config.governor.enabled = false;
+
+    let (mut device, _) = device_with_script(100.0, vec![]).await;
+    device.set_temperature_manager(TemperatureManager::scripted(config, vec![95.0]));
+    assert_eq!(device.thermal_tick(TICK).unwrap(), None);
+    assert_eq!(device.effective_duty_cycle(), 1.0);
+}
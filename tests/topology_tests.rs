@@ -0,0 +1,83 @@
+//! CPU拓扑测试
+//!
+//! 使用 `tests/fixtures/sysfs` 下的 sysfs fixture 验证拓扑解析，以及绑定策略对拓扑的使用
+
+use cgminer_cpu_btc_core::cpu_affinity::{CpuAffinityManager, CpuAffinityStrategy};
+use cgminer_cpu_btc_core::topology::{CoreKind, CpuTopology};
+use std::path::PathBuf;
+
+/// fixture 中的 sysfs CPU 目录
+fn fixture_cpu_root(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sysfs")
+        .join(name)
+        .join("devices/system/cpu")
+}
+
+fn load(name: &str) -> CpuTopology {
+    CpuTopology::from_sysfs(fixture_cpu_root(name)).expect("fixture 拓扑应该可以读取")
+}
+
+/// 依次为设备分配核心，返回分配到的CPU编号
+fn assign(strategy: CpuAffinityStrategy, topology: CpuTopology, devices: u32) -> Vec<usize> {
+    let mut manager = CpuAffinityManager::with_topology(true, strategy, topology);
+    (0..devices)
+        .map(|device_id| manager.assign_cpu_core(1000 + device_id).expect("应该分配到核心").id)
+        .collect()
+}
+
+#[test]
+fn test_hybrid_topology() {
+    let topology = load("hybrid");
+
+    assert_eq!(topology.logical_cpu_count(), 6);
+    assert_eq!(topology.package_count(), 1);
+    assert!(topology.is_hybrid());
+    assert_eq!(topology.physical_cores(), vec![0, 2, 4, 5]);
+    assert_eq!(topology.performance_cpus(), vec![0, 1, 2, 3]);
+    assert_eq!(topology.cpu(4).unwrap().kind, CoreKind::Efficiency);
+    assert_eq!(topology.preferred_order(), vec![0, 2, 4, 5, 1, 3]);
+
+    // 缓存共享: L2 每个P核独享，两个E核共享；L3 全部共享
+    assert_eq!(topology.cache_domains(2), vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+    assert_eq!(topology.cache_domains(3), vec![vec![0, 1, 2, 3, 4, 5]]);
+    let l3 = topology.cpu(0).unwrap().caches.iter().find(|cache| cache.level == 3).unwrap();
+    assert_eq!(l3.size_kb, 30 * 1024);
+}
+
+#[test]
+fn test_interleaved_smt_topology() {
+    let topology = load("smt");
+
+    // cpu4 离线，不计入
+    assert_eq!(topology.logical_cpu_count(), 4);
+    // 同一物理核心的超线程编号为 (0,2) 和 (1,3)，不是相邻编号
+    assert_eq!(topology.physical_cores(), vec![0, 1]);
+    // 小幅频率差异不应被识别为大小核
+    assert!(!topology.is_hybrid());
+    assert_eq!(topology.cpu(1).unwrap().kind, CoreKind::Uniform);
+}
+
+#[test]
+fn test_physical_cores_only_uses_topology() {
+    // 偶数编号假设会把 cpu0 和 cpu2 (同一物理核心) 当作两个物理核心
+    let cores = assign(CpuAffinityStrategy::PhysicalCoresOnly, load("smt"), 2);
+    assert_eq!(cores, vec![0, 1]);
+}
+
+#[test]
+fn test_performance_first_avoids_efficiency_cores() {
+    let cores = assign(CpuAffinityStrategy::PerformanceFirst, load("hybrid"), 4);
+    assert_eq!(cores, vec![0, 2, 1, 3]);
+}
+
+#[test]
+fn test_intelligent_spreads_before_using_siblings() {
+    let cores = assign(CpuAffinityStrategy::Intelligent, load("hybrid"), 6);
+    assert_eq!(cores, vec![0, 2, 4, 5, 1, 3]);
+}
+
+#[test]
+fn test_missing_sysfs_root() {
+    assert!(CpuTopology::from_sysfs(fixture_cpu_root("does-not-exist")).is_err());
+}